@@ -5,39 +5,11 @@ extern crate font;
 use std::fs::File;
 use std::io::Read;
 
-use font::{Font, Name};
+use clap::App;
+use font::{EmbeddingPermissions, EmbeddingUsage, Font, Name};
 
 fn main() {
-    let matches = clap_app!(about =>
-        (about: "Dumps strings stored in font files.")
-        (@arg COPYRIGHT: -C --copyright "Print Copyright")
-        (@arg FONT_FAMILY: -F --family "Print Font Family Name")
-        (@arg FONT_SUBFAMILY: -f --subfamily "Print Font Subfamily Name")
-        (@arg UNIQUE_ID: -U --id "Print Unique ID")
-        (@arg FULL_NAME: -N --name "Print Full Font Name")
-        (@arg VERSION: -V --version "Print Version String")
-        (@arg PS_NAME: -P --psname "Print PostScript Name")
-        (@arg TRADEMARK: -R --trademark "Print Trademark")
-        (@arg MANUFACTURER: -M --manufacturer "Print Manufacturer")
-        (@arg DESIGNER: -A --designer "Print Designer")
-        (@arg DESCRIPTION: -D --description "Print Description")
-        (@arg VENDOR: -v --vendor "Print Vendor URL")
-        (@arg DESIGNER_URL: -u --designerurl "Print Designer URL")
-        (@arg LICENSE: -L --license "Print License")
-        (@arg LICENSE_URL: -i --licenseurl "Print License Info URL")
-        (@arg TYPOGRAPHIC_FAMILY: -T --typefam "Print Typographic Family Name")
-        (@arg TYPOGRAPHIC_SUBFAMILY: -t --typesubfam "Print Typographic Sumfamily Name")
-        (@arg COMPATIBLE_NAME: -n --compatible "Print Compatible Full Name")
-        (@arg SAMPLE_TEXT: -s --sampletext "Print Sample Text")
-        (@arg PS_CID: -c --pscid "Print PostScript CID Find Font Name")
-        (@arg WWS_FAMILY: -W --wwsfam "Print WWS Family Name")
-        (@arg WWS_SUBFAMILY: -w --wwssubfam "Print WWS Subfamily Name")
-        (@arg LIGHT_PALETTE: -l --light "Print Light Background Palette")
-        (@arg DARK_PALETTE: -d --dark "Print Dark Background Palette")
-        (@arg PS_VARS: -p --psvar "Print Variations PostScript Name Prefix")
-        (@arg INPUT: +required "Sets the input file to use")
-    )
-    .get_matches();
+    let matches = app().get_matches();
 
     let filename = matches.value_of("INPUT").unwrap();
     let mut f = File::open(filename).expect("file not found.");
@@ -121,9 +93,19 @@ fn main() {
         selectors.push(Name::VariationsPostScriptNamePrefix);
     }
 
+    let permissions = matches.is_present("PERMISSIONS");
+
     match Font::from_bytes(&data) {
         Ok(parsed) => {
-            if selectors.is_empty() {
+            if permissions {
+                println!(
+                    "{:<25} {}",
+                    "Embedding Permissions",
+                    font_permissions(&parsed)
+                );
+            }
+
+            if selectors.is_empty() && !permissions {
                 let fields = parsed.available_strings();
                 for (f, value) in fields {
                     if value == "" {
@@ -153,6 +135,65 @@ fn main() {
     }
 }
 
+fn app<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(about =>
+        (about: "Dumps strings stored in font files.")
+        (@arg COPYRIGHT: -C --copyright "Print Copyright")
+        (@arg FONT_FAMILY: -F --family "Print Font Family Name")
+        (@arg FONT_SUBFAMILY: -f --subfamily "Print Font Subfamily Name")
+        (@arg UNIQUE_ID: -U --id "Print Unique ID")
+        (@arg FULL_NAME: -N --name "Print Full Font Name")
+        (@arg VERSION: -V --version "Print Version String")
+        (@arg PS_NAME: -P --psname "Print PostScript Name")
+        (@arg TRADEMARK: -R --trademark "Print Trademark")
+        (@arg MANUFACTURER: -M --manufacturer "Print Manufacturer")
+        (@arg DESIGNER: -A --designer "Print Designer")
+        (@arg DESCRIPTION: -D --description "Print Description")
+        (@arg VENDOR: -v --vendor "Print Vendor URL")
+        (@arg DESIGNER_URL: -u --designerurl "Print Designer URL")
+        (@arg LICENSE: -L --license "Print License")
+        (@arg LICENSE_URL: -i --licenseurl "Print License Info URL")
+        (@arg TYPOGRAPHIC_FAMILY: -T --typefam "Print Typographic Family Name")
+        (@arg TYPOGRAPHIC_SUBFAMILY: -t --typesubfam "Print Typographic Sumfamily Name")
+        (@arg COMPATIBLE_NAME: -n --compatible "Print Compatible Full Name")
+        (@arg SAMPLE_TEXT: -s --sampletext "Print Sample Text")
+        (@arg PS_CID: -c --pscid "Print PostScript CID Find Font Name")
+        (@arg WWS_FAMILY: -W --wwsfam "Print WWS Family Name")
+        (@arg WWS_SUBFAMILY: -w --wwssubfam "Print WWS Subfamily Name")
+        (@arg LIGHT_PALETTE: -l --light "Print Light Background Palette")
+        (@arg DARK_PALETTE: -d --dark "Print Dark Background Palette")
+        (@arg PS_VARS: -p --psvar "Print Variations PostScript Name Prefix")
+        (@arg PERMISSIONS: -E --permissions "Print Embedding Permissions")
+        (@arg INPUT: +required "Sets the input file to use")
+    )
+}
+
+fn font_permissions(font: &Font) -> String {
+    match font.embedding_policy() {
+        Some(policy) => describe_permissions(policy),
+        None => "Not specified (no OS/2 table)".to_string(),
+    }
+}
+
+fn describe_permissions(policy: EmbeddingPermissions) -> String {
+    let mut description = match policy.usage {
+        EmbeddingUsage::Installable => "Installable: may be embedded and permanently installed",
+        EmbeddingUsage::Editable => "Editable: may be embedded in documents which can be edited",
+        EmbeddingUsage::PreviewAndPrint => {
+            "Preview & Print: may be embedded to view and print documents only"
+        }
+        EmbeddingUsage::Restricted => "Restricted: must not be embedded without permission",
+    }
+    .to_string();
+    if policy.no_subsetting {
+        description.push_str("; must be embedded whole, without subsetting");
+    }
+    if policy.bitmap_only {
+        description.push_str("; only embedded bitmaps may be embedded");
+    }
+    description
+}
+
 fn field_label(field: Name) -> Option<&'static str> {
     match field {
         Name::CopyrightNotice => Some("Copyright Notice"),
@@ -182,3 +223,69 @@ fn field_label(field: Name) -> Option<&'static str> {
         Name::VariationsPostScriptNamePrefix => Some("Variations PostScript Name Prefix"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permissions_flag() {
+        let matches = app().get_matches_from(vec!["about", "-E", "font.ttf"]);
+        assert!(matches.is_present("PERMISSIONS"));
+
+        let matches = app().get_matches_from(vec!["about", "--permissions", "font.ttf"]);
+        assert!(matches.is_present("PERMISSIONS"));
+        assert_eq!(matches.value_of("INPUT"), Some("font.ttf"));
+    }
+
+    #[test]
+    fn describe_each_usage() {
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0000)),
+            "Installable: may be embedded and permanently installed"
+        );
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0002)),
+            "Restricted: must not be embedded without permission"
+        );
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0004)),
+            "Preview & Print: may be embedded to view and print documents only"
+        );
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0008)),
+            "Editable: may be embedded in documents which can be edited"
+        );
+    }
+
+    #[test]
+    fn describe_restriction_bits() {
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0100)),
+            "Installable: may be embedded and permanently installed; \
+             must be embedded whole, without subsetting"
+        );
+        assert_eq!(
+            describe_permissions(EmbeddingPermissions::from_fs_type(0x0308)),
+            "Editable: may be embedded in documents which can be edited; \
+             must be embedded whole, without subsetting; only embedded bitmaps may be embedded"
+        );
+    }
+
+    #[test]
+    fn describe_font() {
+        let data = include_bytes!("../../font/samples/empty.ttf");
+        let font = Font::from_bytes(data).unwrap();
+        assert_eq!(
+            font_permissions(&font),
+            "Installable: may be embedded and permanently installed"
+        );
+
+        // A font with no tables at all.
+        let data = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let font = Font::from_bytes(&data).unwrap();
+        assert_eq!(font_permissions(&font), "Not specified (no OS/2 table)");
+    }
+}
@@ -0,0 +1,117 @@
+use font::{Font, FontParseErr};
+use sfnt::CollectionHeader;
+
+/// A font collection, such as a `.ttc` or `.otc` file, which holds several fonts that can share
+/// tables.
+#[derive(Debug)]
+pub struct FontCollection<'a> {
+    pub header: CollectionHeader,
+    content: &'a [u8],
+}
+
+impl<'a> FontCollection<'a> {
+    pub fn from_bytes(content: &'a [u8]) -> Result<Self, FontParseErr> {
+        Ok(Self {
+            header: CollectionHeader::deserialize(content)?,
+            content,
+        })
+    }
+
+    /// The number of fonts in the collection.
+    pub fn len(&self) -> usize {
+        self.header.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.offsets.is_empty()
+    }
+
+    /// Parse the font at `index`, or return `None` if the collection has no such font.
+    pub fn font(&self, index: usize) -> Option<Result<Font<'a>, FontParseErr>> {
+        let offset = *self.header.offsets.get(index)?;
+        Some(Font::from_bytes_at(self.content, offset))
+    }
+
+    /// Parse each font in the collection, in order.
+    pub fn fonts(&self) -> Box<dyn Iterator<Item = Result<Font<'a>, FontParseErr>> + '_> {
+        let content = self.content;
+        Box::new(
+            self.header
+                .offsets
+                .iter()
+                .map(move |&offset| Font::from_bytes_at(content, offset)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetype::FileType;
+    use font::ErrorReason;
+    use opentype::tables::test_data::SAMPLE_FONT;
+    use opentype::types::{DataType, Offset32};
+
+    /// Build a collection of two fonts whose table directories both point at the sample font's
+    /// tables.
+    fn sample_collection() -> Vec<u8> {
+        let num_tables = SAMPLE_FONT[5] as usize;
+        let directory_len = 12 + num_tables * 16;
+        let base = 20 + 2 * directory_len;
+
+        let mut directory = SAMPLE_FONT[..directory_len].to_vec();
+        for n in 0..num_tables {
+            let field = 12 + n * 16 + 8;
+            let offset = Offset32::extract(&directory, field).unwrap() + base;
+            directory[field..field + 4].clone_from_slice(&(offset as u32).to_be_bytes());
+        }
+
+        let mut content = vec![
+            0x74, 0x74, 0x63, 0x66, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+        ];
+        content.extend_from_slice(&20u32.to_be_bytes());
+        content.extend_from_slice(&((20 + directory_len) as u32).to_be_bytes());
+        content.extend_from_slice(&directory);
+        content.extend_from_slice(&directory);
+        content.extend_from_slice(SAMPLE_FONT);
+        content
+    }
+
+    #[test]
+    fn open_members() {
+        let content = sample_collection();
+        let collection = FontCollection::from_bytes(&content).unwrap();
+        assert_eq!(collection.len(), 2);
+
+        let fonts: Vec<Font> = collection.fonts().map(Result::unwrap).collect();
+        assert_eq!(fonts.len(), 2);
+        for font in &fonts {
+            assert_eq!(font.file_type, FileType::OpenTypeWithTrueTypeOutlines);
+            assert_eq!(font.num_glyphs(), Some(3));
+        }
+        assert!(collection.font(2).is_none());
+    }
+
+    #[test]
+    fn from_bytes_opens_first_member() {
+        let content = sample_collection();
+        let font = Font::from_bytes(&content).unwrap();
+        assert_eq!(font.file_type, FileType::OpenTypeWithTrueTypeOutlines);
+        assert_eq!(font.num_glyphs(), Some(3));
+    }
+
+    #[test]
+    fn member_offset_out_of_bounds() {
+        let mut content = sample_collection();
+        content[12..16].clone_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF]);
+        let collection = FontCollection::from_bytes(&content).unwrap();
+        assert_eq!(
+            collection.font(0).unwrap().unwrap_err(),
+            FontParseErr::HeaderError {
+                offset: 0xFFFFFFFF,
+                reason: ErrorReason::BadOffset,
+            }
+        );
+        assert!(collection.font(1).unwrap().is_ok());
+    }
+}
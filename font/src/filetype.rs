@@ -30,8 +30,8 @@ impl FileType {
     }
 
     fn detect_sfnt(content: &[u8]) -> Option<Self> {
-        let sfnt_header =
-            &content[Self::SFNT_HEADER_OFFSET..Self::SFNT_HEADER_OFFSET + Self::SFNT_HEADER_LENGTH];
+        let sfnt_header = content
+            .get(Self::SFNT_HEADER_OFFSET..Self::SFNT_HEADER_OFFSET + Self::SFNT_HEADER_LENGTH)?;
         match sfnt_header {
             // 0x00010000
             &[0x00u8, 0x01, 0x00, 0x00] => Some(FileType::OpenTypeWithTrueTypeOutlines),
@@ -52,8 +52,8 @@ impl FileType {
     }
 
     fn detect_eot(content: &[u8]) -> Option<Self> {
-        let eot_header =
-            &content[Self::EOT_HEADER_OFFSET..Self::EOT_HEADER_OFFSET + Self::EOT_HEADER_LENGTH];
+        let eot_header = content
+            .get(Self::EOT_HEADER_OFFSET..Self::EOT_HEADER_OFFSET + Self::EOT_HEADER_LENGTH)?;
         match eot_header {
             // '0x00010000' (little endian)
             &[0x00, 0x00, 0x01, 0x00] |
@@ -79,4 +79,14 @@ mod tests {
             Some(FileType::OpenTypeWithTrueTypeOutlines)
         );
     }
+
+    #[test]
+    fn detect_short_content() {
+        assert_eq!(FileType::detect(&[]), None);
+        assert_eq!(FileType::detect(&[0x74u8, 0x72, 0x75]), None);
+        assert_eq!(
+            FileType::detect(&[0x74u8, 0x72, 0x75, 0x65]),
+            Some(FileType::AppleCompatibleTrueType)
+        );
+    }
 }
@@ -1,5 +1,15 @@
 use filetype::FileType;
 use opentype;
+use opentype::tables::cmap::{self, VariantLookup};
+use opentype::tables::gdef::{CaretValue, GdefTable, GlyphClass};
+use opentype::tables::glyf::{Outline, ResolveError, SvgOptions};
+use opentype::tables::layout::{Direction, GlyphInfo, GlyphPosition};
+use opentype::tables::name;
+use opentype::tables::os2::{EmbeddingPermissions, Os2Table, Panose, Weight};
+use opentype::tables::post::PostTable;
+use raster::{self, Bitmap};
+use sfnt::{CollectionHeader, Table};
+use std::iter;
 use strings;
 use truetype;
 use Name;
@@ -18,16 +28,45 @@ pub struct Font<'a> {
 }
 
 impl<'a> Font<'a> {
+    /// The most common em size for TrueType outlines, used when a font doesn't say.
+    pub(crate) const DEFAULT_UNITS_PER_EM: u16 = 2048;
+
+    /// Parse a font file. A font collection opens as its first font; use `FontCollection` to
+    /// reach the others.
     pub fn from_bytes(content: &'a [u8]) -> Result<Self, FontParseErr> {
         match FileType::detect(content) {
+            Some(FileType::OpenTypeFontCollection) => {
+                match CollectionHeader::deserialize(content)?.offsets.first() {
+                    Some(&offset) => Self::from_bytes_at(content, offset),
+                    None => Ok(Font {
+                        file_type: FileType::OpenTypeFontCollection,
+                        font: ParsedFont::None,
+                    }),
+                }
+            }
+            Some(_) => Self::from_bytes_at(content, 0),
+            None => Err(FontParseErr::UnrecognizedFormatError),
+        }
+    }
+
+    /// Parse the font whose table directory starts `offset` bytes into `content`. Its tables
+    /// are read from anywhere in `content`, so fonts in a collection share them.
+    pub(crate) fn from_bytes_at(content: &'a [u8], offset: usize) -> Result<Self, FontParseErr> {
+        let header = content.get(offset..).ok_or(FontParseErr::HeaderError {
+            offset,
+            reason: ErrorReason::BadOffset,
+        })?;
+        match FileType::detect(header) {
             Some(file_type) => {
                 let font = match file_type {
                     FileType::OpenTypeWithTrueTypeOutlines | FileType::OpenTypeWithCFFData => {
-                        ParsedFont::OpenType(opentype::OpenTypeFile::deserialize(content))
-                    }
-                    FileType::AppleCompatibleTrueType => {
-                        ParsedFont::TrueType(truetype::TrueTypeFile::deserialize(content))
+                        ParsedFont::OpenType(opentype::OpenTypeFile::deserialize_at(
+                            content, offset,
+                        )?)
                     }
+                    FileType::AppleCompatibleTrueType => ParsedFont::TrueType(
+                        truetype::TrueTypeFile::deserialize_at(content, offset)?,
+                    ),
                     _ => ParsedFont::None,
                 };
 
@@ -37,6 +76,557 @@ impl<'a> Font<'a> {
         }
     }
 
+    /// Errors from tables the font can do without, such as OS/2 or GSUB, which were left out
+    /// so that the rest of the font could be opened.
+    pub fn table_errors(&self) -> &[FontParseErr] {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.table_errors(),
+            _ => &[],
+        }
+    }
+
+    /// Find the index of the glyph which represents a Unicode codepoint.
+    /// Returns `None` if the font has no glyph for it.
+    ///
+    /// Fonts without a Unicode cmap are also searched by glyph name, so that a glyph named
+    /// `Euro` or `uni20AC` is found for U+20AC.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.glyph_index(codepoint),
+            _ => None,
+        }
+    }
+
+    /// Iterate over every Unicode codepoint the font maps to a glyph, along with its glyph.
+    ///
+    /// This is useful for checking coverage of a script or language, regardless of which cmap
+    /// format the font uses.
+    pub fn mappings(&self) -> Box<dyn Iterator<Item = (u32, u16)> + '_> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.mappings(),
+            _ => Box::new(iter::empty()),
+        }
+    }
+
+    /// List every Unicode codepoint which maps to a glyph, in ascending order.
+    ///
+    /// Glyphs the cmap table doesn't reach, such as those in fonts with only a Mac OS Roman cmap,
+    /// fall back to the characters their PostScript name represents. For a ligature named like
+    /// `f_f_i`, that's each of its characters in order.
+    pub fn codepoints_for_glyph(&self, glyph_id: u16) -> Vec<u32> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.codepoints_for_glyph(glyph_id),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Look up a Unicode Variation Sequence, such as an emoji followed by U+FE0F.
+    ///
+    /// `VariantLookup::UseDefault` means the sequence is drawn with the base character's usual
+    /// glyph, as returned by `glyph_index`.
+    pub fn glyph_index_variant(&self, base: u32, selector: u32) -> VariantLookup {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.glyph_index_variant(base, selector),
+            _ => VariantLookup::NotSupported,
+        }
+    }
+
+    /// The outline of a glyph in font units. Compound glyphs, such as accented letters, have their
+    /// components transformed and flattened into a single list of contours.
+    ///
+    /// Use `Outline::replay` to draw the outline with an `OutlineBuilder`.
+    pub fn glyph_outline(&self, glyph_id: u16) -> Result<Outline, ResolveError> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.glyph_outline(glyph_id),
+            _ => Err(ResolveError::MissingGlyph(glyph_id)),
+        }
+    }
+
+    pub fn num_glyphs(&self) -> Option<u16> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.num_glyphs(),
+            _ => None,
+        }
+    }
+
+    /// The number of font units in an em, which outline coordinates are measured in.
+    pub fn units_per_em(&self) -> Option<u16> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.units_per_em(),
+            _ => None,
+        }
+    }
+
+    /// How far to move the pen after drawing a glyph, in ems.
+    pub fn advance_width(&self, glyph_id: u16) -> Option<f32> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font
+                .horizontal_metric(glyph_id)
+                .map(|metric| self.to_ems(i32::from(metric.advance_width))),
+            _ => None,
+        }
+    }
+
+    /// The distance from the pen position to the left edge of a glyph's outline, in ems.
+    pub fn left_side_bearing(&self, glyph_id: u16) -> Option<f32> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font
+                .horizontal_metric(glyph_id)
+                .map(|metric| self.to_ems(i32::from(metric.left_side_bearing))),
+            _ => None,
+        }
+    }
+
+    /// The adjustment to the space between two adjacent glyphs from the legacy kern table, in
+    /// font units. Negative values move the glyphs closer together, and pairs the font doesn't
+    /// kern give 0.
+    pub fn kerning(&self, left_glyph: u16, right_glyph: u16) -> i16 {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.kerning(left_glyph, right_glyph).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// The vertical metrics for laying out lines of horizontal text, in ems.
+    ///
+    /// These come from hhea, unless the font asks for the typographic metrics in OS/2 to be used
+    /// instead.
+    pub fn line_metrics(&self) -> Option<LineMetrics> {
+        let font = match &self.font {
+            ParsedFont::OpenType(font) => font,
+            _ => return None,
+        };
+
+        let hhea = font.hhea()?;
+        let (ascender, descender, line_gap) =
+            match font.os2().and_then(|os2| os2.preferred_typo_metrics()) {
+                Some(typo) => (typo.ascender, typo.descender, typo.line_gap),
+                None => (hhea.ascender, hhea.descender, hhea.line_gap),
+            };
+        Some(LineMetrics {
+            ascender: self.to_ems(i32::from(ascender)),
+            descender: self.to_ems(i32::from(descender)),
+            line_gap: self.to_ems(i32::from(line_gap)),
+            max_advance_width: self.to_ems(i32::from(hhea.advance_width_max)),
+        })
+    }
+
+    /// The metrics for laying out columns of vertical text, in ems, from the vhea table.
+    ///
+    /// The ascender and descender are measured from the vertical centerline, to the right and to
+    /// the left, and `max_advance_width` holds the largest advance height.
+    pub fn vertical_line_metrics(&self) -> Option<LineMetrics> {
+        let vhea = match &self.font {
+            ParsedFont::OpenType(font) => font.vhea()?,
+            _ => return None,
+        };
+
+        Some(LineMetrics {
+            ascender: self.to_ems(i32::from(vhea.ascender)),
+            descender: self.to_ems(i32::from(vhea.descender)),
+            line_gap: self.to_ems(i32::from(vhea.line_gap)),
+            max_advance_width: self.to_ems(i32::from(vhea.advance_height_max)),
+        })
+    }
+
+    /// How thick the font's strokes are.
+    pub fn weight(&self) -> Option<Weight> {
+        self.os2().map(|os2| os2.weight())
+    }
+
+    /// How condensed or expanded the font is, from 1 (ultra-condensed) to 9 (ultra-expanded),
+    /// where 5 is normal.
+    pub fn width_class(&self) -> Option<u16> {
+        self.os2().map(|os2| os2.width_class)
+    }
+
+    /// What the font's license allows when it's embedded in a document, such as a PDF.
+    ///
+    /// Returns `None` for fonts without an OS/2 table, which predate embedding restrictions.
+    pub fn embedding_policy(&self) -> Option<EmbeddingPermissions> {
+        self.os2().map(|os2| os2.embedding_permissions())
+    }
+
+    #[deprecated(note = "renamed to `embedding_policy`")]
+    pub fn embedding_permissions(&self) -> Option<EmbeddingPermissions> {
+        self.embedding_policy()
+    }
+
+    pub fn panose(&self) -> Option<Panose> {
+        self.os2().map(|os2| os2.panose)
+    }
+
+    /// The four character ID registered by the font's vendor with Microsoft.
+    pub fn vendor_id(&self) -> Option<String> {
+        self.os2().map(|os2| {
+            os2.vendor_id
+                .iter()
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+    }
+
+    /// Whether the font claims to cover the Unicode block with the given bit in the OS/2
+    /// `ulUnicodeRange` fields, such as 0 for Basic Latin or 59 for CJK Unified Ideographs.
+    pub fn supports_unicode_range(&self, bit: u32) -> bool {
+        match self.os2() {
+            Some(os2) => has_bit(&os2.unicode_range, bit),
+            None => false,
+        }
+    }
+
+    /// Whether the font claims to cover the Windows code page with the given bit in the OS/2
+    /// `ulCodePageRange` fields, such as 0 for Latin 1 or 17 for Japanese.
+    pub fn supports_code_page(&self, bit: u32) -> bool {
+        match self.os2().and_then(|os2| os2.code_page_range) {
+            Some(ranges) => has_bit(&ranges, bit),
+            None => false,
+        }
+    }
+
+    /// The height of lowercase letters such as 'x', in ems.
+    pub fn x_height(&self) -> Option<f32> {
+        self.os2()
+            .and_then(|os2| os2.x_height)
+            .map(|x_height| self.to_ems(i32::from(x_height)))
+    }
+
+    /// The height of flat capital letters such as 'H', in ems.
+    pub fn cap_height(&self) -> Option<f32> {
+        self.os2()
+            .and_then(|os2| os2.cap_height)
+            .map(|cap_height| self.to_ems(i32::from(cap_height)))
+    }
+
+    /// The range of sizes the font was designed for, in points, from the smallest to the
+    /// largest. Only version 5 OS/2 tables record this.
+    pub fn optical_size_range(&self) -> Option<(f32, f32)> {
+        self.os2()
+            .and_then(|os2| os2.optical_point_sizes)
+            .map(|(lower, upper)| (f32::from(lower) / 20.0, f32::from(upper) / 20.0))
+    }
+
+    /// The PostScript name of a glyph, such as `Aacute`, if the font records one.
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<&str> {
+        self.post().and_then(|post| post.glyph_name(glyph_id))
+    }
+
+    /// Find a glyph by its PostScript name.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u16> {
+        self.post().and_then(|post| post.glyph_by_name(name))
+    }
+
+    /// The slant of the font in degrees, counter-clockwise from vertical. Italic fonts usually
+    /// have a negative angle.
+    pub fn italic_angle(&self) -> Option<f32> {
+        self.post().map(|post| post.italic_angle())
+    }
+
+    /// Whether every glyph in the font has the same advance width.
+    pub fn is_fixed_pitch(&self) -> Option<bool> {
+        self.post().map(|post| post.is_fixed_pitch())
+    }
+
+    /// The distance from the baseline up to the top of the underline, in ems. Usually negative.
+    pub fn underline_position(&self) -> Option<f32> {
+        self.post()
+            .map(|post| self.to_ems(i32::from(post.underline_position)))
+    }
+
+    /// The thickness of the underline, in ems.
+    pub fn underline_thickness(&self) -> Option<f32> {
+        self.post()
+            .map(|post| self.to_ems(i32::from(post.underline_thickness)))
+    }
+
+    /// Whether a glyph is a base, a ligature, a mark or a component, if the font says.
+    pub fn glyph_class(&self, glyph_id: u16) -> Option<GlyphClass> {
+        self.gdef().and_then(|gdef| gdef.glyph_class(glyph_id))
+    }
+
+    /// The indices of the points in a glyph's outline which marks can attach to, as listed in the
+    /// GDEF table. Empty if the font doesn't list any for the glyph.
+    pub fn attachment_points(&self, glyph_id: u16) -> &[u16] {
+        match self.gdef() {
+            Some(gdef) => gdef.attachment_points(glyph_id),
+            None => &[],
+        }
+    }
+
+    /// The positions of the carets between the components of a ligature, such as the two inside
+    /// "ffi", in ems from the glyph's origin. Empty if the glyph isn't a ligature or the font
+    /// doesn't give its carets.
+    pub fn ligature_carets(&self, glyph_id: u16) -> Vec<f32> {
+        let carets = match self.gdef() {
+            Some(gdef) => gdef.ligature_carets(glyph_id),
+            None => return vec![],
+        };
+        // Carets placed on a point of the outline take that point's unhinted position.
+        let outline = self.glyph_outline(glyph_id).ok();
+        let point_x = |idx: u16| {
+            outline
+                .as_ref()?
+                .contours
+                .iter()
+                .flat_map(|contour| contour.points.iter())
+                .nth(idx as usize)
+                .map(|point| point.x)
+        };
+        let units_per_em = f32::from(
+            self.units_per_em()
+                .unwrap_or(Self::DEFAULT_UNITS_PER_EM)
+                .max(1),
+        );
+
+        carets
+            .iter()
+            .filter_map(|caret| match *caret {
+                CaretValue::Coordinate(x) => Some(self.to_ems(i32::from(x))),
+                CaretValue::ContourPoint(idx) => point_x(idx).map(|x| x / units_per_em),
+            })
+            .collect()
+    }
+
+    /// Whether the font's substitutions or positioning have features for a script, such as
+    /// `['d', 'e', 'v', '2']`, rather than only ones for its default script.
+    pub fn has_script(&self, script: [char; 4]) -> bool {
+        match &self.font {
+            ParsedFont::OpenType(font) => {
+                let in_gsub = font.gsub().map(|gsub| gsub.has_script(script));
+                let in_gpos = font.gpos().map(|gpos| gpos.has_script(script));
+                in_gsub == Some(true) || in_gpos == Some(true)
+            }
+            _ => false,
+        }
+    }
+
+    /// Replace glyphs using the font's substitutions for the given features, such as
+    /// `['l', 'i', 'g', 'a']` for ligatures, as the font defines them for a script and language.
+    ///
+    /// Features are applied in the order the font gives, not the order they are listed.
+    pub fn substitute(
+        &self,
+        glyphs: &mut Vec<GlyphInfo>,
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[[char; 4]],
+    ) {
+        let features: Vec<_> = features.iter().map(|&tag| (tag, !0)).collect();
+        self.substitute_masked(glyphs, script, language, &features, true);
+    }
+
+    /// Replace glyphs like `substitute`, with each feature only applying to the glyphs whose
+    /// mask shares a bit with the one given for it. `GlyphInfo::new` gives a mask which every
+    /// feature applies to.
+    ///
+    /// `required` adds the font's required feature for the script and language, if it has one.
+    /// When features are substituted in stages, it should only be added to one of them.
+    pub fn substitute_masked(
+        &self,
+        glyphs: &mut Vec<GlyphInfo>,
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[([char; 4], u32)],
+        required: bool,
+    ) {
+        let gsub = match &self.font {
+            ParsedFont::OpenType(font) => match font.gsub() {
+                Some(gsub) => gsub,
+                None => return,
+            },
+            _ => return,
+        };
+        let lookups = gsub.lookups_for(script, language, features, required, &[]);
+        gsub.apply(self.gdef(), glyphs, &lookups);
+    }
+
+    /// Work out where to draw each glyph of a run, in font units, using the font's positioning
+    /// for the given features, such as `['k', 'e', 'r', 'n']` for kerning and
+    /// `['m', 'a', 'r', 'k']` for placing marks.
+    ///
+    /// Each glyph starts with its advance width, which the features then adjust. The glyphs
+    /// should be in the order of the text, as `substitute` leaves them, and scripts written from
+    /// right to left are positioned that way.
+    ///
+    /// Fonts without a GPOS table are kerned with the legacy kern table instead, when `kern` is
+    /// among the features.
+    pub fn position(
+        &self,
+        glyphs: &[GlyphInfo],
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[[char; 4]],
+    ) -> Vec<GlyphPosition> {
+        let font = match &self.font {
+            ParsedFont::OpenType(font) => font,
+            _ => return vec![GlyphPosition::default(); glyphs.len()],
+        };
+        let mut positions: Vec<_> = glyphs
+            .iter()
+            .map(|glyph| GlyphPosition {
+                x_advance: font
+                    .horizontal_metric(glyph.glyph_id)
+                    .map_or(0, |metric| i32::from(metric.advance_width)),
+                ..GlyphPosition::default()
+            })
+            .collect();
+
+        let direction = Direction::from_script(script);
+        if let Some(gpos) = font.gpos() {
+            let features: Vec<_> = features.iter().map(|&tag| (tag, !0)).collect();
+            let lookups = gpos.lookups_for(script, language, &features, true, &[]);
+            gpos.apply(self.gdef(), glyphs, &mut positions, &lookups, direction);
+        } else if features.contains(&['k', 'e', 'r', 'n']) {
+            self.apply_kern_table(glyphs, &mut positions, direction);
+        }
+        positions
+    }
+
+    /// Kern each pair of glyphs which aren't marks, changing the advance of the first.
+    fn apply_kern_table(
+        &self,
+        glyphs: &[GlyphInfo],
+        positions: &mut [GlyphPosition],
+        direction: Direction,
+    ) {
+        let mut previous: Option<usize> = None;
+        for (idx, glyph) in glyphs.iter().enumerate() {
+            if self.glyph_class(glyph.glyph_id) == Some(GlyphClass::Mark) {
+                continue;
+            }
+            if let Some(prev) = previous {
+                let prev_glyph = glyphs[prev].glyph_id;
+                // The kern table gives pairs in the order they are drawn, from left to right.
+                let kerning = match direction {
+                    Direction::LeftToRight => self.kerning(prev_glyph, glyph.glyph_id),
+                    Direction::RightToLeft => self.kerning(glyph.glyph_id, prev_glyph),
+                };
+                positions[prev].x_advance += i32::from(kerning);
+            }
+            previous = Some(idx);
+        }
+    }
+
+    fn gdef(&self) -> Option<&GdefTable> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.gdef(),
+            _ => None,
+        }
+    }
+
+    fn post(&self) -> Option<&PostTable> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.post(),
+            _ => None,
+        }
+    }
+
+    fn os2(&self) -> Option<&Os2Table> {
+        match &self.font {
+            ParsedFont::OpenType(font) => font.os2(),
+            _ => None,
+        }
+    }
+
+    /// How far to move the pen down after drawing a glyph in vertical text, in ems.
+    ///
+    /// Fonts without vertical metrics get the distance from the ascender to the descender, so
+    /// that every glyph takes up the same height.
+    pub fn vertical_advance(&self, glyph_id: u16) -> Option<f32> {
+        match &self.font {
+            ParsedFont::OpenType(font) => match font.vertical_metric(glyph_id) {
+                Some(metric) => Some(self.to_ems(i32::from(metric.advance_height))),
+                None => self
+                    .ascender_and_descender()
+                    .map(|(ascender, descender)| self.to_ems(ascender - descender)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Where the pen sits relative to a glyph in vertical text, as `(x, y)` in ems from the glyph's
+    /// horizontal origin.
+    ///
+    /// The origin is centred on the glyph's advance width. Its height comes from the VORG table if
+    /// there is one, then from the top side bearing above the glyph's bounding box, and otherwise
+    /// from the ascender.
+    pub fn vertical_origin(&self, glyph_id: u16) -> Option<(f32, f32)> {
+        let font = match &self.font {
+            ParsedFont::OpenType(font) => font,
+            _ => return None,
+        };
+
+        let x = match font.horizontal_metric(glyph_id) {
+            Some(metric) => self.to_ems(i32::from(metric.advance_width)) / 2.0,
+            None => 0.5,
+        };
+        let from_bounds = || {
+            let metric = font.vertical_metric(glyph_id)?;
+            let (_, _, _, max_y) = font.glyph_bounds(glyph_id)?;
+            Some(i32::from(max_y) + i32::from(metric.top_side_bearing))
+        };
+        let y = font
+            .vert_origin_y(glyph_id)
+            .map(i32::from)
+            .or_else(from_bounds)
+            .or_else(|| self.ascender_and_descender().map(|(ascender, _)| ascender))?;
+
+        Some((x, self.to_ems(y)))
+    }
+
+    /// The ascender and descender used to synthesise vertical metrics, in font units. OpenType
+    /// recommends the typographic metrics from OS/2, so hhea is only used for fonts without them.
+    fn ascender_and_descender(&self) -> Option<(i32, i32)> {
+        let font = match &self.font {
+            ParsedFont::OpenType(font) => font,
+            _ => return None,
+        };
+
+        match font.os2().and_then(|os2| os2.typo_metrics) {
+            Some(typo) => Some((i32::from(typo.ascender), i32::from(typo.descender))),
+            None => font
+                .hhea()
+                .map(|hhea| (i32::from(hhea.ascender), i32::from(hhea.descender))),
+        }
+    }
+
+    /// Convert a distance in font units to ems.
+    fn to_ems(&self, value: i32) -> f32 {
+        let units_per_em = self.units_per_em().unwrap_or(Self::DEFAULT_UNITS_PER_EM);
+        value as f32 / f32::from(units_per_em.max(1))
+    }
+
+    /// The outline of a glyph as SVG path data, scaled so that an em is `options.em_size` units.
+    pub fn glyph_svg_path(
+        &self,
+        glyph_id: u16,
+        options: &SvgOptions,
+    ) -> Result<String, ResolveError> {
+        let outline = self.glyph_outline(glyph_id)?;
+        let units_per_em = self.units_per_em().unwrap_or(Self::DEFAULT_UNITS_PER_EM);
+        Ok(outline.to_svg_path(units_per_em, options))
+    }
+
+    /// Render a glyph into an anti-aliased coverage bitmap at `pixel_size` pixels per em.
+    ///
+    /// `offset` shifts the glyph right and up by a fraction of a pixel before rendering.
+    pub fn rasterize_glyph(
+        &self,
+        glyph_id: u16,
+        pixel_size: f32,
+        offset: (f32, f32),
+    ) -> Result<Bitmap, ResolveError> {
+        let outline = self.glyph_outline(glyph_id)?;
+        let units_per_em = self.units_per_em().unwrap_or(Self::DEFAULT_UNITS_PER_EM);
+        Ok(raster::rasterize(
+            &outline,
+            units_per_em,
+            pixel_size,
+            offset,
+        ))
+    }
+
     pub fn dump_glyphs(&self) {
         match &self.font {
             ParsedFont::OpenType(font) => {
@@ -45,7 +635,8 @@ impl<'a> Font<'a> {
                     println!("Num glyphs: {}", n);
                     for idx in 0..n {
                         let glyph = font.lookup_glyph(idx as usize);
-                        println!("DUMP: {} {:?}", idx, glyph);
+                        let name = self.glyph_name(idx).unwrap_or("-");
+                        println!("DUMP: {} {} {:?}", idx, name, glyph);
                     }
                 });
             }
@@ -100,6 +691,35 @@ impl<'a> Font<'a> {
     }
 }
 
+/// Metrics which apply to every line of horizontal text, in ems. Multiply by the font size to get
+/// distances in pixels or points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LineMetrics {
+    /// The distance from the baseline up to the top of the tallest glyphs.
+    pub ascender: f32,
+    /// The distance from the baseline up to the bottom of the lowest glyphs, usually negative.
+    pub descender: f32,
+    /// The extra space the font asks for between lines.
+    pub line_gap: f32,
+    /// The largest advance width of any glyph.
+    pub max_advance_width: f32,
+}
+
+impl LineMetrics {
+    /// The distance from one baseline to the next.
+    pub fn line_height(&self) -> f32 {
+        self.ascender - self.descender + self.line_gap
+    }
+}
+
+/// Test a bit in a bit set stored as a sequence of 32 bit words, least significant word first.
+fn has_bit(words: &[u32], bit: u32) -> bool {
+    match words.get((bit / 32) as usize) {
+        Some(word) => word & (1 << (bit % 32)) != 0,
+        None => false,
+    }
+}
+
 fn parse_string(encoding: opentype::encoding::Encoding, bytes: &[u8]) -> Option<String> {
     use opentype::encoding::Encoding;
 
@@ -123,10 +743,51 @@ fn to_utf8<T: Into<strings::Utf8>>(string: T) -> strings::Utf8 {
 }
 
 fn to_string(utf8: strings::Utf8) -> String {
-    String::from_utf8(utf8.to_bytes().to_vec()).unwrap()
+    // Name records are untrusted input and may hold unpaired surrogates or invalid UTF-8.
+    String::from_utf8_lossy(utf8.to_bytes()).into_owned()
 }
 
-#[derive(Debug)]
+/// Errors which prevent a font file from being parsed.
+#[derive(Debug, PartialEq)]
 pub enum FontParseErr {
+    /// The file doesn't start with the signature of any recognised font format.
     UnrecognizedFormatError,
+    /// The file header or table directory is malformed.
+    /// The offset is relative to the start of the file.
+    HeaderError { offset: usize, reason: ErrorReason },
+    /// A table is malformed or requires another table which is missing.
+    /// The offset is relative to the start of the file.
+    TableError {
+        tag: [char; 4],
+        offset: usize,
+        reason: ErrorReason,
+    },
+}
+
+impl FontParseErr {
+    /// Build an error for a problem found `offset` bytes into `table`.
+    pub(crate) fn in_table(table: &Table, offset: usize, reason: ErrorReason) -> Self {
+        FontParseErr::TableError {
+            tag: table.tag,
+            offset: table.offset + offset,
+            reason,
+        }
+    }
+}
+
+/// Describes what was wrong with the data at the location reported by a `FontParseErr`.
+#[derive(Debug, PartialEq)]
+pub enum ErrorReason {
+    /// The data ends before the structure being read.
+    Truncated,
+    /// The version or format of the structure isn't supported.
+    BadVersion,
+    /// An offset or length points outside of the data it refers to.
+    BadOffset,
+    /// The table can't be parsed without the table with the given tag.
+    MissingDependency([char; 4]),
+    /// The cmap table failed to parse.
+    Cmap(cmap::ParseError),
+    /// The name table failed to parse.
+    Name(name::ParseError),
 }
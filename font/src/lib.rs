@@ -2,12 +2,27 @@ extern crate byteorder;
 extern crate fixed;
 extern crate strings;
 
+mod collection;
 mod filetype;
 mod font;
 mod opentype;
+mod raster;
 pub mod sfnt;
+mod shape;
 mod truetype;
 
-pub use font::{Font, FontParseErr};
+pub use collection::FontCollection;
+pub use font::{ErrorReason, Font, FontParseErr, LineMetrics};
+pub use opentype::tables::cmap::ParseError as CmapParseError;
+pub use opentype::tables::cmap::VariantLookup;
+pub use opentype::tables::gdef::GlyphClass;
+pub use opentype::tables::glyf::{
+    Contour, Outline, OutlineBuilder, Point, ResolveError, SvgOptions,
+};
+pub use opentype::tables::layout::{Direction, GlyphInfo, GlyphPosition};
+pub use opentype::tables::name::ParseError as NameParseError;
+pub use opentype::tables::os2::{EmbeddingPermissions, EmbeddingUsage, Panose, Weight};
+pub use raster::{rasterize, Bitmap};
+pub use shape::{shape, ShapedGlyph, DEFAULT_FEATURES};
 // TODO: Have a generic version of Name which maps to format-specific formats as needed.
 pub use opentype::tables::name::Name;
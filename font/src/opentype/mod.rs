@@ -1,15 +1,31 @@
 pub mod encoding;
 pub mod platform;
 pub mod tables;
-mod types;
+pub(crate) mod types;
 
-use self::tables::cmap::CmapTable;
-use self::tables::glyf::GlyfTable;
+use self::tables::cmap::{self, CmapTable, VariantLookup};
+use self::tables::gdef::GdefTable;
+use self::tables::glyf::{GlyfTable, Outline, ResolveError};
+use self::tables::gpos::GposTable;
+use self::tables::gsub::GsubTable;
 use self::tables::head::HeadTable;
-use self::tables::loca::LocaTable;
+use self::tables::hhea::HheaTable;
+use self::tables::hmtx::{HmtxTable, HorizontalMetric};
+use self::tables::kern::{self, KernTable};
+use self::tables::layout;
+use self::tables::loca::{self, LocaTable};
 use self::tables::maxp::MaxpTable;
-use self::tables::name::NameTable;
-use super::sfnt::SfntFile;
+use self::tables::name::{self, NameTable};
+use self::tables::os2::Os2Table;
+use self::tables::post::PostTable;
+use self::tables::vhea::VheaTable;
+use self::tables::vmtx::{VerticalMetric, VmtxTable};
+use self::tables::vorg::{self, VorgTable};
+use self::types::ReadError;
+use super::sfnt::{SfntFile, Table};
+use font::{ErrorReason, FontParseErr};
+use std::iter;
+use strings;
 
 pub use self::tables::glyf::Glyph;
 
@@ -17,112 +33,416 @@ pub use self::tables::glyf::Glyph;
 pub struct OpenTypeFile<'a> {
     sfnt: SfntFile<'a>,
     cmap: Option<CmapTable>,
+    gdef: Option<GdefTable>,
     glyf: Option<GlyfTable>,
+    gpos: Option<GposTable>,
+    gsub: Option<GsubTable>,
     head: Option<HeadTable>,
+    hhea: Option<HheaTable>,
+    hmtx: Option<HmtxTable>,
+    kern: Option<KernTable>,
     loca: Option<LocaTable>,
     maxp: Option<MaxpTable>,
     pub name: Option<NameTable>,
+    os2: Option<Os2Table>,
+    post: Option<PostTable>,
+    vhea: Option<VheaTable>,
+    vmtx: Option<VmtxTable>,
+    vorg: Option<VorgTable>,
+    /// Errors from optional tables which were left out, so that the rest of the font could be
+    /// used.
+    table_errors: Vec<FontParseErr>,
 }
 
 impl<'a> OpenTypeFile<'a> {
-    pub fn deserialize(content: &'a [u8]) -> Self {
-        let sfnt = SfntFile::deserialize(content);
+    /// Parse the font whose table directory starts `offset` bytes into `content`, such as one
+    /// of the fonts in a collection.
+    pub fn deserialize_at(content: &'a [u8], offset: usize) -> Result<Self, FontParseErr> {
+        let sfnt = SfntFile::deserialize_at(content, offset)?;
+        let mut table_errors = vec![];
 
-        let mut cmap_data = None;
-        let mut glyf_data = None;
-        let mut head_data = None;
-        let mut loca_data = None;
-        let mut maxp_data = None;
-        let mut name_data = None;
+        let mut cmap_record = None;
+        let mut gdef_record = None;
+        let mut glyf_record = None;
+        let mut gpos_record = None;
+        let mut gsub_record = None;
+        let mut head_record = None;
+        let mut hhea_record = None;
+        let mut hmtx_record = None;
+        let mut kern_record = None;
+        let mut loca_record = None;
+        let mut maxp_record = None;
+        let mut name_record = None;
+        let mut os2_record = None;
+        let mut post_record = None;
+        let mut vhea_record = None;
+        let mut vmtx_record = None;
+        let mut vorg_record = None;
 
         for record in &sfnt.tables {
             let table_type = TableType::table_type(record.tag);
             match table_type {
-                TableType::Cmap => cmap_data = Some(record.table_data),
-                TableType::Glyf => glyf_data = Some(record.table_data),
-                TableType::Head => head_data = Some(record.table_data),
-                TableType::Loca => loca_data = Some(record.table_data),
-                TableType::Maxp => maxp_data = Some(record.table_data),
-                TableType::Name => name_data = Some(record.table_data),
+                TableType::Cmap => cmap_record = Some(record),
+                TableType::Gdef => gdef_record = Some(record),
+                TableType::Glyf => glyf_record = Some(record),
+                TableType::Gpos => gpos_record = Some(record),
+                TableType::Gsub => gsub_record = Some(record),
+                TableType::Head => head_record = Some(record),
+                TableType::Hhea => hhea_record = Some(record),
+                TableType::Hmtx => hmtx_record = Some(record),
+                TableType::Kern => kern_record = Some(record),
+                TableType::Loca => loca_record = Some(record),
+                TableType::Maxp => maxp_record = Some(record),
+                TableType::Name => name_record = Some(record),
+                TableType::Os2 => os2_record = Some(record),
+                TableType::Post => post_record = Some(record),
+                TableType::Vhea => vhea_record = Some(record),
+                TableType::Vmtx => vmtx_record = Some(record),
+                TableType::Vorg => vorg_record = Some(record),
                 _ => {}
             }
         }
 
         let mut cmap = None;
-        if let Some(table_data) = cmap_data {
-            match CmapTable::deserialize(table_data) {
-                Ok(parsed) => {
-                    cmap = Some(parsed);
-                }
-                Err(err) => {
-                    panic!("Error deserializing cmap table {:?}", err);
-                }
-            }
+        if let Some(record) = cmap_record {
+            cmap = Some(
+                CmapTable::deserialize(record.table_data).map_err(|err| match err {
+                    cmap::ParseError::Truncated(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+                    }
+                    err => FontParseErr::in_table(record, 0, ErrorReason::Cmap(err)),
+                })?,
+            );
         }
         let mut head = None;
-        if let Some(table_data) = head_data {
-            head = Some(HeadTable::parse(table_data));
+        if let Some(record) = head_record {
+            head = Some(HeadTable::parse(record.table_data).map_err(truncated(record))?);
         }
         let mut maxp = None;
-        if let Some(table_data) = maxp_data {
-            maxp = Some(MaxpTable::parse(table_data));
+        if let Some(record) = maxp_record {
+            maxp = Some(MaxpTable::parse(record.table_data).map_err(truncated(record))?);
+        }
+        let mut hhea = None;
+        if let Some(record) = hhea_record {
+            hhea = Some(HheaTable::parse(record.table_data).map_err(truncated(record))?);
+        }
+        let mut hmtx = None;
+        if let Some(record) = hmtx_record {
+            let missing =
+                |tag| FontParseErr::in_table(record, 0, ErrorReason::MissingDependency(tag));
+            let hhea_table = hhea.as_ref().ok_or_else(|| missing(['h', 'h', 'e', 'a']))?;
+            let maxp_table = maxp.as_ref().ok_or_else(|| missing(['m', 'a', 'x', 'p']))?;
+
+            hmtx = Some(
+                HmtxTable::parse(
+                    record.table_data,
+                    hhea_table.num_h_metrics,
+                    maxp_table.num_glyphs,
+                )
+                .map_err(truncated(record))?,
+            );
         }
         let mut loca = None;
-        if let Some(table_data) = loca_data {
-            let (ret_maxp, ret_head) = match (maxp, head, glyf_data) {
-                (Some(maxp_table), Some(head_table), Some(glyf_data)) => {
-                    loca = Some(LocaTable::parse(
-                        table_data,
-                        head_table.index_to_loc_fmt,
-                        maxp_table.num_glyphs,
-                        glyf_data.len(),
-                    ));
-                    (Some(maxp_table), Some(head_table))
-                }
-                (None, _, _) => {
-                    panic!("Cannot deserialize loca table because no maxp table found.");
-                }
-                (_, None, _) => {
-                    panic!("Cannot deserialize loca table because no head table found.");
-                }
-                (_, _, None) => {
-                    panic!("Cannot deserialize loca table because no glyf data found.");
-                }
-            };
-            maxp = ret_maxp;
-            head = ret_head;
+        if let Some(record) = loca_record {
+            let missing =
+                |tag| FontParseErr::in_table(record, 0, ErrorReason::MissingDependency(tag));
+            let maxp_table = maxp.as_ref().ok_or_else(|| missing(['m', 'a', 'x', 'p']))?;
+            let head_table = head.as_ref().ok_or_else(|| missing(['h', 'e', 'a', 'd']))?;
+            let glyf_table = glyf_record.ok_or_else(|| missing(['g', 'l', 'y', 'f']))?;
+
+            loca = Some(
+                LocaTable::parse(
+                    record.table_data,
+                    head_table.index_to_loc_fmt,
+                    maxp_table.num_glyphs,
+                    glyf_table.table_data.len(),
+                )
+                .map_err(|err| match err {
+                    loca::ParseError::UnknownFormat => FontParseErr::in_table(
+                        head_record.unwrap(),
+                        HeadTable::INDEX_TO_LOC_FMT_OFFSET,
+                        ErrorReason::BadVersion,
+                    ),
+                    loca::ParseError::Truncated(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+                    }
+                    loca::ParseError::BadOffset(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::BadOffset)
+                    }
+                })?,
+            );
         }
         let mut glyf = None;
-        if let (Some(table_data), Some(loca)) = (glyf_data, loca.as_ref()) {
-            glyf = Some(GlyfTable::parse(table_data, loca));
+        if let (Some(record), Some(loca)) = (glyf_record, loca.as_ref()) {
+            glyf = Some(GlyfTable::parse(record.table_data, loca).map_err(truncated(record))?);
         }
         let mut name = None;
-        if let Some(table_data) = name_data {
-            match NameTable::deserialize(table_data) {
-                Ok(parsed) => {
-                    name = Some(parsed);
-                }
-                Err(err) => {
-                    panic!("Error deserializing cmap table {:?}", err);
-                }
-            }
+        if let Some(record) = name_record {
+            name = Some(
+                NameTable::deserialize(record.table_data).map_err(|err| match err {
+                    name::ParseError::Truncated(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+                    }
+                    err => FontParseErr::in_table(record, 0, ErrorReason::Name(err)),
+                })?,
+            );
         }
 
-        Self {
+        let mut gdef = None;
+        if let Some(record) = gdef_record {
+            gdef = optional(
+                GdefTable::parse(record.table_data).map_err(layout_error(record)),
+                &mut table_errors,
+            );
+        }
+        let mut gpos = None;
+        if let Some(record) = gpos_record {
+            gpos = optional(
+                GposTable::parse(record.table_data).map_err(layout_error(record)),
+                &mut table_errors,
+            );
+        }
+        let mut gsub = None;
+        if let Some(record) = gsub_record {
+            gsub = optional(
+                GsubTable::parse(record.table_data).map_err(layout_error(record)),
+                &mut table_errors,
+            );
+        }
+        let mut kern = None;
+        if let Some(record) = kern_record {
+            kern = optional(
+                KernTable::parse(record.table_data).map_err(|err| match err {
+                    kern::ParseError::UnknownVersion => {
+                        FontParseErr::in_table(record, 0, ErrorReason::BadVersion)
+                    }
+                    kern::ParseError::Truncated(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+                    }
+                }),
+                &mut table_errors,
+            );
+        }
+        let mut os2 = None;
+        if let Some(record) = os2_record {
+            os2 = optional(
+                Os2Table::parse(record.table_data).map_err(truncated(record)),
+                &mut table_errors,
+            );
+        }
+        let mut post = None;
+        if let Some(record) = post_record {
+            post = optional(
+                PostTable::parse(record.table_data).map_err(truncated(record)),
+                &mut table_errors,
+            );
+        }
+        let mut vhea = None;
+        if let Some(record) = vhea_record {
+            vhea = optional(
+                VheaTable::parse(record.table_data).map_err(truncated(record)),
+                &mut table_errors,
+            );
+        }
+        let mut vmtx = None;
+        if let Some(record) = vmtx_record {
+            let missing =
+                |tag| FontParseErr::in_table(record, 0, ErrorReason::MissingDependency(tag));
+            let parse = || {
+                let vhea_table = vhea.as_ref().ok_or_else(|| missing(['v', 'h', 'e', 'a']))?;
+                let maxp_table = maxp.as_ref().ok_or_else(|| missing(['m', 'a', 'x', 'p']))?;
+                VmtxTable::parse(
+                    record.table_data,
+                    vhea_table.num_v_metrics,
+                    maxp_table.num_glyphs,
+                )
+                .map_err(truncated(record))
+            };
+
+            vmtx = optional(parse(), &mut table_errors);
+        }
+        let mut vorg = None;
+        if let Some(record) = vorg_record {
+            vorg = optional(
+                VorgTable::parse(record.table_data).map_err(|err| match err {
+                    vorg::ParseError::UnknownVersion => {
+                        FontParseErr::in_table(record, 0, ErrorReason::BadVersion)
+                    }
+                    vorg::ParseError::Truncated(offset) => {
+                        FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+                    }
+                }),
+                &mut table_errors,
+            );
+        }
+
+        Ok(Self {
             sfnt,
             cmap,
+            gdef,
             glyf,
+            gpos,
+            gsub,
             head,
+            hhea,
+            hmtx,
+            kern,
             loca,
             maxp,
             name,
-        }
+            os2,
+            post,
+            vmtx,
+            vhea,
+            vorg,
+            table_errors,
+        })
+    }
+
+    /// Errors from tables the font can do without, which were ignored when it was opened.
+    pub fn table_errors(&self) -> &[FontParseErr] {
+        &self.table_errors
     }
 
     pub fn num_glyphs(&self) -> Option<u16> {
         self.loca.as_ref().map(|table| table.num_glyphs)
     }
 
+    pub fn units_per_em(&self) -> Option<u16> {
+        self.head.as_ref().map(|table| table.units_per_em)
+    }
+
+    /// The horizontal metrics of the font as a whole, in font units.
+    pub fn hhea(&self) -> Option<&HheaTable> {
+        self.hhea.as_ref()
+    }
+
+    /// The vertical metrics of the font as a whole, in font units.
+    pub fn vhea(&self) -> Option<&VheaTable> {
+        self.vhea.as_ref()
+    }
+
+    /// The glyph definitions used by the layout tables.
+    pub fn gdef(&self) -> Option<&GdefTable> {
+        self.gdef.as_ref()
+    }
+
+    /// The glyph positioning for features such as kerning and mark placement.
+    pub fn gpos(&self) -> Option<&GposTable> {
+        self.gpos.as_ref()
+    }
+
+    /// The glyph substitutions for features such as ligatures.
+    pub fn gsub(&self) -> Option<&GsubTable> {
+        self.gsub.as_ref()
+    }
+
+    pub fn os2(&self) -> Option<&Os2Table> {
+        self.os2.as_ref()
+    }
+
+    pub fn post(&self) -> Option<&PostTable> {
+        self.post.as_ref()
+    }
+
+    /// A glyph's advance width and left side bearing, in font units.
+    pub fn horizontal_metric(&self, glyph_id: u16) -> Option<HorizontalMetric> {
+        self.hmtx.as_ref().and_then(|hmtx| hmtx.metric(glyph_id))
+    }
+
+    /// The legacy kerning between two glyphs from the kern table, in font units.
+    pub fn kerning(&self, left: u16, right: u16) -> Option<i16> {
+        self.kern.as_ref().map(|kern| kern.kerning(left, right))
+    }
+
+    /// A glyph's advance height and top side bearing, in font units.
+    pub fn vertical_metric(&self, glyph_id: u16) -> Option<VerticalMetric> {
+        self.vmtx.as_ref().and_then(|vmtx| vmtx.metric(glyph_id))
+    }
+
+    /// The y coordinate of a glyph's vertical origin from the VORG table, in font units.
+    pub fn vert_origin_y(&self, glyph_id: u16) -> Option<i16> {
+        self.vorg.as_ref().map(|vorg| vorg.vert_origin_y(glyph_id))
+    }
+
+    /// A glyph's bounding box as recorded in the glyf table.
+    pub fn glyph_bounds(&self, glyph_id: u16) -> Option<(i16, i16, i16, i16)> {
+        self.glyf.as_ref().and_then(|glyf| glyf.bounds(glyph_id))
+    }
+
+    /// Find the glyph index for a Unicode codepoint.
+    ///
+    /// Fonts without a Unicode cmap subtable fall back to the glyph names in the post table.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        if let Some(cmap) = &self.cmap {
+            let glyph_id = cmap.glyph_index(codepoint);
+            if glyph_id.is_some() || cmap.has_unicode_subtable() {
+                return glyph_id;
+            }
+        }
+
+        let post = self.post.as_ref()?;
+        let num_glyphs = self.maxp.as_ref()?.num_glyphs;
+        (0..num_glyphs).find(|&glyph_id| {
+            post.glyph_name(glyph_id)
+                .map(strings::glyph_name_to_codepoints)
+                == Some(vec![codepoint])
+        })
+    }
+
+    /// Every Unicode codepoint which maps to a glyph, along with its glyph.
+    pub fn mappings(&self) -> cmap::Mappings<'_> {
+        match &self.cmap {
+            Some(cmap) => cmap.mappings(),
+            None => Box::new(iter::empty()),
+        }
+    }
+
+    /// The codepoints which map to a glyph in the cmap table or, failing that, the characters
+    /// its name in the post table represents.
+    pub fn codepoints_for_glyph(&self, glyph_id: u16) -> Vec<u32> {
+        let codepoints = self
+            .cmap
+            .as_ref()
+            .map_or_else(Vec::new, |cmap| cmap.codepoints_for_glyph(glyph_id));
+        if !codepoints.is_empty() {
+            return codepoints;
+        }
+
+        self.post
+            .as_ref()
+            .and_then(|post| post.glyph_name(glyph_id))
+            .map_or_else(Vec::new, strings::glyph_name_to_codepoints)
+    }
+
+    /// Find the glyph for a base character followed by a variation selector.
+    pub fn glyph_index_variant(&self, base: u32, selector: u32) -> VariantLookup {
+        self.cmap
+            .as_ref()
+            .map_or(VariantLookup::NotSupported, |cmap| {
+                cmap.glyph_index_variant(base, selector)
+            })
+    }
+
+    /// A glyph's outline, with the components of compound glyphs flattened into place.
+    pub fn glyph_outline(&self, glyph_id: u16) -> Result<Outline, ResolveError> {
+        let glyf = self
+            .glyf
+            .as_ref()
+            .ok_or(ResolveError::MissingGlyph(glyph_id))?;
+        // A compound glyph made of simple glyphs has a depth of 1. Some fonts which contain
+        // compound glyphs still declare 0, so always allow at least that much.
+        let max_depth = self
+            .maxp
+            .as_ref()
+            .and_then(|maxp| maxp.max_comp_depth)
+            .unwrap_or(0)
+            .max(1);
+
+        let contours = glyf.contours(glyph_id, max_depth)?;
+        Ok(Outline { contours })
+    }
+
     pub fn lookup_glyph(&self, idx: usize) -> Option<Glyph> {
         let glyf = self.glyf.as_ref();
 
@@ -130,6 +450,28 @@ impl<'a> OpenTypeFile<'a> {
     }
 }
 
+/// The table from a parse, or `None` if it failed, in which case the error is added to `errors`.
+fn optional<T>(result: Result<T, FontParseErr>, errors: &mut Vec<FontParseErr>) -> Option<T> {
+    result.map_err(|err| errors.push(err)).ok()
+}
+
+/// Maps an error parsing the layout table in `record` to an error locating it in the file.
+fn layout_error<'a>(record: &'a Table) -> impl Fn(layout::ParseError) -> FontParseErr + 'a {
+    move |err| match err {
+        layout::ParseError::UnknownVersion | layout::ParseError::UnknownFormat => {
+            FontParseErr::in_table(record, 0, ErrorReason::BadVersion)
+        }
+        layout::ParseError::Truncated(offset) => {
+            FontParseErr::in_table(record, offset, ErrorReason::Truncated)
+        }
+    }
+}
+
+/// Maps a failed read within `record` to an error locating the read in the file.
+fn truncated<'a>(record: &'a Table) -> impl Fn(ReadError) -> FontParseErr + 'a {
+    move |err| FontParseErr::in_table(record, err.offset, ErrorReason::Truncated)
+}
+
 #[derive(PartialEq, Debug)]
 enum TableType {
     /// Axis variations table
@@ -294,3 +636,219 @@ impl TableType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use opentype::tables::test_data::build_font;
+
+    const SAMPLE_FONT: &[u8] = include_bytes!("../../samples/empty.ttf");
+
+    #[test]
+    fn deserialize_sample_font() {
+        let font = OpenTypeFile::deserialize_at(SAMPLE_FONT, 0).unwrap();
+
+        assert!(font.cmap.is_some());
+        assert!(font.name.is_some());
+        assert_eq!(font.num_glyphs(), Some(3));
+        assert_eq!(font.hhea().map(|hhea| hhea.ascender), Some(666));
+    }
+
+    #[test]
+    fn deserialize_loca_without_maxp() {
+        let mut content = vec![0x00u8; 32];
+        content[..6].clone_from_slice(&[0x00u8, 0x01, 0x00, 0x00, 0x00, 0x01]);
+        content[12..16].clone_from_slice(&[0x6Cu8, 0x6F, 0x63, 0x61]);
+        content[23] = 28;
+        content[27] = 4;
+
+        assert_eq!(
+            OpenTypeFile::deserialize_at(&content, 0).unwrap_err(),
+            FontParseErr::TableError {
+                tag: ['l', 'o', 'c', 'a'],
+                offset: 28,
+                reason: ErrorReason::MissingDependency(['m', 'a', 'x', 'p']),
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_hmtx_without_hhea() {
+        let mut content = vec![0x00u8; 32];
+        content[..6].clone_from_slice(&[0x00u8, 0x01, 0x00, 0x00, 0x00, 0x01]);
+        content[12..16].clone_from_slice(&[0x68u8, 0x6D, 0x74, 0x78]);
+        content[23] = 28;
+        content[27] = 4;
+
+        assert_eq!(
+            OpenTypeFile::deserialize_at(&content, 0).unwrap_err(),
+            FontParseErr::TableError {
+                tag: ['h', 'm', 't', 'x'],
+                offset: 28,
+                reason: ErrorReason::MissingDependency(['h', 'h', 'e', 'a']),
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_truncated_head() {
+        let mut content = vec![0x00u8; 48];
+        content[..6].clone_from_slice(&[0x00u8, 0x01, 0x00, 0x00, 0x00, 0x01]);
+        content[12..16].clone_from_slice(&[0x68u8, 0x65, 0x61, 0x64]);
+        content[23] = 28;
+        content[27] = 20;
+
+        assert_eq!(
+            OpenTypeFile::deserialize_at(&content, 0).unwrap_err(),
+            FontParseErr::TableError {
+                tag: ['h', 'e', 'a', 'd'],
+                offset: 48,
+                reason: ErrorReason::Truncated,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_vhea() {
+        let vhea = [
+            0x00u8, 0x01, 0x10, 0x00, 0x01, 0xF4, 0xFE, 0x0C, 0x00, 0x00, 0x04, 0x00, 0x00, 0x32,
+            0x00, 0x14, 0x03, 0xB6, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let content = build_font(&[], &[500], &[(b"vhea", &vhea)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        let vhea = font.vhea().unwrap();
+        assert_eq!(vhea.ascender, 500);
+        assert_eq!(vhea.advance_height_max, 1024);
+    }
+
+    #[test]
+    fn deserialize_truncated_os2() {
+        let os2 = &tables::test_data::sample_table(b"OS/2")[..40];
+        let content = build_font(&[('A', 1)], &[500, 500], &[(b"OS/2", os2)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert!(font.os2().is_none());
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [FontParseErr::TableError {
+                tag: ['O', 'S', '/', '2'],
+                reason: ErrorReason::Truncated,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn deserialize_truncated_post() {
+        let post = [0x00u8, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let content = build_font(&[('A', 1)], &[500, 500], &[(b"post", &post)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert!(font.post().is_none());
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [FontParseErr::TableError {
+                tag: ['p', 'o', 's', 't'],
+                reason: ErrorReason::Truncated,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn deserialize_unknown_kern_version() {
+        let kern = [0x00u8, 0x09, 0x00, 0x00];
+        let content = build_font(&[('A', 1)], &[500, 500], &[(b"kern", &kern)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert_eq!(font.kerning(1, 1), None);
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [FontParseErr::TableError {
+                tag: ['k', 'e', 'r', 'n'],
+                reason: ErrorReason::BadVersion,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn deserialize_unknown_gdef_version() {
+        let gdef = [0x00u8, 0x02, 0x00, 0x00];
+        let content = build_font(&[('A', 1)], &[500, 500], &[(b"GDEF", &gdef)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert!(font.gdef().is_none());
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [FontParseErr::TableError {
+                tag: ['G', 'D', 'E', 'F'],
+                reason: ErrorReason::BadVersion,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn deserialize_broken_gsub_and_vertical_tables() {
+        let gsub = [0x00u8, 0x02, 0x00, 0x00];
+        let vmtx = [0x01u8, 0xF4, 0x00, 0x00];
+        let vorg = [0x00u8, 0x02, 0x00, 0x00];
+        let content = build_font(
+            &[('A', 1)],
+            &[500, 500],
+            &[(b"GSUB", &gsub), (b"vmtx", &vmtx), (b"VORG", &vorg)],
+        );
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert!(font.gsub().is_none());
+        assert_eq!(font.vertical_metric(1), None);
+        assert_eq!(font.vert_origin_y(1), None);
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [
+                FontParseErr::TableError {
+                    tag: ['G', 'S', 'U', 'B'],
+                    reason: ErrorReason::BadVersion,
+                    ..
+                },
+                FontParseErr::TableError {
+                    tag: ['v', 'm', 't', 'x'],
+                    reason: ErrorReason::MissingDependency(['v', 'h', 'e', 'a']),
+                    ..
+                },
+                FontParseErr::TableError {
+                    tag: ['V', 'O', 'R', 'G'],
+                    reason: ErrorReason::BadVersion,
+                    ..
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn deserialize_unknown_gpos_version() {
+        let gpos = [0x00u8, 0x02, 0x00, 0x00];
+        let content = build_font(&[('A', 1)], &[500, 500], &[(b"GPOS", &gpos)]);
+        let font = OpenTypeFile::deserialize_at(&content, 0).unwrap();
+
+        assert!(font.gpos().is_none());
+        assert_eq!(font.glyph_index(0x41), Some(1));
+        assert!(matches!(
+            font.table_errors(),
+            [FontParseErr::TableError {
+                tag: ['G', 'P', 'O', 'S'],
+                reason: ErrorReason::BadVersion,
+                ..
+            }]
+        ));
+    }
+}
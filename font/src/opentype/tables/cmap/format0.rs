@@ -0,0 +1,76 @@
+//! Format 0 is the original Apple byte encoding table. It maps each of 256 single-byte character
+//! codes directly to a glyph, so it can only address the first 256 glyphs.
+
+use super::ParseError;
+use opentype::types::{Reader, U8};
+
+#[derive(Debug)]
+pub struct Format0 {
+    glyph_id_array: Vec<u8>,
+}
+
+impl Format0 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, the length, which is always 262, and the language.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 6);
+        let glyph_id_array = reader.read_array::<u8, U8>(256)?;
+
+        Ok(Self { glyph_id_array })
+    }
+
+    /// Find the glyph for a character code. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, code: u32) -> Option<u16> {
+        match *self.glyph_id_array.get(code as usize)? {
+            0 => None,
+            glyph => Some(u16::from(glyph)),
+        }
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        self.glyph_id_array
+            .iter()
+            .enumerate()
+            .filter(|(_, glyph)| **glyph != 0)
+            .map(|(code, glyph)| (code as u32, u16::from(*glyph)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_subtable() -> Vec<u8> {
+        let mut data = vec![0x00, 0x00, 0x01, 0x06, 0x00, 0x00];
+        data.extend((0..=255u8).map(|code| if code >= 0x41 { code - 0x40 } else { 0 }));
+        data
+    }
+
+    #[test]
+    fn lookup() {
+        let subtable = Format0::parse(&sample_subtable(), 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('A' as u32), Some(1));
+        assert_eq!(subtable.glyph_index(0xFF), Some(0xBF));
+        assert_eq!(subtable.glyph_index(0x20), None);
+        assert_eq!(subtable.glyph_index(0x100), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format0::parse(&sample_subtable(), 0).unwrap();
+        let mappings: Vec<_> = subtable.mappings().collect();
+
+        assert_eq!(mappings.len(), 0xBF);
+        assert_eq!(mappings[0], ('A' as u32, 1));
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format0::parse(&sample_subtable()[..261], 0).unwrap_err(),
+            ParseError::Truncated(261)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Format 10 is the 32 bit counterpart of format 6: a single contiguous range of character codes
+//! mapped to glyphs.
+
+use super::ParseError;
+use opentype::types::{Reader, U16};
+
+#[derive(Debug)]
+pub struct Format10 {
+    start_char_code: u32,
+    glyphs: Vec<u16>,
+}
+
+impl Format10 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, reserved field, length and language.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 12);
+        let start_char_code = reader.read_u32()?;
+        let num_chars = reader.read_u32()? as usize;
+        let glyphs = reader.read_array::<u16, U16>(num_chars)?;
+
+        Ok(Self {
+            start_char_code,
+            glyphs,
+        })
+    }
+
+    /// Find the glyph for a character. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        let idx = codepoint.checked_sub(self.start_char_code)?;
+        match *self.glyphs.get(idx as usize)? {
+            0 => None,
+            glyph => Some(glyph),
+        }
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        let start = self.start_char_code;
+        self.glyphs
+            .iter()
+            .enumerate()
+            .map_while(move |(idx, glyph)| Some((start.checked_add(idx as u32)?, *glyph)))
+            .filter(|(_, glyph)| *glyph != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps U+1F600-U+1F601 to glyphs 3 and 4.
+    const SAMPLE_SUBTABLE: [u8; 24] = [
+        0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xF6,
+        0x00, 0x00, 0x00, 0x00, 0x02, // header
+        0x00, 0x03, 0x00, 0x04, // glyphs
+    ];
+
+    #[test]
+    fn lookup() {
+        let subtable = Format10::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x1F600), Some(3));
+        assert_eq!(subtable.glyph_index(0x1F601), Some(4));
+        assert_eq!(subtable.glyph_index(0x1F602), None);
+        assert_eq!(subtable.glyph_index('A' as u32), None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format10::parse(&SAMPLE_SUBTABLE[..23], 0).unwrap_err(),
+            ParseError::Truncated(22)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Format 12 is the standard mapping subtable for fonts which support characters outside the
+//! Unicode Basic Multilingual Plane.
+//!
+//! Characters are divided into groups of consecutive codepoints which map to consecutive glyphs.
+
+use super::ParseError;
+use opentype::types::{ReadError, Reader};
+use std::ops::RangeInclusive;
+
+/// The last Unicode codepoint. Groups which run beyond it are malformed.
+const MAX_CODEPOINT: u32 = 0x10_FFFF;
+
+#[derive(Debug)]
+pub struct Format12 {
+    groups: Vec<MapGroup>,
+}
+
+impl Format12 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        Ok(Self {
+            groups: parse_groups(cmap_data, subtable_offset)?,
+        })
+    }
+
+    /// Find the glyph for a character. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        find_group(&self.groups, codepoint).and_then(|group| {
+            let offset = codepoint - group.start_char_code;
+            group
+                .start_glyph_id
+                .checked_add(offset)
+                .and_then(to_glyph_id)
+        })
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        uncovered_char_codes(&self.groups)
+            .flat_map(|(group, char_codes)| sequential_mappings(group, char_codes))
+    }
+}
+
+/// A range of characters which map to glyphs. Formats 12 and 13 share the same layout but
+/// interpret the glyph differently.
+#[derive(Debug)]
+pub struct MapGroup {
+    pub start_char_code: u32,
+    pub end_char_code: u32,
+    pub start_glyph_id: u32,
+}
+
+/// Parse the groups from a format 12 or 13 subtable.
+pub fn parse_groups(cmap_data: &[u8], subtable_offset: usize) -> Result<Vec<MapGroup>, ReadError> {
+    // Skip the format and reserved fields, the length which is implied by the group count, and
+    // the language.
+    let mut reader = Reader::at(cmap_data, subtable_offset + 12);
+    read_groups(&mut reader)
+}
+
+/// Read a group count followed by that many groups.
+pub fn read_groups(reader: &mut Reader) -> Result<Vec<MapGroup>, ReadError> {
+    let num_groups = reader.read_u32()?;
+
+    let mut groups = Vec::new();
+    for _ in 0..num_groups {
+        groups.push(MapGroup {
+            start_char_code: reader.read_u32()?,
+            end_char_code: reader.read_u32()?,
+            start_glyph_id: reader.read_u32()?,
+        });
+    }
+
+    Ok(groups)
+}
+
+/// Find the group containing a character. Groups are sorted by character code.
+pub fn find_group(groups: &[MapGroup], codepoint: u32) -> Option<&MapGroup> {
+    let idx = match groups.binary_search_by(|group| group.end_char_code.cmp(&codepoint)) {
+        Ok(idx) => idx,
+        Err(idx) => idx,
+    };
+
+    groups
+        .get(idx)
+        .filter(|group| group.start_char_code <= codepoint)
+}
+
+/// Each group along with the characters in it which no earlier group covers, stopping at the end
+/// of Unicode.
+///
+/// Malformed subtables can have overlapping groups, such as several which each cover all of
+/// Unicode, so each character is only given once.
+pub fn uncovered_char_codes(
+    groups: &[MapGroup],
+) -> impl Iterator<Item = (&MapGroup, RangeInclusive<u32>)> {
+    let mut next = 0;
+    groups.iter().filter_map(move |group| {
+        let start = group.start_char_code.max(next);
+        let end = group.end_char_code.min(MAX_CODEPOINT);
+        if start > end {
+            return None;
+        }
+        next = end + 1;
+        Some((group, start..=end))
+    })
+}
+
+/// The characters in a group which maps them to consecutive glyphs, along with their glyphs.
+pub fn sequential_mappings(
+    group: &MapGroup,
+    char_codes: RangeInclusive<u32>,
+) -> impl Iterator<Item = (u32, u16)> + '_ {
+    char_codes
+        .map_while(move |codepoint| {
+            let glyph = group
+                .start_glyph_id
+                .checked_add(codepoint - group.start_char_code)
+                .filter(|glyph| *glyph <= 0xFFFF)?;
+            Some((codepoint, glyph as u16))
+        })
+        .filter(|(_, glyph)| *glyph != 0)
+}
+
+/// Glyph IDs are stored as 32 bit values but fonts can only hold 65,535 glyphs.
+/// Glyph 0 is the missing glyph.
+pub fn to_glyph_id(glyph: u32) -> Option<u16> {
+    match glyph {
+        0 => None,
+        glyph if glyph <= 0xFFFF => Some(glyph as u16),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps U+0041-U+0043 to glyphs 1-3 and U+1F600-U+1F64F to glyphs 100-179.
+    const SAMPLE_SUBTABLE: [u8; 40] = [
+        0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, // header
+        0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0x43, 0x00, 0x00, 0x00, 0x01, // group 0
+        0x00, 0x01, 0xF6, 0x00, 0x00, 0x01, 0xF6, 0x4F, 0x00, 0x00, 0x00, 0x64, // group 1
+    ];
+
+    #[test]
+    fn lookup_groups() {
+        let subtable = Format12::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x41), Some(1));
+        assert_eq!(subtable.glyph_index(0x43), Some(3));
+        assert_eq!(subtable.glyph_index(0x1F600), Some(100));
+        assert_eq!(subtable.glyph_index(0x1F64F), Some(179));
+    }
+
+    #[test]
+    fn lookup_between_groups() {
+        let subtable = Format12::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x40), None);
+        assert_eq!(subtable.glyph_index(0x44), None);
+        assert_eq!(subtable.glyph_index(0x1F650), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format12::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+        let mappings: Vec<_> = subtable.mappings().collect();
+
+        assert_eq!(mappings.len(), 83);
+        assert_eq!(&mappings[..3], &[(0x41, 1), (0x42, 2), (0x43, 3)]);
+        assert_eq!(mappings[82], (0x1F64F, 179));
+    }
+
+    #[test]
+    fn enumerate_stops_at_last_glyph() {
+        let group = MapGroup {
+            start_char_code: 0x10,
+            end_char_code: 0x10_FFFF,
+            start_glyph_id: 0xFFFE,
+        };
+
+        assert_eq!(
+            sequential_mappings(&group, 0x10..=0x10_FFFF).collect::<Vec<_>>(),
+            vec![(0x10, 0xFFFE), (0x11, 0xFFFF)]
+        );
+    }
+
+    #[test]
+    fn enumerate_overlapping_groups() {
+        // Groups 1 and 3 repeat characters group 0 already covers, and group 2 runs past the
+        // end of Unicode.
+        let group = |start_char_code, end_char_code, start_glyph_id| MapGroup {
+            start_char_code,
+            end_char_code,
+            start_glyph_id,
+        };
+        let subtable = Format12 {
+            groups: vec![
+                group(0x41, 0x43, 1),
+                group(0x00, 0x42, 100),
+                group(0x10_FFFE, 0xFFFF_FFFF, 10),
+                group(0x00, 0xFFFF_FFFF, 200),
+            ],
+        };
+
+        assert_eq!(
+            subtable.mappings().collect::<Vec<_>>(),
+            vec![
+                (0x41, 1),
+                (0x42, 2),
+                (0x43, 3),
+                (0x10_FFFE, 10),
+                (0x10_FFFF, 11)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format12::parse(&SAMPLE_SUBTABLE[..30], 0).unwrap_err(),
+            ParseError::Truncated(28)
+        );
+    }
+}
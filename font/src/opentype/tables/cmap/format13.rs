@@ -0,0 +1,66 @@
+//! Format 13 maps whole ranges of characters to a single glyph. It's used by "last resort" fonts
+//! which show one glyph for every character in a Unicode block.
+
+use super::format12::{find_group, parse_groups, to_glyph_id, uncovered_char_codes, MapGroup};
+use super::ParseError;
+
+#[derive(Debug)]
+pub struct Format13 {
+    groups: Vec<MapGroup>,
+}
+
+impl Format13 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        Ok(Self {
+            groups: parse_groups(cmap_data, subtable_offset)?,
+        })
+    }
+
+    /// Find the glyph for a character. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        find_group(&self.groups, codepoint).and_then(|group| to_glyph_id(group.start_glyph_id))
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        uncovered_char_codes(&self.groups).flat_map(|(group, char_codes)| {
+            to_glyph_id(group.start_glyph_id)
+                .into_iter()
+                .flat_map(move |glyph| char_codes.clone().map(move |codepoint| (codepoint, glyph)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps U+0000-U+007F to glyph 1 and U+0370-U+03FF to glyph 2.
+    const SAMPLE_SUBTABLE: [u8; 40] = [
+        0x00, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, // header
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x00, 0x00, 0x00, 0x01, // group 0
+        0x00, 0x00, 0x03, 0x70, 0x00, 0x00, 0x03, 0xFF, 0x00, 0x00, 0x00, 0x02, // group 1
+    ];
+
+    #[test]
+    fn lookup_many_to_one() {
+        let subtable = Format13::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x00), Some(1));
+        assert_eq!(subtable.glyph_index(0x7F), Some(1));
+        assert_eq!(subtable.glyph_index(0x3B1), Some(2));
+        assert_eq!(subtable.glyph_index(0x80), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format13::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+        let mappings: Vec<_> = subtable.mappings().collect();
+
+        assert_eq!(mappings.len(), 0x80 + 0x90);
+        assert_eq!(mappings[0x7F], (0x7F, 1));
+        assert_eq!(mappings[0x80], (0x370, 2));
+    }
+}
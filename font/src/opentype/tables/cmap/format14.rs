@@ -0,0 +1,220 @@
+//! Format 14 maps Unicode Variation Sequences, a base character followed by a variation selector,
+//! to glyphs.
+//!
+//! Each variation selector has a set of base characters which use their usual glyph from the
+//! font's other subtables (the default UVS table) and a set which use a specific alternate glyph
+//! (the non-default UVS table).
+
+use super::ParseError;
+use opentype::types::Reader;
+
+#[derive(Debug)]
+pub struct Format14 {
+    selectors: Vec<VariationSelector>,
+}
+
+impl Format14 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format and length.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 6);
+        let num_records = reader.read_u32()?;
+
+        let mut selectors = Vec::new();
+        for _ in 0..num_records {
+            let var_selector = reader.read_u24()?;
+            let default_uvs_offset = reader.read_offset32()?;
+            let non_default_uvs_offset = reader.read_offset32()?;
+
+            // A zero offset means the selector has no table of that kind.
+            let default_uvs = match default_uvs_offset {
+                0 => Vec::new(),
+                offset => Self::parse_default_uvs(cmap_data, subtable_offset + offset)?,
+            };
+            let non_default_uvs = match non_default_uvs_offset {
+                0 => Vec::new(),
+                offset => Self::parse_non_default_uvs(cmap_data, subtable_offset + offset)?,
+            };
+
+            selectors.push(VariationSelector {
+                var_selector,
+                default_uvs,
+                non_default_uvs,
+            });
+        }
+
+        Ok(Self { selectors })
+    }
+
+    fn parse_default_uvs(
+        cmap_data: &[u8],
+        table_offset: usize,
+    ) -> Result<Vec<UnicodeRange>, ParseError> {
+        let mut reader = Reader::at(cmap_data, table_offset);
+        let num_ranges = reader.read_u32()?;
+
+        let mut ranges = Vec::new();
+        for _ in 0..num_ranges {
+            ranges.push(UnicodeRange {
+                start_unicode_value: reader.read_u24()?,
+                additional_count: reader.read_u8()?,
+            });
+        }
+
+        Ok(ranges)
+    }
+
+    fn parse_non_default_uvs(
+        cmap_data: &[u8],
+        table_offset: usize,
+    ) -> Result<Vec<UvsMapping>, ParseError> {
+        let mut reader = Reader::at(cmap_data, table_offset);
+        let num_mappings = reader.read_u32()?;
+
+        let mut mappings = Vec::new();
+        for _ in 0..num_mappings {
+            mappings.push(UvsMapping {
+                unicode_value: reader.read_u24()?,
+                glyph_id: reader.read_u16()?,
+            });
+        }
+
+        Ok(mappings)
+    }
+
+    /// Look up the glyph for a base character followed by a variation selector.
+    pub fn glyph_index_variant(&self, base: u32, selector: u32) -> VariantLookup {
+        let record = match self
+            .selectors
+            .binary_search_by(|record| record.var_selector.cmp(&selector))
+        {
+            Ok(idx) => &self.selectors[idx],
+            Err(_) => return VariantLookup::NotSupported,
+        };
+
+        let idx = match record
+            .default_uvs
+            .binary_search_by(|range| range.start_unicode_value.cmp(&base))
+        {
+            Ok(idx) => Some(idx),
+            // The range starting before the character may still extend over it.
+            Err(idx) => idx.checked_sub(1),
+        };
+        if let Some(range) = idx.map(|idx| &record.default_uvs[idx]) {
+            if base - range.start_unicode_value <= range.additional_count as u32 {
+                return VariantLookup::UseDefault;
+            }
+        }
+
+        match record
+            .non_default_uvs
+            .binary_search_by(|mapping| mapping.unicode_value.cmp(&base))
+        {
+            Ok(idx) => VariantLookup::Glyph(record.non_default_uvs[idx].glyph_id),
+            Err(_) => VariantLookup::NotSupported,
+        }
+    }
+}
+
+/// The result of looking up a Unicode Variation Sequence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VariantLookup {
+    /// The sequence is supported and is drawn with the base character's usual glyph.
+    UseDefault,
+    /// The sequence is supported and is drawn with the given glyph.
+    Glyph(u16),
+    /// The font doesn't support the sequence.
+    NotSupported,
+}
+
+#[derive(Debug)]
+struct VariationSelector {
+    var_selector: u32,
+    default_uvs: Vec<UnicodeRange>,
+    non_default_uvs: Vec<UvsMapping>,
+}
+
+#[derive(Debug)]
+struct UnicodeRange {
+    start_unicode_value: u32,
+    additional_count: u8,
+}
+
+#[derive(Debug)]
+struct UvsMapping {
+    unicode_value: u32,
+    glyph_id: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// VS16 (U+FE0F) uses the default glyph for U+2764 and U+2600-U+2604 and glyph 7 for U+263A.
+    /// The Ideographic Variation Selector U+E0100 uses glyph 9 for U+845B.
+    const SAMPLE_SUBTABLE: [u8; 62] = [
+        0x00, 0x0E, 0x00, 0x00, 0x00, 0x3E, 0x00, 0x00, 0x00, 0x02, // header
+        0x00, 0xFE, 0x0F, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x2C, // VS16 record
+        0x0E, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x35, // U+E0100 record
+        0x00, 0x00, 0x00, 0x02, 0x00, 0x26, 0x00, 0x04, 0x00, 0x27, 0x64,
+        0x00, // VS16 default
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x26, 0x3A, 0x00, 0x07, // VS16 non-default
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x84, 0x5B, 0x00, 0x09, // U+E0100 non-default
+    ];
+
+    #[test]
+    fn lookup_default_glyph() {
+        let subtable = Format14::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(
+            subtable.glyph_index_variant(0x2764, 0xFE0F),
+            VariantLookup::UseDefault
+        );
+        assert_eq!(
+            subtable.glyph_index_variant(0x2604, 0xFE0F),
+            VariantLookup::UseDefault
+        );
+    }
+
+    #[test]
+    fn lookup_specific_glyph() {
+        let subtable = Format14::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(
+            subtable.glyph_index_variant(0x263A, 0xFE0F),
+            VariantLookup::Glyph(7)
+        );
+        assert_eq!(
+            subtable.glyph_index_variant(0x845B, 0xE0100),
+            VariantLookup::Glyph(9)
+        );
+    }
+
+    #[test]
+    fn lookup_unsupported() {
+        let subtable = Format14::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(
+            subtable.glyph_index_variant(0x2605, 0xFE0F),
+            VariantLookup::NotSupported
+        );
+        assert_eq!(
+            subtable.glyph_index_variant(0x2764, 0xFE0E),
+            VariantLookup::NotSupported
+        );
+        assert_eq!(
+            subtable.glyph_index_variant(0x2764, 0xE0100),
+            VariantLookup::NotSupported
+        );
+    }
+
+    #[test]
+    fn parse_bad_offset() {
+        let mut data = SAMPLE_SUBTABLE.to_vec();
+        data[20] = 0xFF;
+
+        assert_eq!(
+            Format14::parse(&data, 0).unwrap_err(),
+            ParseError::Truncated(0xFF)
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Format 2 is the high-byte mapping used by Japanese, Chinese and Korean fonts whose encodings
+//! mix one and two byte character codes.
+//!
+//! The high byte of a code selects a sub-header which maps a range of low bytes to glyphs. High
+//! bytes which select the first sub-header are single byte codes in their own right.
+
+use super::ParseError;
+use opentype::types::{Reader, U16};
+
+#[derive(Debug)]
+pub struct Format2 {
+    sub_header_keys: Vec<u16>,
+    sub_headers: Vec<SubHeader>,
+    glyph_id_array: Vec<u16>,
+}
+
+#[derive(Debug)]
+struct SubHeader {
+    first_code: u16,
+    entry_count: u16,
+    id_delta: i16,
+    /// The index in the glyph ID array of the glyph for `first_code`, if the range offset points
+    /// into the array.
+    first_glyph_idx: Option<usize>,
+}
+
+impl Format2 {
+    const SUB_HEADER_KEYS_OFFSET: usize = 6;
+    const SUB_HEADER_SIZE: usize = 8;
+
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, which has already been read.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 2);
+        let length = reader.read_u16()? as usize;
+        // Skip the language.
+        reader.skip(2)?;
+        let sub_header_keys = reader.read_array::<u16, U16>(256)?;
+
+        // Keys are byte offsets into the sub-headers, so the largest gives the number of them.
+        let num_sub_headers = sub_header_keys
+            .iter()
+            .map(|key| *key as usize / Self::SUB_HEADER_SIZE)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let sub_headers_start = Self::SUB_HEADER_KEYS_OFFSET + 512;
+        let glyph_id_array_start = sub_headers_start + num_sub_headers * Self::SUB_HEADER_SIZE;
+
+        let mut sub_headers = Vec::new();
+        for idx in 0..num_sub_headers {
+            let first_code = reader.read_u16()?;
+            let entry_count = reader.read_u16()?;
+            let id_delta = reader.read_i16()?;
+            // Like format 4, the range offset is relative to its own position.
+            let range_offset_position = sub_headers_start + idx * Self::SUB_HEADER_SIZE + 6;
+            let id_range_offset = reader.read_u16()? as usize;
+            let first_glyph_idx = (range_offset_position + id_range_offset)
+                .checked_sub(glyph_id_array_start)
+                .map(|offset| offset / 2);
+
+            sub_headers.push(SubHeader {
+                first_code,
+                entry_count,
+                id_delta,
+                first_glyph_idx,
+            });
+        }
+
+        // The glyph ID array fills the rest of the subtable.
+        let subtable_end = (subtable_offset + length).min(cmap_data.len());
+        let glyph_id_count = subtable_end.saturating_sub(reader.offset()) / 2;
+        let glyph_id_array = reader.read_array::<u16, U16>(glyph_id_count)?;
+
+        Ok(Self {
+            sub_header_keys,
+            sub_headers,
+            glyph_id_array,
+        })
+    }
+
+    /// Find the glyph for a one or two byte character code. Returns `None` if the character maps
+    /// to the missing glyph.
+    pub fn glyph_index(&self, code: u32) -> Option<u16> {
+        if code > 0xFFFF {
+            return None;
+        }
+        let high_byte = (code >> 8) as usize;
+        let low_byte = (code & 0xFF) as u16;
+
+        let sub_header_idx = if high_byte == 0 {
+            // A single byte code must be one which doesn't start a two byte code.
+            match self.sub_header_keys[low_byte as usize] {
+                0 => 0,
+                _ => return None,
+            }
+        } else {
+            // Two byte codes must not use the single byte sub-header.
+            match self.sub_header_keys[high_byte] as usize / Self::SUB_HEADER_SIZE {
+                0 => return None,
+                idx => idx,
+            }
+        };
+        let sub_header = self.sub_headers.get(sub_header_idx)?;
+
+        let entry = low_byte.checked_sub(sub_header.first_code)?;
+        if entry >= sub_header.entry_count {
+            return None;
+        }
+        let idx = sub_header.first_glyph_idx? + entry as usize;
+        let glyph = match *self.glyph_id_array.get(idx)? {
+            0 => 0,
+            glyph => glyph.wrapping_add(sub_header.id_delta as u16),
+        };
+
+        match glyph {
+            0 => None,
+            glyph => Some(glyph),
+        }
+    }
+
+    /// Every character which maps to a glyph, in order of their codes.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        (0..256u32)
+            .flat_map(move |byte| match self.sub_header_keys[byte as usize] {
+                0 => byte..=byte,
+                _ => byte << 8..=byte << 8 | 0xFF,
+            })
+            .filter_map(move |code| self.glyph_index(code).map(|glyph| (code, glyph)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single byte codes 'A'-'B' map to glyphs 1-2. High byte 0x81 starts two byte codes, of which
+    /// 0x8140-0x8142 map to glyphs 11, 0 (missing) and 13.
+    fn sample_subtable() -> Vec<u8> {
+        let mut data = vec![0x00, 0x02, 0x02, 0x20, 0x00, 0x00];
+        let mut keys = vec![0x00; 512];
+        keys[0x81 * 2 + 1] = 0x08;
+        data.extend(keys);
+        data.extend(&[
+            0x00, 0x41, 0x00, 0x02, 0x00, 0x00, 0x00, 0x0A, // sub-header 0
+            0x00, 0x40, 0x00, 0x03, 0x00, 0x0A, 0x00, 0x06, // sub-header 1
+            0x00, 0x01, 0x00, 0x02, // glyphs for sub-header 0
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x03, // glyphs for sub-header 1
+        ]);
+        data
+    }
+
+    #[test]
+    fn lookup_single_byte() {
+        let subtable = Format2::parse(&sample_subtable(), 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('A' as u32), Some(1));
+        assert_eq!(subtable.glyph_index('B' as u32), Some(2));
+        assert_eq!(subtable.glyph_index('C' as u32), None);
+        // The lead byte of a two byte code isn't a character by itself.
+        assert_eq!(subtable.glyph_index(0x81), None);
+    }
+
+    #[test]
+    fn lookup_two_byte() {
+        let subtable = Format2::parse(&sample_subtable(), 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x8140), Some(11));
+        assert_eq!(subtable.glyph_index(0x8141), None);
+        assert_eq!(subtable.glyph_index(0x8142), Some(13));
+        assert_eq!(subtable.glyph_index(0x8143), None);
+        // 0x41 is a single byte code so can't lead a two byte code.
+        assert_eq!(subtable.glyph_index(0x4141), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format2::parse(&sample_subtable(), 0).unwrap();
+
+        assert_eq!(
+            subtable.mappings().collect::<Vec<_>>(),
+            vec![('A' as u32, 1), ('B' as u32, 2), (0x8140, 11), (0x8142, 13)]
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format2::parse(&sample_subtable()[..520], 0).unwrap_err(),
+            ParseError::Truncated(520)
+        );
+    }
+}
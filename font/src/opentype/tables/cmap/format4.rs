@@ -0,0 +1,198 @@
+//! Format 4 is the standard mapping subtable for fonts which only support characters in the
+//! Unicode Basic Multilingual Plane.
+//!
+//! Characters are divided into contiguous segments. Each segment either maps its characters to
+//! glyphs by adding a fixed delta, or indexes into a shared array of glyph IDs.
+
+use super::ParseError;
+use opentype::types::{Reader, I16, U16};
+
+#[derive(Debug)]
+pub struct Format4 {
+    end_codes: Vec<u16>,
+    start_codes: Vec<u16>,
+    id_deltas: Vec<i16>,
+    id_range_offsets: Vec<u16>,
+    glyph_id_array: Vec<u16>,
+}
+
+impl Format4 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, which has already been read.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 2);
+        let length = reader.read_u16()? as usize;
+        // Skip the language.
+        reader.skip(2)?;
+        let seg_count = reader.read_u16()? as usize / 2;
+        // Skip searchRange, entrySelector and rangeShift which are only useful for a binary
+        // search over the raw data.
+        reader.skip(6)?;
+
+        let end_codes = reader.read_array::<u16, U16>(seg_count)?;
+        // Skip reservedPad.
+        reader.skip(2)?;
+        let start_codes = reader.read_array::<u16, U16>(seg_count)?;
+        let id_deltas = reader.read_array::<i16, I16>(seg_count)?;
+        let id_range_offsets = reader.read_array::<u16, U16>(seg_count)?;
+
+        // The glyph ID array fills the rest of the subtable. Some fonts give a length which
+        // overruns the cmap table, so stop at whichever ends first.
+        let subtable_end = (subtable_offset + length).min(cmap_data.len());
+        let glyph_id_count = subtable_end.saturating_sub(reader.offset()) / 2;
+        let glyph_id_array = reader.read_array::<u16, U16>(glyph_id_count)?;
+
+        Ok(Self {
+            end_codes,
+            start_codes,
+            id_deltas,
+            id_range_offsets,
+            glyph_id_array,
+        })
+    }
+
+    /// Find the glyph for a character. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        if codepoint > 0xFFFF {
+            return None;
+        }
+        let c = codepoint as u16;
+
+        // Segments are sorted by end code so the first segment ending at or after the character
+        // is the only one which can contain it.
+        let segment = match self.end_codes.binary_search(&c) {
+            Ok(idx) => idx,
+            Err(idx) => idx,
+        };
+        let start = *self.start_codes.get(segment)?;
+        if c < start {
+            return None;
+        }
+        let delta = self.id_deltas[segment] as u16;
+        let range_offset = self.id_range_offsets[segment] as usize;
+
+        let glyph = if range_offset == 0 {
+            c.wrapping_add(delta)
+        } else {
+            // The range offset is relative to its own position in the idRangeOffset array, which
+            // is immediately followed by the glyph ID array.
+            let seg_count = self.id_range_offsets.len();
+            let idx = (range_offset / 2 + (c - start) as usize).checked_sub(seg_count - segment)?;
+            match *self.glyph_id_array.get(idx)? {
+                0 => 0,
+                glyph => glyph.wrapping_add(delta),
+            }
+        };
+
+        match glyph {
+            0 => None,
+            glyph => Some(glyph),
+        }
+    }
+
+    /// Every character which maps to a glyph, in order.
+    ///
+    /// Malformed subtables can have overlapping segments, so characters an earlier segment
+    /// already covered are skipped and each character is only visited once.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        let mut next = 0;
+        self.start_codes
+            .iter()
+            .zip(self.end_codes.iter())
+            .flat_map(move |(start, end)| {
+                let start = u32::from(*start).max(next);
+                let end = u32::from(*end);
+                next = next.max(end + 1);
+                start..=end
+            })
+            .filter_map(move |c| self.glyph_index(c).map(|glyph| (c, glyph)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps 'A'-'C' by delta to glyphs 1-3 and 'x'-'z' through the glyph ID array to glyphs
+    /// 10, 0 (missing) and 12.
+    const SAMPLE_SUBTABLE: [u8; 46] = [
+        0x00, 0x04, 0x00, 0x2E, 0x00, 0x00, 0x00, 0x06, 0x00, 0x04, 0x00, 0x01, 0x00,
+        0x02, // header
+        0x00, 0x43, 0x00, 0x7A, 0xFF, 0xFF, // endCode
+        0x00, 0x00, // reservedPad
+        0x00, 0x41, 0x00, 0x78, 0xFF, 0xFF, // startCode
+        0xFF, 0xC0, 0x00, 0x00, 0x00, 0x01, // idDelta
+        0x00, 0x00, 0x00, 0x04, 0x00, 0x00, // idRangeOffset
+        0x00, 0x0A, 0x00, 0x00, 0x00, 0x0C, // glyphIdArray
+    ];
+
+    #[test]
+    fn lookup_by_delta() {
+        let subtable = Format4::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('A' as u32), Some(1));
+        assert_eq!(subtable.glyph_index('C' as u32), Some(3));
+        assert_eq!(subtable.glyph_index('D' as u32), None);
+    }
+
+    #[test]
+    fn lookup_by_glyph_id_array() {
+        let subtable = Format4::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('x' as u32), Some(10));
+        assert_eq!(subtable.glyph_index('y' as u32), None);
+        assert_eq!(subtable.glyph_index('z' as u32), Some(12));
+    }
+
+    #[test]
+    fn lookup_outside_segments() {
+        let subtable = Format4::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index(0x20), None);
+        assert_eq!(subtable.glyph_index(0xFFFF), None);
+        assert_eq!(subtable.glyph_index(0x1F600), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format4::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(
+            subtable.mappings().collect::<Vec<_>>(),
+            vec![
+                ('A' as u32, 1),
+                ('B' as u32, 2),
+                ('C' as u32, 3),
+                ('x' as u32, 10),
+                ('z' as u32, 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn enumerate_overlapping_segments() {
+        // Three segments which each cover the whole BMP by delta, followed by the final segment.
+        let mut subtable = vec![
+            0x00, 0x04, 0x00, 0x30, 0x00, 0x00, 0x00, 0x08, 0x00, 0x08, 0x00, 0x02, 0x00, 0x00,
+        ];
+        subtable.extend_from_slice(&[0xFF, 0xFE, 0xFF, 0xFE, 0xFF, 0xFE, 0xFF, 0xFF]);
+        subtable.extend_from_slice(&[0x00, 0x00]);
+        subtable.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF]);
+        subtable.extend_from_slice(&[0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01]);
+        subtable.extend_from_slice(&[0x00; 8]);
+        let subtable = Format4::parse(&subtable, 0).unwrap();
+        let mappings: Vec<_> = subtable.mappings().collect();
+
+        assert_eq!(mappings.len(), 0xFFFF);
+        assert_eq!(mappings[0], (0, 1));
+        assert_eq!(mappings[0xFFFE], (0xFFFE, 0xFFFF));
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format4::parse(&SAMPLE_SUBTABLE[..19], 0).unwrap_err(),
+            ParseError::Truncated(18)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Format 6 maps a single contiguous range of 16 bit character codes to glyphs.
+
+use super::ParseError;
+use opentype::types::{Reader, U16};
+
+#[derive(Debug)]
+pub struct Format6 {
+    first_code: u16,
+    glyph_id_array: Vec<u16>,
+}
+
+impl Format6 {
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, length and language.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 6);
+        let first_code = reader.read_u16()?;
+        let entry_count = reader.read_u16()? as usize;
+        let glyph_id_array = reader.read_array::<u16, U16>(entry_count)?;
+
+        Ok(Self {
+            first_code,
+            glyph_id_array,
+        })
+    }
+
+    /// Find the glyph for a character code. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, code: u32) -> Option<u16> {
+        let idx = code.checked_sub(u32::from(self.first_code))?;
+        match *self.glyph_id_array.get(idx as usize)? {
+            0 => None,
+            glyph => Some(glyph),
+        }
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        let first_code = u32::from(self.first_code);
+        self.glyph_id_array
+            .iter()
+            .enumerate()
+            .filter(|(_, glyph)| **glyph != 0)
+            .map(move |(idx, glyph)| (first_code + idx as u32, *glyph))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps 'a'-'c' to glyphs 5, 0 (missing) and 7.
+    const SAMPLE_SUBTABLE: [u8; 16] = [
+        0x00, 0x06, 0x00, 0x10, 0x00, 0x00, 0x00, 0x61, 0x00, 0x03, // header
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x07, // glyphIdArray
+    ];
+
+    #[test]
+    fn lookup() {
+        let subtable = Format6::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('a' as u32), Some(5));
+        assert_eq!(subtable.glyph_index('b' as u32), None);
+        assert_eq!(subtable.glyph_index('c' as u32), Some(7));
+        assert_eq!(subtable.glyph_index('d' as u32), None);
+        assert_eq!(subtable.glyph_index('`' as u32), None);
+    }
+
+    #[test]
+    fn enumerate_mappings() {
+        let subtable = Format6::parse(&SAMPLE_SUBTABLE, 0).unwrap();
+
+        assert_eq!(
+            subtable.mappings().collect::<Vec<_>>(),
+            vec![('a' as u32, 5), ('c' as u32, 7)]
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format6::parse(&SAMPLE_SUBTABLE[..15], 0).unwrap_err(),
+            ParseError::Truncated(14)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Format 8 maps a mix of 16 and 32 bit character codes, as used by some early attempts at
+//! supplementary plane support. The `is32` bitmap only matters when decoding a byte stream;
+//! lookups by a whole character code only need the groups.
+
+use super::format12::{
+    find_group, read_groups, sequential_mappings, to_glyph_id, uncovered_char_codes, MapGroup,
+};
+use super::ParseError;
+use opentype::types::Reader;
+
+#[derive(Debug)]
+pub struct Format8 {
+    groups: Vec<MapGroup>,
+}
+
+impl Format8 {
+    const IS32_LENGTH: usize = 8192;
+
+    pub fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        // Skip the format, reserved field, length and language.
+        let mut reader = Reader::at(cmap_data, subtable_offset + 12);
+        reader.skip(Self::IS32_LENGTH)?;
+        let groups = read_groups(&mut reader)?;
+
+        Ok(Self { groups })
+    }
+
+    /// Find the glyph for a character. Returns `None` if the character maps to the missing
+    /// glyph.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        find_group(&self.groups, codepoint).and_then(|group| {
+            let offset = codepoint - group.start_char_code;
+            group
+                .start_glyph_id
+                .checked_add(offset)
+                .and_then(to_glyph_id)
+        })
+    }
+
+    /// Every character which maps to a glyph, in order.
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, u16)> + '_ {
+        uncovered_char_codes(&self.groups)
+            .flat_map(|(group, char_codes)| sequential_mappings(group, char_codes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maps 'A'-'Z' to glyphs 1-26 and U+10000-U+1000F to glyphs 30-45.
+    fn sample_subtable() -> Vec<u8> {
+        let mut data = vec![
+            0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x20, 0x28, 0x00, 0x00, 0x00, 0x00,
+        ];
+        let mut is32 = vec![0x00; Format8::IS32_LENGTH];
+        // The high word 0x0001 isn't a 16 bit character code, so it starts a 32 bit one.
+        is32[0] = 0x40;
+        data.extend(is32);
+        data.extend(&[
+            0x00, 0x00, 0x00, 0x02, // numGroups
+            0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0x5A, 0x00, 0x00, 0x00, 0x01, // group 0
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x1E, // group 1
+        ]);
+        data
+    }
+
+    #[test]
+    fn lookup() {
+        let subtable = Format8::parse(&sample_subtable(), 0).unwrap();
+
+        assert_eq!(subtable.glyph_index('A' as u32), Some(1));
+        assert_eq!(subtable.glyph_index('Z' as u32), Some(26));
+        assert_eq!(subtable.glyph_index(0x1000F), Some(45));
+        assert_eq!(subtable.glyph_index(0x10010), None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            Format8::parse(&sample_subtable()[..100], 0).unwrap_err(),
+            ParseError::Truncated(12)
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! The cmap table is a map from character codes to glyphs or, more accurately, indexes in the
+//! glyf table.
+
+mod format0;
+mod format10;
+mod format12;
+mod format13;
+mod format14;
+mod format2;
+mod format4;
+mod format6;
+mod format8;
+
+use self::format0::Format0;
+use self::format10::Format10;
+use self::format12::Format12;
+use self::format13::Format13;
+use self::format14::Format14;
+pub use self::format14::VariantLookup;
+use self::format2::Format2;
+use self::format4::Format4;
+use self::format6::Format6;
+use self::format8::Format8;
+use opentype::encoding::Encoding;
+use opentype::platform::Platform;
+use opentype::types::{DataType, ReadError, Reader, U16};
+use std::iter;
+use strings::AppleRoman;
+
+/// An iterator over characters and the glyphs they map to.
+pub type Mappings<'a> = Box<dyn Iterator<Item = (u32, u16)> + 'a>;
+
+#[derive(Debug)]
+pub struct CmapTable {
+    table_version: Version,
+    num_tables: u16,
+    encoding_records: Vec<EncodingRecord>,
+    subtables: Vec<Subtable>,
+}
+
+impl CmapTable {
+    const VERSION_OFFSET: usize = 0;
+    const NUM_TABLES_OFFSET: usize = 2;
+    const ENCODING_RECORDS_OFFSET: usize = 4;
+
+    /// Encodings which can be queried directly by Unicode codepoint, most preferred first.
+    /// Full repertoire encodings come first so that characters outside the BMP can be found.
+    const ENCODING_PREFERENCE: [Encoding; 7] = [
+        Encoding::WindowsUnicodeUCS4,
+        Encoding::Unicode2Full,
+        Encoding::UnicodeFull,
+        Encoding::WindowsUnicodeBMP,
+        Encoding::Unicode2BMP,
+        Encoding::Unicode11,
+        Encoding::Unicode1,
+    ];
+
+    pub fn deserialize(data: &[u8]) -> Result<Self, ParseError> {
+        let num_tables = U16::extract(data, Self::NUM_TABLES_OFFSET)?;
+        let encoding_records = Self::parse_encoding_records(data, num_tables)?;
+        let subtables = Self::parse_subtables(data, &encoding_records)?;
+
+        Ok(CmapTable {
+            table_version: Self::parse_version(data)?,
+            num_tables,
+            encoding_records,
+            subtables,
+        })
+    }
+
+    /// Find the glyph for a Unicode codepoint using the most suitable subtable in the font.
+    /// Returns `None` if the font has no glyph for the character.
+    ///
+    /// Fonts without a Unicode subtable are searched through their Mac OS Roman subtable, if they
+    /// have one.
+    pub fn glyph_index(&self, codepoint: u32) -> Option<u16> {
+        match self.preferred_subtable() {
+            Some(subtable) => subtable.glyph_index(codepoint),
+            None => {
+                let code = AppleRoman::encode_codepoint(codepoint)?;
+                self.subtable_for(Encoding::MacintoshRoman)
+                    .and_then(|subtable| subtable.glyph_index(u32::from(code)))
+            }
+        }
+    }
+
+    /// Whether the font has a Unicode subtable, rather than only ones for legacy encodings.
+    pub fn has_unicode_subtable(&self) -> bool {
+        self.preferred_subtable().is_some()
+    }
+
+    /// Every Unicode codepoint which maps to a glyph, along with its glyph, from the same subtable
+    /// used by `glyph_index`.
+    pub fn mappings(&self) -> Mappings<'_> {
+        if let Some(subtable) = self.preferred_subtable() {
+            return subtable.mappings();
+        }
+
+        match self.subtable_for(Encoding::MacintoshRoman) {
+            Some(subtable) => Box::new(
+                subtable
+                    .mappings()
+                    .filter(|(code, _)| *code <= 0xFF)
+                    .map(|(code, glyph)| (AppleRoman::decode_byte(code as u8), glyph)),
+            ),
+            None => Box::new(iter::empty()),
+        }
+    }
+
+    /// Every Unicode codepoint which maps to a glyph, in ascending order.
+    pub fn codepoints_for_glyph(&self, glyph_id: u16) -> Vec<u32> {
+        let mut codepoints: Vec<u32> = self
+            .mappings()
+            .filter(|(_, glyph)| *glyph == glyph_id)
+            .map(|(codepoint, _)| codepoint)
+            .collect();
+        codepoints.sort_unstable();
+        codepoints
+    }
+
+    /// Find the glyph for a Unicode Variation Sequence: a base character followed by a variation
+    /// selector.
+    pub fn glyph_index_variant(&self, base: u32, selector: u32) -> VariantLookup {
+        self.subtables
+            .iter()
+            .filter_map(|subtable| match subtable {
+                Subtable::Format14(subtable) => Some(subtable),
+                _ => None,
+            })
+            .next()
+            .map_or(VariantLookup::NotSupported, |subtable| {
+                subtable.glyph_index_variant(base, selector)
+            })
+    }
+
+    fn preferred_subtable(&self) -> Option<&Subtable> {
+        Self::ENCODING_PREFERENCE
+            .iter()
+            .filter_map(|encoding| self.subtable_for(*encoding))
+            .next()
+    }
+
+    /// The first subtable for an encoding which can be used for lookups.
+    fn subtable_for(&self, encoding: Encoding) -> Option<&Subtable> {
+        self.encoding_records
+            .iter()
+            .zip(self.subtables.iter())
+            .find(|(record, subtable)| record.encoding == encoding && subtable.supports_lookup())
+            .map(|(_, subtable)| subtable)
+    }
+
+    fn parse_version(data: &[u8]) -> Result<Version, ParseError> {
+        match U16::extract(data, Self::VERSION_OFFSET)? {
+            0 => Ok(Version::Version0),
+            _ => Err(ParseError::UnknownVersion),
+        }
+    }
+
+    fn parse_encoding_records(
+        data: &[u8],
+        num_tables: u16,
+    ) -> Result<Vec<EncodingRecord>, ParseError> {
+        let mut records: Vec<EncodingRecord> = vec![];
+
+        let mut reader = Reader::at(data, Self::ENCODING_RECORDS_OFFSET);
+        for _ in 0..num_tables {
+            records.push(EncodingRecord::parse(&mut reader)?);
+        }
+
+        Ok(records)
+    }
+
+    fn parse_subtables(
+        data: &[u8],
+        encoding_records: &Vec<EncodingRecord>,
+    ) -> Result<Vec<Subtable>, ParseError> {
+        let mut subtables: Vec<Subtable> = vec![];
+
+        for r in encoding_records {
+            subtables.push(Subtable::parse(data, r.offset)?);
+        }
+
+        Ok(subtables)
+    }
+}
+
+#[derive(Debug)]
+struct EncodingRecord {
+    platform: Platform,
+    encoding: Encoding,
+    offset: usize,
+}
+
+impl EncodingRecord {
+    fn parse(reader: &mut Reader) -> Result<Self, ParseError> {
+        let platform = match Platform::lookup(reader.read_u16()?) {
+            None => return Err(ParseError::UnknownPlatform),
+            Some(p) => p,
+        };
+
+        let encoding = match Encoding::lookup(platform, reader.read_u16()?) {
+            None => return Err(ParseError::UnknownEncoding),
+            Some(e) => e,
+        };
+
+        Ok(Self {
+            platform,
+            encoding,
+            offset: reader.read_offset32()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Subtable {
+    /// Format 0: Byte encoding table
+    Format0(Format0),
+    /// Format 2: High-byte mapping through table
+    Format2(Format2),
+    /// Format 4: Segment mapping to delta values
+    Format4(Format4),
+    /// Format 6: Trimmed table mapping
+    Format6(Format6),
+    /// Format 8: mixed 16-bit and 32-bit coverage
+    Format8(Format8),
+    /// Format 10: Trimmed array
+    Format10(Format10),
+    /// Format 12: Segmented coverage
+    Format12(Format12),
+    /// Format 13: Many-to-one range mappings
+    Format13(Format13),
+    /// Format 14: Unicode Variation Sequences
+    Format14(Format14),
+}
+
+impl Subtable {
+    // This parser needs to take the entire cmap table because we don't actually know the length of the subtable until we detect the format.
+    fn parse(cmap_data: &[u8], subtable_offset: usize) -> Result<Self, ParseError> {
+        match U16::extract(cmap_data, subtable_offset)? {
+            0 => Ok(Subtable::Format0(Format0::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            2 => Ok(Subtable::Format2(Format2::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            4 => Ok(Subtable::Format4(Format4::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            6 => Ok(Subtable::Format6(Format6::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            8 => Ok(Subtable::Format8(Format8::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            10 => Ok(Subtable::Format10(Format10::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            12 => Ok(Subtable::Format12(Format12::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            13 => Ok(Subtable::Format13(Format13::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            14 => Ok(Subtable::Format14(Format14::parse(
+                cmap_data,
+                subtable_offset,
+            )?)),
+            _ => Err(ParseError::UnknownSubtableFormat),
+        }
+    }
+
+    /// Whether single characters can be looked up in this subtable. Format 14 only maps
+    /// variation sequences.
+    fn supports_lookup(&self) -> bool {
+        !matches!(self, Subtable::Format14(_))
+    }
+
+    /// Find the glyph for a character code in the subtable's own encoding.
+    fn glyph_index(&self, code: u32) -> Option<u16> {
+        match self {
+            Subtable::Format0(subtable) => subtable.glyph_index(code),
+            Subtable::Format2(subtable) => subtable.glyph_index(code),
+            Subtable::Format4(subtable) => subtable.glyph_index(code),
+            Subtable::Format6(subtable) => subtable.glyph_index(code),
+            Subtable::Format8(subtable) => subtable.glyph_index(code),
+            Subtable::Format10(subtable) => subtable.glyph_index(code),
+            Subtable::Format12(subtable) => subtable.glyph_index(code),
+            Subtable::Format13(subtable) => subtable.glyph_index(code),
+            Subtable::Format14(_) => None,
+        }
+    }
+
+    /// Every character code in the subtable's own encoding which maps to a glyph.
+    fn mappings(&self) -> Mappings<'_> {
+        match self {
+            Subtable::Format0(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format2(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format4(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format6(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format8(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format10(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format12(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format13(subtable) => Box::new(subtable.mappings()),
+            Subtable::Format14(_) => Box::new(iter::empty()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    UnknownVersion,
+    UnknownPlatform,
+    UnknownEncoding,
+    UnknownSubtableFormat,
+    /// A read at the given offset ran past the end of the table.
+    Truncated(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Version {
+    /// V0 is, in fact, the only documented version so far.
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap
+    Version0,
+}
+
+#[cfg(test)]
+mod cmap_tests {
+    use super::*;
+    use opentype::tables::test_data::sample_table;
+
+    #[test]
+    fn parse_version_0() {
+        let mut content = vec![0x00u8; 100];
+        content[..2].clone_from_slice(&[0x00u8, 0x00]);
+        assert_eq!(CmapTable::parse_version(&content), Ok(Version::Version0));
+    }
+
+    #[test]
+    fn parse_version_unknown() {
+        let mut content = vec![0x00u8; 100];
+        content[..2].clone_from_slice(&[0x00u8, 0x01]);
+        assert_eq!(
+            CmapTable::parse_version(&content),
+            Err(ParseError::UnknownVersion)
+        );
+    }
+
+    #[test]
+    fn deserialize_sample_table() {
+        let table = CmapTable::deserialize(sample_table(b"cmap")).unwrap();
+
+        assert_eq!(table.num_tables, 3);
+        assert_eq!(table.subtables.len(), 3);
+    }
+
+    #[test]
+    fn prefer_windows_unicode_bmp() {
+        // Unicode 2.0 BMP and Windows Unicode BMP records which map 'A' to glyphs 1 and 2.
+        let mut data = vec![
+            0x00u8, 0x00, 0x00, 0x02, // header
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x14, // Unicode 2.0 BMP record
+            0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x34, // Windows Unicode BMP record
+        ];
+        for delta in [0xFFC0u16, 0xFFC1].iter() {
+            let delta = delta.to_be_bytes();
+            data.extend_from_slice(&[
+                0x00, 0x04, 0x00, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00, 0x04, 0x00, 0x01, 0x00, 0x00,
+                0x00, 0x41, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x41, 0xFF, 0xFF, delta[0], delta[1],
+                0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+            ]);
+        }
+        let table = CmapTable::deserialize(&data).unwrap();
+
+        assert_eq!(table.glyph_index('A' as u32), Some(2));
+        assert_eq!(table.glyph_index('B' as u32), None);
+    }
+
+    #[test]
+    fn prefer_full_repertoire() {
+        // A Windows Unicode BMP record mapping 'A' to glyph 1 and a Windows UCS-4 record mapping
+        // 'A' to glyph 2 and U+1F600 to glyph 3.
+        let mut data = vec![
+            0x00u8, 0x00, 0x00, 0x02, // header
+            0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x14, // Windows Unicode BMP record
+            0x00, 0x03, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x34, // Windows UCS-4 record
+        ];
+        data.extend_from_slice(&[
+            0x00, 0x04, 0x00, 0x20, 0x00, 0x00, 0x00, 0x04, 0x00, 0x04, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x41, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x41, 0xFF, 0xFF, 0xFF, 0xC0, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        data.extend_from_slice(&[
+            0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x02, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0x41, 0x00, 0x00, 0x00, 0x02,
+            0x00, 0x01, 0xF6, 0x00, 0x00, 0x01, 0xF6, 0x00, 0x00, 0x00, 0x00, 0x03,
+        ]);
+        let table = CmapTable::deserialize(&data).unwrap();
+
+        assert_eq!(table.glyph_index('A' as u32), Some(2));
+        assert_eq!(table.glyph_index(0x1F600), Some(3));
+    }
+
+    #[test]
+    fn lookup_through_mac_roman() {
+        // A single Macintosh Roman record whose format 0 subtable maps 'A' to glyph 1 and 0x8E,
+        // which is U+00E9 in Mac OS Roman, to glyph 2.
+        let mut data = vec![
+            0x00u8, 0x00, 0x00, 0x01, // header
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, // Macintosh Roman record
+            0x00, 0x00, 0x01, 0x06, 0x00, 0x00, // subtable header
+        ];
+        let mut glyph_ids = [0x00u8; 256];
+        glyph_ids[0x41] = 1;
+        glyph_ids[0x8E] = 2;
+        data.extend_from_slice(&glyph_ids);
+        let table = CmapTable::deserialize(&data).unwrap();
+
+        assert_eq!(table.glyph_index('A' as u32), Some(1));
+        assert_eq!(table.glyph_index(0xE9), Some(2));
+        assert_eq!(table.glyph_index(0x8E), None);
+        assert_eq!(table.glyph_index(0x1F600), None);
+
+        assert_eq!(
+            table.mappings().collect::<Vec<_>>(),
+            vec![('A' as u32, 1), (0xE9, 2)]
+        );
+    }
+
+    #[test]
+    fn reverse_lookup() {
+        // A Windows Unicode BMP record whose format 6 subtable maps 'a' and 'c' to glyph 5 and 'b'
+        // to glyph 6.
+        let data = [
+            0x00u8, 0x00, 0x00, 0x01, // header
+            0x00, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0C, // Windows Unicode BMP record
+            0x00, 0x06, 0x00, 0x10, 0x00, 0x00, 0x00, 0x61, 0x00, 0x03, // subtable header
+            0x00, 0x05, 0x00, 0x06, 0x00, 0x05, // glyphIdArray
+        ];
+        let table = CmapTable::deserialize(&data).unwrap();
+
+        assert_eq!(table.codepoints_for_glyph(5), vec!['a' as u32, 'c' as u32]);
+        assert_eq!(table.codepoints_for_glyph(6), vec!['b' as u32]);
+        assert!(table.codepoints_for_glyph(0).is_empty());
+        assert!(table.codepoints_for_glyph(7).is_empty());
+    }
+
+    #[test]
+    fn lookup_sample_table() {
+        // The sample font's only Unicode subtable maps nothing but the final 0xFFFF segment.
+        let table = CmapTable::deserialize(sample_table(b"cmap")).unwrap();
+
+        assert_eq!(table.glyph_index('A' as u32), None);
+        assert_eq!(table.glyph_index(0xFFFF), None);
+    }
+
+    #[test]
+    fn deserialize_truncated_table() {
+        let data = sample_table(b"cmap");
+
+        assert_eq!(
+            CmapTable::deserialize(&data[..1]).unwrap_err(),
+            ParseError::Truncated(2)
+        );
+        assert_eq!(
+            CmapTable::deserialize(&data[..10]).unwrap_err(),
+            ParseError::Truncated(8)
+        );
+        // The first subtable's header runs past the end of the truncated data.
+        assert_eq!(
+            CmapTable::deserialize(&data[..40]).unwrap_err(),
+            ParseError::Truncated(36)
+        );
+        // The last subtable starts beyond the end of the truncated data.
+        assert_eq!(
+            CmapTable::deserialize(&data[..52]).unwrap_err(),
+            ParseError::Truncated(52)
+        );
+    }
+}
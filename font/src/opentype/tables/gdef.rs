@@ -0,0 +1,291 @@
+//! The glyph definition table classifies glyphs for the layout tables, so that lookups can tell
+//! marks from the glyphs they attach to, and gives the caret positions within ligatures.
+
+use opentype::tables::layout::{ClassDef, Coverage, ParseError};
+use opentype::types::{Offset16, Offset32, Reader, U16};
+
+#[derive(Debug)]
+pub struct GdefTable {
+    glyph_classes: Option<ClassDef>,
+    attach_list: Option<AttachList>,
+    lig_caret_list: Option<LigCaretList>,
+    mark_attach_classes: Option<ClassDef>,
+    /// From version 1.2.
+    mark_glyph_sets: Vec<Coverage>,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum GlyphClass {
+    /// A glyph which marks attach to, such as a letter.
+    Base,
+    /// A glyph standing for several characters, such as "ffi".
+    Ligature,
+    /// A glyph which attaches to another, such as an accent.
+    Mark,
+    /// Part of a glyph which is drawn as several pieces.
+    Component,
+}
+
+/// A caret position within a ligature.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CaretValue {
+    /// An x coordinate, in font units. For vertical text it is a y coordinate.
+    Coordinate(i16),
+    /// The index of a point in the glyph's outline, whose x coordinate gives the position once the
+    /// glyph has been hinted.
+    ContourPoint(u16),
+}
+
+#[derive(Debug)]
+struct AttachList {
+    coverage: Coverage,
+    /// The contour points of each covered glyph which marks can attach to.
+    point_indices: Vec<Vec<u16>>,
+}
+
+#[derive(Debug)]
+struct LigCaretList {
+    coverage: Coverage,
+    /// The caret positions of each covered ligature, in increasing order.
+    carets: Vec<Vec<CaretValue>>,
+}
+
+impl GdefTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(table_data);
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(ParseError::UnknownVersion);
+        }
+
+        let glyph_class_def_offset = reader.read::<usize, Offset16>()?;
+        let attach_list_offset = reader.read::<usize, Offset16>()?;
+        let lig_caret_list_offset = reader.read::<usize, Offset16>()?;
+        let mark_attach_class_def_offset = reader.read::<usize, Offset16>()?;
+        let mark_glyph_sets_def_offset = if minor_version >= 2 {
+            reader.read::<usize, Offset16>()?
+        } else {
+            0
+        };
+        // Version 1.3 adds an item variation store, which is only needed by variable fonts.
+
+        // A null offset means the font doesn't have that part of the table.
+        let class_def = |offset| match offset {
+            0 => Ok(None),
+            offset => ClassDef::parse(table_data, offset).map(Some),
+        };
+        let glyph_classes = class_def(glyph_class_def_offset)?;
+        let mark_attach_classes = class_def(mark_attach_class_def_offset)?;
+        let attach_list = match attach_list_offset {
+            0 => None,
+            offset => Some(AttachList::parse(table_data, offset)?),
+        };
+        let lig_caret_list = match lig_caret_list_offset {
+            0 => None,
+            offset => Some(LigCaretList::parse(table_data, offset)?),
+        };
+        let mark_glyph_sets = match mark_glyph_sets_def_offset {
+            0 => vec![],
+            offset => Self::parse_mark_glyph_sets(table_data, offset)?,
+        };
+
+        Ok(Self {
+            glyph_classes,
+            attach_list,
+            lig_caret_list,
+            mark_attach_classes,
+            mark_glyph_sets,
+        })
+    }
+
+    fn parse_mark_glyph_sets(
+        table_data: &[u8],
+        offset: usize,
+    ) -> Result<Vec<Coverage>, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        if reader.read_u16()? != 1 {
+            return Err(ParseError::UnknownFormat);
+        }
+        let count = reader.read_u16()? as usize;
+        let coverage_offsets = reader.read_array::<usize, Offset32>(count)?;
+        coverage_offsets
+            .into_iter()
+            .map(|coverage_offset| Coverage::parse(table_data, offset + coverage_offset))
+            .collect()
+    }
+
+    /// The class of a glyph, or `None` if the font doesn't classify it.
+    pub fn glyph_class(&self, glyph_id: u16) -> Option<GlyphClass> {
+        match self.glyph_classes.as_ref()?.class(glyph_id) {
+            1 => Some(GlyphClass::Base),
+            2 => Some(GlyphClass::Ligature),
+            3 => Some(GlyphClass::Mark),
+            4 => Some(GlyphClass::Component),
+            _ => None,
+        }
+    }
+
+    /// The mark attachment class of a mark glyph, which lookups can use to skip other kinds of
+    /// marks. 0 if the glyph has none.
+    pub fn mark_attachment_class(&self, glyph_id: u16) -> u16 {
+        self.mark_attach_classes
+            .as_ref()
+            .map_or(0, |classes| classes.class(glyph_id))
+    }
+
+    /// Whether a glyph is in the mark glyph set with the given index. Lookups which name a set
+    /// only process the marks in it.
+    pub fn is_in_mark_glyph_set(&self, set_index: u16, glyph_id: u16) -> bool {
+        match self.mark_glyph_sets.get(set_index as usize) {
+            Some(coverage) => coverage.contains(glyph_id),
+            None => false,
+        }
+    }
+
+    /// The indices of the contour points in a glyph which marks can attach to.
+    pub fn attachment_points(&self, glyph_id: u16) -> &[u16] {
+        self.attach_list
+            .as_ref()
+            .and_then(|list| {
+                let idx = list.coverage.index(glyph_id)?;
+                list.point_indices.get(idx as usize)
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The caret positions between the components of a ligature, in increasing order. Empty for
+    /// glyphs which aren't ligatures.
+    pub fn ligature_carets(&self, glyph_id: u16) -> &[CaretValue] {
+        self.lig_caret_list
+            .as_ref()
+            .and_then(|list| {
+                let idx = list.coverage.index(glyph_id)?;
+                list.carets.get(idx as usize)
+            })
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+impl AttachList {
+    fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let glyph_count = reader.read_u16()? as usize;
+        let attach_point_offsets = reader.read_array::<usize, Offset16>(glyph_count)?;
+
+        let coverage = Coverage::parse(table_data, offset + coverage_offset)?;
+        let mut point_indices = Vec::with_capacity(glyph_count);
+        for attach_point_offset in attach_point_offsets {
+            let mut reader = Reader::at(table_data, offset + attach_point_offset);
+            let point_count = reader.read_u16()? as usize;
+            point_indices.push(reader.read_array::<u16, U16>(point_count)?);
+        }
+
+        Ok(Self {
+            coverage,
+            point_indices,
+        })
+    }
+}
+
+impl LigCaretList {
+    fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let lig_glyph_count = reader.read_u16()? as usize;
+        let lig_glyph_offsets = reader.read_array::<usize, Offset16>(lig_glyph_count)?;
+
+        let coverage = Coverage::parse(table_data, offset + coverage_offset)?;
+        let mut carets = Vec::with_capacity(lig_glyph_count);
+        for lig_glyph_offset in lig_glyph_offsets {
+            let lig_glyph_offset = offset + lig_glyph_offset;
+            let mut reader = Reader::at(table_data, lig_glyph_offset);
+            let caret_count = reader.read_u16()? as usize;
+            let caret_value_offsets = reader.read_array::<usize, Offset16>(caret_count)?;
+
+            let mut lig_carets = Vec::with_capacity(caret_count);
+            for caret_value_offset in caret_value_offsets {
+                let mut reader = Reader::at(table_data, lig_glyph_offset + caret_value_offset);
+                lig_carets.push(match reader.read_u16()? {
+                    // Format 3 adds a device table to adjust the coordinate at particular sizes.
+                    1 | 3 => CaretValue::Coordinate(reader.read_i16()?),
+                    2 => CaretValue::ContourPoint(reader.read_u16()?),
+                    _ => return Err(ParseError::UnknownFormat),
+                });
+            }
+            carets.push(lig_carets);
+        }
+
+        Ok(Self { coverage, carets })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A version 1.2 table. Glyph 1 is a base with an attachment point at point 4, 2 a ligature
+    /// with carets at 300 and point 7, and 3 a mark in mark glyph set 0. The glyph classes double
+    /// as the mark attachment classes.
+    const SAMPLE_TABLE: [u8; 82] = [
+        0x00, 0x01, 0x00, 0x02, // version 1.2
+        0x00, 0x0E, 0x00, 0x1A, 0x00, 0x2A, 0x00, 0x0E, 0x00, 0x44, // offsets
+        // 14: glyph class def
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03,
+        // 26: attach list
+        0x00, 0x0A, 0x00, 0x01, 0x00, 0x06, // coverage and one attach point table
+        0x00, 0x01, 0x00, 0x04, // points
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x01, // coverage: glyph 1
+        // 42: ligature caret list
+        0x00, 0x0C, 0x00, 0x01, 0x00, 0x06, // coverage and one lig glyph table
+        0x00, 0x02, 0x00, 0x0C, 0x00, 0x10, // 2 carets
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x02, // coverage: glyph 2
+        0x00, 0x01, 0x01, 0x2C, // coordinate 300
+        0x00, 0x02, 0x00, 0x07, // contour point 7
+        // 68: mark glyph sets
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x08, // one set
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x03, // coverage: glyph 3
+    ];
+
+    #[test]
+    fn parse() {
+        let gdef = GdefTable::parse(&SAMPLE_TABLE).unwrap();
+
+        assert_eq!(gdef.glyph_class(0), None);
+        assert_eq!(gdef.glyph_class(1), Some(GlyphClass::Base));
+        assert_eq!(gdef.glyph_class(2), Some(GlyphClass::Ligature));
+        assert_eq!(gdef.glyph_class(3), Some(GlyphClass::Mark));
+        assert_eq!(gdef.mark_attachment_class(3), 3);
+        assert_eq!(gdef.attachment_points(1), &[4]);
+        assert!(gdef.attachment_points(2).is_empty());
+        assert_eq!(
+            gdef.ligature_carets(2),
+            &[CaretValue::Coordinate(300), CaretValue::ContourPoint(7)]
+        );
+        assert!(gdef.ligature_carets(1).is_empty());
+        assert!(gdef.is_in_mark_glyph_set(0, 3));
+        assert!(!gdef.is_in_mark_glyph_set(0, 2));
+        assert!(!gdef.is_in_mark_glyph_set(1, 3));
+    }
+
+    #[test]
+    fn parse_version_1_0() {
+        // Without the mark glyph sets offset, the version 1.2 table's last offset is ignored.
+        let mut data = SAMPLE_TABLE;
+        data[3] = 0;
+        let gdef = GdefTable::parse(&data).unwrap();
+
+        assert!(!gdef.is_in_mark_glyph_set(0, 3));
+    }
+
+    #[test]
+    fn parse_unknown_version() {
+        let data = [0x00, 0x02, 0x00, 0x00];
+
+        assert_eq!(
+            GdefTable::parse(&data).unwrap_err(),
+            ParseError::UnknownVersion
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Compound glyphs are built from other glyphs, each placed with an offset or by matching points
+//! and optionally scaled, flipped or rotated.
+
+use super::{Contour, ResolveError};
+use opentype::types::{ReadError, Reader};
+
+#[derive(Clone, Debug)]
+pub struct CompoundGlyphTable {
+    pub components: Vec<Component>,
+    instructions: Vec<u8>,
+}
+
+impl CompoundGlyphTable {
+    const MASK_MORE_COMPONENTS: u16 = 0x0020;
+    const MASK_WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+    pub fn deserialize(reader: &mut Reader) -> Result<Self, ReadError> {
+        let mut components = vec![];
+        let mut flags = Self::MASK_MORE_COMPONENTS;
+        while flags & Self::MASK_MORE_COMPONENTS != 0 {
+            let component = Component::deserialize(reader)?;
+            flags = component.flags;
+            components.push(component);
+        }
+
+        // Instructions for the whole glyph follow the last component.
+        let instructions = if flags & Self::MASK_WE_HAVE_INSTRUCTIONS != 0 {
+            let instruction_length = reader.read_u16()? as usize;
+            reader.read_bytes(instruction_length)?.to_vec()
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            components,
+            instructions,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Component {
+    flags: u16,
+    pub glyph_index: u16,
+    placement: Placement,
+    /// The 2x2 matrix `[xx, xy, yx, yy]`, mapping a point to `(xx * x + yx * y, xy * x + yy * y)`.
+    transform: [f32; 4],
+}
+
+/// How a component is positioned within the compound glyph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Placement {
+    /// Move the component by a vector.
+    Offset { x: i16, y: i16 },
+    /// Move the component so that its point `child` lands on point `parent` of the components
+    /// placed before it.
+    MatchPoints { parent: u16, child: u16 },
+}
+
+impl Component {
+    const MASK_ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const MASK_ARGS_ARE_XY_VALUES: u16 = 0x0002;
+    const MASK_ROUND_XY_TO_GRID: u16 = 0x0004;
+    const MASK_WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MASK_WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const MASK_WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+    const MASK_SCALED_COMPONENT_OFFSET: u16 = 0x0800;
+    const MASK_UNSCALED_COMPONENT_OFFSET: u16 = 0x1000;
+
+    fn deserialize(reader: &mut Reader) -> Result<Self, ReadError> {
+        let flags = reader.read_u16()?;
+        let glyph_index = reader.read_u16()?;
+
+        let parse_flags = (
+            flags & Self::MASK_ARG_1_AND_2_ARE_WORDS != 0,
+            flags & Self::MASK_ARGS_ARE_XY_VALUES != 0,
+        );
+        let placement = match parse_flags {
+            (true, true) => Placement::Offset {
+                x: reader.read_i16()?,
+                y: reader.read_i16()?,
+            },
+            (true, false) => Placement::MatchPoints {
+                parent: reader.read_u16()?,
+                child: reader.read_u16()?,
+            },
+            (false, true) => Placement::Offset {
+                x: i16::from(reader.read_u8()? as i8),
+                y: i16::from(reader.read_u8()? as i8),
+            },
+            (false, false) => Placement::MatchPoints {
+                parent: u16::from(reader.read_u8()?),
+                child: u16::from(reader.read_u8()?),
+            },
+        };
+
+        let transform = if flags & Self::MASK_WE_HAVE_A_SCALE != 0 {
+            let scale = reader.read_f2dot14()?;
+            [scale, 0.0, 0.0, scale]
+        } else if flags & Self::MASK_WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            let x_scale = reader.read_f2dot14()?;
+            let y_scale = reader.read_f2dot14()?;
+            [x_scale, 0.0, 0.0, y_scale]
+        } else if flags & Self::MASK_WE_HAVE_A_TWO_BY_TWO != 0 {
+            [
+                reader.read_f2dot14()?,
+                reader.read_f2dot14()?,
+                reader.read_f2dot14()?,
+                reader.read_f2dot14()?,
+            ]
+        } else {
+            [1.0, 0.0, 0.0, 1.0]
+        };
+
+        Ok(Self {
+            flags,
+            glyph_index,
+            placement,
+            transform,
+        })
+    }
+
+    /// Transform the component's contours and move them into place. `parent` holds the contours
+    /// of the components placed before this one.
+    pub fn place(&self, contours: &mut [Contour], parent: &[Contour]) -> Result<(), ResolveError> {
+        for point in contours
+            .iter_mut()
+            .flat_map(|contour| contour.points.iter_mut())
+        {
+            let (x, y) = self.apply_transform(point.x, point.y);
+            point.x = x;
+            point.y = y;
+        }
+
+        let (dx, dy) = match self.placement {
+            Placement::Offset { x, y } => {
+                let (x, y) = (f32::from(x), f32::from(y));
+                // Fonts from Apple scale the offset along with the component; everyone else
+                // leaves it alone unless asked.
+                let scaled = self.flags & Self::MASK_SCALED_COMPONENT_OFFSET != 0
+                    && self.flags & Self::MASK_UNSCALED_COMPONENT_OFFSET == 0;
+                let (dx, dy) = if scaled {
+                    self.apply_transform(x, y)
+                } else {
+                    (x, y)
+                };
+                if self.flags & Self::MASK_ROUND_XY_TO_GRID != 0 {
+                    (dx.round(), dy.round())
+                } else {
+                    (dx, dy)
+                }
+            }
+            Placement::MatchPoints {
+                parent: p,
+                child: c,
+            } => {
+                let parent_point = nth_point(parent, p).ok_or(ResolveError::BadPointIndex(p))?;
+                let child_point = nth_point(contours, c).ok_or(ResolveError::BadPointIndex(c))?;
+                (
+                    parent_point.0 - child_point.0,
+                    parent_point.1 - child_point.1,
+                )
+            }
+        };
+
+        for point in contours
+            .iter_mut()
+            .flat_map(|contour| contour.points.iter_mut())
+        {
+            point.x += dx;
+            point.y += dy;
+        }
+
+        Ok(())
+    }
+
+    fn apply_transform(&self, x: f32, y: f32) -> (f32, f32) {
+        let [xx, xy, yx, yy] = self.transform;
+        (xx * x + yx * y, xy * x + yy * y)
+    }
+}
+
+/// Find a point by its index across all of the contours.
+fn nth_point(contours: &[Contour], idx: u16) -> Option<(f32, f32)> {
+    contours
+        .iter()
+        .flat_map(|contour| contour.points.iter())
+        .nth(idx as usize)
+        .map(|point| (point.x, point.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_offsets() {
+        let data = [
+            0x00u8, 0x22, 0x00, 0x05, 0x0A, 0xEC, // bytes, more components
+            0x00, 0x03, 0x00, 0x06, 0x01, 0x00, 0xFF, 0x00, // words
+        ];
+        let table = CompoundGlyphTable::deserialize(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(table.components.len(), 2);
+        assert_eq!(table.components[0].glyph_index, 5);
+        assert_eq!(
+            table.components[0].placement,
+            Placement::Offset { x: 10, y: -20 }
+        );
+        assert_eq!(table.components[1].glyph_index, 6);
+        assert_eq!(
+            table.components[1].placement,
+            Placement::Offset { x: 256, y: -256 }
+        );
+        assert!(table.instructions.is_empty());
+    }
+
+    #[test]
+    fn parse_point_matching() {
+        let data = [
+            0x00u8, 0x20, 0x00, 0x01, 0x02, 0x03, // bytes, more components
+            0x00, 0x01, 0x00, 0x01, 0x01, 0x00, 0x00, 0x02, // words
+        ];
+        let table = CompoundGlyphTable::deserialize(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(
+            table.components[0].placement,
+            Placement::MatchPoints {
+                parent: 2,
+                child: 3
+            }
+        );
+        assert_eq!(
+            table.components[1].placement,
+            Placement::MatchPoints {
+                parent: 256,
+                child: 2
+            }
+        );
+    }
+
+    #[test]
+    fn parse_scales() {
+        let data = [
+            0x00u8, 0x2A, 0x00, 0x01, 0x00, 0x00, 0x20, 0x00, // scale
+            0x00, 0x62, 0x00, 0x01, 0x00, 0x00, 0x40, 0x00, 0xC0, 0x00, // x and y scale
+            0x00, 0x82, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0xC0, 0x00, 0x00,
+            0x00, // 2x2
+        ];
+        let table = CompoundGlyphTable::deserialize(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(table.components[0].transform, [0.5, 0.0, 0.0, 0.5]);
+        assert_eq!(table.components[1].transform, [1.0, 0.0, 0.0, -1.0]);
+        assert_eq!(table.components[2].transform, [0.0, 1.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn parse_instructions() {
+        let data = [
+            0x01u8, 0x02, 0x00, 0x01, 0x00, 0x00, // component with instructions
+            0x00, 0x02, 0xB0, 0x01, // instructions
+        ];
+        let table = CompoundGlyphTable::deserialize(&mut Reader::new(&data)).unwrap();
+
+        assert_eq!(table.instructions, vec![0xB0, 0x01]);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let data = [0x00u8, 0x22, 0x00, 0x05, 0x0A, 0xEC];
+
+        assert_eq!(
+            CompoundGlyphTable::deserialize(&mut Reader::new(&data)).unwrap_err(),
+            ReadError { offset: 6 }
+        );
+    }
+}
@@ -0,0 +1,551 @@
+mod compound;
+mod outline;
+mod svg;
+
+use self::compound::CompoundGlyphTable;
+pub use self::outline::{Contour, Outline, OutlineBuilder, Point};
+pub use self::svg::SvgOptions;
+use opentype::tables::loca::LocaTable;
+use opentype::types::{slice, ReadError, Reader};
+
+#[derive(Debug)]
+pub struct GlyfTable {
+    glyphs: Vec<Option<Glyph>>,
+}
+
+impl GlyfTable {
+    pub fn parse(table_data: &[u8], loca_table: &LocaTable) -> Result<Self, ReadError> {
+        let mut glyphs = Vec::new();
+        for location in loca_table.locations() {
+            let len = location.length;
+            if len == 0 {
+                glyphs.push(None);
+                continue;
+            }
+
+            let offset = location.offset;
+            let glyph_data = slice(table_data, offset, len)?;
+            let glyph = Glyph::deserialize(glyph_data).map_err(|err| err.offset_by(offset))?;
+            glyphs.push(Some(glyph));
+        }
+
+        Ok(Self { glyphs })
+    }
+
+    pub fn read_glyph(&self, loca_idx: usize) -> Option<Glyph> {
+        self.glyphs.get(loca_idx).map(|o| o.clone()).unwrap_or(None)
+    }
+
+    /// The bounding box of a glyph, as `(min_x, min_y, max_x, max_y)` in font units, as recorded
+    /// in the font. Returns `None` for glyphs without an outline.
+    pub fn bounds(&self, glyph_id: u16) -> Option<(i16, i16, i16, i16)> {
+        match self.glyphs.get(glyph_id as usize) {
+            Some(Some(glyph)) => Some((glyph.min_x, glyph.min_y, glyph.max_x, glyph.max_y)),
+            _ => None,
+        }
+    }
+
+    /// The deepest components are nested, whatever depth the font declares. Real fonts need a
+    /// few levels at most.
+    pub const MAX_DEPTH: u16 = 16;
+    /// The most components a glyph can be built from, counting those of nested glyphs, so that
+    /// components which each repeat the glyph below them several times can't multiply out.
+    const MAX_COMPONENTS: usize = 1024;
+    /// The most points a glyph can have. Components match points by 16-bit index, so a glyph
+    /// can't use any more.
+    const MAX_POINTS: usize = 0xFFFF;
+
+    /// The contours of a glyph, with any components flattened into place.
+    ///
+    /// Components may themselves be compound glyphs, nested up to `max_depth` levels deep, or
+    /// `MAX_DEPTH` for fonts which declare more.
+    pub fn contours(&self, glyph_id: u16, max_depth: u16) -> Result<Vec<Contour>, ResolveError> {
+        let mut contours = vec![];
+        let mut budget = Budget {
+            path: vec![],
+            components: 0,
+            points: 0,
+        };
+        self.append_contours(
+            glyph_id,
+            max_depth.min(Self::MAX_DEPTH),
+            &mut budget,
+            &mut contours,
+        )?;
+        Ok(contours)
+    }
+
+    fn append_contours(
+        &self,
+        glyph_id: u16,
+        depth: u16,
+        budget: &mut Budget,
+        contours: &mut Vec<Contour>,
+    ) -> Result<(), ResolveError> {
+        let glyph = match self.glyphs.get(glyph_id as usize) {
+            None => return Err(ResolveError::MissingGlyph(glyph_id)),
+            // Glyphs such as the space have no outline.
+            Some(None) => return Ok(()),
+            Some(Some(glyph)) => glyph,
+        };
+
+        if let Some(simple_glyph) = &glyph.simple_glyph {
+            let simple_contours = simple_glyph.contours();
+            budget.points += simple_contours
+                .iter()
+                .map(|contour| contour.points.len())
+                .sum::<usize>();
+            if budget.points > Self::MAX_POINTS {
+                return Err(ResolveError::TooComplex);
+            }
+            contours.extend(simple_contours);
+        }
+        if let Some(compound_glyph) = &glyph.compound_glyph {
+            // Each level of nesting uses up one level of depth.
+            if depth == 0 {
+                return Err(ResolveError::TooDeep);
+            }
+            if budget.path.contains(&glyph_id) {
+                return Err(ResolveError::Recursive(glyph_id));
+            }
+
+            // Components are placed relative to the ones before them, so they're kept apart from
+            // any contours the caller has already collected.
+            budget.path.push(glyph_id);
+            let mut placed = vec![];
+            for component in &compound_glyph.components {
+                budget.components += 1;
+                if budget.components > Self::MAX_COMPONENTS {
+                    return Err(ResolveError::TooComplex);
+                }
+                let mut component_contours = vec![];
+                self.append_contours(
+                    component.glyph_index,
+                    depth - 1,
+                    budget,
+                    &mut component_contours,
+                )?;
+                component.place(&mut component_contours, &placed)?;
+                placed.extend(component_contours);
+            }
+            budget.path.pop();
+            contours.extend(placed);
+        }
+
+        Ok(())
+    }
+}
+
+/// What resolving a glyph's components has used so far.
+struct Budget {
+    /// The compound glyphs being resolved, from the outermost in.
+    path: Vec<u16>,
+    components: usize,
+    points: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ResolveError {
+    /// The glyph, or one of its components, isn't in the font.
+    MissingGlyph(u16),
+    /// Components are nested more deeply than the font allows.
+    TooDeep,
+    /// A compound glyph includes itself, directly or through its components.
+    Recursive(u16),
+    /// The glyph is built from more components or points than any real glyph needs.
+    TooComplex,
+    /// A component is positioned by matching a point which doesn't exist.
+    BadPointIndex(u16),
+}
+
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    num_contours: i16,
+    min_x: i16,
+    min_y: i16,
+    max_x: i16,
+    max_y: i16,
+    simple_glyph: Option<SimpleGlyphTable>,
+    compound_glyph: Option<CompoundGlyphTable>,
+}
+
+impl Glyph {
+    fn deserialize(glyph_data: &[u8]) -> Result<Self, ReadError> {
+        let mut reader = Reader::new(glyph_data);
+        let num_contours = reader.read_i16()?;
+        let min_x = reader.read_i16()?;
+        let min_y = reader.read_i16()?;
+        let max_x = reader.read_i16()?;
+        let max_y = reader.read_i16()?;
+
+        let mut simple_glyph = None;
+        let mut compound_glyph = None;
+
+        if num_contours >= 0 {
+            simple_glyph = Some(SimpleGlyphTable::deserialize(
+                &mut reader,
+                num_contours as u16,
+            )?);
+        } else {
+            compound_glyph = Some(CompoundGlyphTable::deserialize(&mut reader)?);
+        }
+
+        Ok(Self {
+            num_contours,
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            simple_glyph,
+            compound_glyph,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SimpleGlyphTable {
+    end_points_of_contours: Vec<usize>,
+    instruction_length: usize,
+    instructions: Vec<u8>,
+    flags: Vec<u8>,
+    x_coordinates: Vec<i16>,
+    y_coordinates: Vec<i16>,
+}
+
+impl SimpleGlyphTable {
+    const MASK_ON_CURVE_POINT: u8 = 0b0000_0001;
+    const MASK_X_SHORT_VECTOR: u8 = 0b0000_0010;
+    const MASK_Y_SHORT_VECTOR: u8 = 0b0000_0100;
+    const MASK_REPEAT_FLAG: u8 = 0b0000_1000;
+    const MASK_X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR: u8 = 0b0001_0000;
+    const MASK_Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR: u8 = 0b0010_0000;
+
+    fn deserialize(reader: &mut Reader, num_contours: u16) -> Result<Self, ReadError> {
+        let mut end_points_of_contours = vec![];
+        for _ in 0..num_contours {
+            end_points_of_contours.push(reader.read_u16()? as usize);
+        }
+        // The last contour ends on the last point; a glyph with no contours has no points.
+        let num_points = end_points_of_contours.last().map_or(0, |end| end + 1);
+
+        let instruction_length = reader.read_u16()? as usize;
+        let instructions = reader.read_bytes(instruction_length)?.to_vec();
+
+        let flags = Self::deserialize_flags(reader, num_points)?;
+        let x_coordinates = Self::deserialize_x_coordinates(reader, num_points, &flags)?;
+        let y_coordinates = Self::deserialize_y_coordinates(reader, num_points, &flags)?;
+
+        Ok(Self {
+            end_points_of_contours,
+            instruction_length,
+            instructions,
+            flags,
+            x_coordinates,
+            y_coordinates,
+        })
+    }
+
+    fn contours(&self) -> Vec<Contour> {
+        let points: Vec<Point> = self
+            .x_coordinates
+            .iter()
+            .zip(self.y_coordinates.iter())
+            .zip(self.flags.iter())
+            .map(|((&x, &y), &flag)| Point {
+                x: f32::from(x),
+                y: f32::from(y),
+                on_curve: flag & Self::MASK_ON_CURVE_POINT != 0,
+            })
+            .collect();
+
+        let mut contours = vec![];
+        let mut start = 0;
+        for &end in &self.end_points_of_contours {
+            // Malformed glyphs may have end points which go backwards; skip those contours.
+            if let Some(contour_points) = points.get(start..=end) {
+                contours.push(Contour {
+                    points: contour_points.to_vec(),
+                });
+            }
+            start = end + 1;
+        }
+
+        contours
+    }
+
+    fn deserialize_flags(reader: &mut Reader, num_points: usize) -> Result<Vec<u8>, ReadError> {
+        let mut flags = vec![];
+        while flags.len() < num_points {
+            let flag = reader.read_u8()?;
+
+            let repetitions = if flag & Self::MASK_REPEAT_FLAG == 0 {
+                0
+            } else {
+                reader.read_u8()?
+            };
+
+            for _ in 0..repetitions as usize + 1 {
+                flags.push(flag);
+            }
+        }
+
+        Ok(flags)
+    }
+
+    fn deserialize_x_coordinates(
+        reader: &mut Reader,
+        num_points: usize,
+        flags: &[u8],
+    ) -> Result<Vec<i16>, ReadError> {
+        let mut x_coordinates: Vec<i16> = vec![];
+        // Coordinates are stored as deltas from the previous point.
+        let mut x = 0i16;
+        for &flag in flags.iter().take(num_points) {
+            let parse_flags = (
+                flag & Self::MASK_X_SHORT_VECTOR != 0,
+                flag & Self::MASK_X_IS_SAME_OR_POSITIVE_X_SHORT_VECTOR != 0,
+            );
+            let delta = match parse_flags {
+                (true, true) => reader.read_u8()? as i16,
+                (true, false) => 0 - reader.read_u8()? as i16,
+                (false, true) => 0,
+                (false, false) => reader.read_i16()?,
+            };
+
+            x = x.wrapping_add(delta);
+            x_coordinates.push(x);
+        }
+
+        Ok(x_coordinates)
+    }
+
+    fn deserialize_y_coordinates(
+        reader: &mut Reader,
+        num_points: usize,
+        flags: &[u8],
+    ) -> Result<Vec<i16>, ReadError> {
+        let mut y_coordinates: Vec<i16> = vec![];
+        // Coordinates are stored as deltas from the previous point.
+        let mut y = 0i16;
+        for &flag in flags.iter().take(num_points) {
+            let parse_flags = (
+                flag & Self::MASK_Y_SHORT_VECTOR != 0,
+                flag & Self::MASK_Y_IS_SAME_OR_POSITIVE_Y_SHORT_VECTOR != 0,
+            );
+            let delta = match parse_flags {
+                (true, true) => reader.read_u8()? as i16,
+                (true, false) => 0 - reader.read_u8()? as i16,
+                (false, true) => 0,
+                (false, false) => reader.read_i16()?,
+            };
+
+            y = y.wrapping_add(delta);
+            y_coordinates.push(y);
+        }
+
+        Ok(y_coordinates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::head::IndexToLocFormat;
+    use opentype::tables::test_data::sample_table;
+
+    fn sample_loca(glyf_len: usize) -> LocaTable {
+        LocaTable::parse(
+            sample_table(b"loca"),
+            IndexToLocFormat::ShortOffset,
+            3,
+            glyf_len,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_sample_glyphs() {
+        let glyf_data = sample_table(b"glyf");
+        let table = GlyfTable::parse(glyf_data, &sample_loca(glyf_data.len())).unwrap();
+
+        let glyph = table.read_glyph(0).unwrap();
+        assert_eq!(glyph.num_contours, 2);
+        let simple = glyph.simple_glyph.unwrap();
+        assert_eq!(simple.end_points_of_contours, vec![3, 7]);
+        assert_eq!(simple.x_coordinates.len(), 8);
+        assert_eq!(simple.y_coordinates.len(), 8);
+    }
+
+    #[test]
+    fn parse_truncated_glyph() {
+        let glyf_data = sample_table(b"glyf");
+        // The sample glyph's data is followed by a single byte of padding.
+        for len in 0..glyf_data.len() - 1 {
+            assert!(Glyph::deserialize(&glyf_data[..len]).is_err());
+        }
+        assert!(Glyph::deserialize(&glyf_data[..glyf_data.len() - 1]).is_ok());
+    }
+
+    #[test]
+    fn parse_sample_coordinates() {
+        let glyf_data = sample_table(b"glyf");
+        let table = GlyfTable::parse(glyf_data, &sample_loca(glyf_data.len())).unwrap();
+
+        let contours = table.contours(0, 1).unwrap();
+        assert_eq!(contours.len(), 2);
+        let outer = &contours[0].points;
+        assert_eq!(outer.len(), 4);
+        assert!(outer.iter().all(|point| point.on_curve));
+        // The sample's missing glyph is a box, so the outer contour's corners have distinct
+        // coordinates on both axes.
+        let mut xs: Vec<i32> = outer.iter().map(|point| point.x as i32).collect();
+        xs.sort_unstable();
+        xs.dedup();
+        assert_eq!(xs.len(), 2);
+    }
+
+    /// A 100 unit square with a corner at the origin.
+    const SQUARE: [u8; 21] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x64, 0x00, 0x64, // header
+        0x00, 0x03, 0x00, 0x00, // end points and instruction length
+        0x31, 0x33, 0x35, 0x23, // flags
+        0x64, 0x64, // x deltas
+        0x64, // y deltas
+    ];
+
+    fn square(x: f32, y: f32, size: f32) -> Contour {
+        let point = |x, y| Point {
+            x,
+            y,
+            on_curve: true,
+        };
+        Contour {
+            points: vec![
+                point(x, y),
+                point(x + size, y),
+                point(x + size, y + size),
+                point(x, y + size),
+            ],
+        }
+    }
+
+    fn compound_table(compound: &[u8]) -> GlyfTable {
+        let mut data = vec![0xFFu8, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        data.extend_from_slice(compound);
+        GlyfTable {
+            glyphs: vec![
+                Some(Glyph::deserialize(&SQUARE).unwrap()),
+                Some(Glyph::deserialize(&data).unwrap()),
+                None,
+            ],
+        }
+    }
+
+    #[test]
+    fn resolve_simple_glyph() {
+        let table = compound_table(&[0x00, 0x02, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(table.contours(0, 0).unwrap(), vec![square(0.0, 0.0, 100.0)]);
+        assert_eq!(table.contours(2, 0).unwrap(), vec![]);
+        assert_eq!(
+            table.contours(3, 0).unwrap_err(),
+            ResolveError::MissingGlyph(3)
+        );
+    }
+
+    #[test]
+    fn resolve_offset_and_scaled_components() {
+        let table = compound_table(&[
+            0x00, 0x22, 0x00, 0x00, 0x0A, 0x14, // offset by (10, 20)
+            0x00, 0x0B, 0x00, 0x00, 0x01, 0x00, 0xFF, 0xF6, 0x20,
+            0x00, // half size at (256, -10)
+        ]);
+
+        assert_eq!(
+            table.contours(1, 1).unwrap(),
+            vec![square(10.0, 20.0, 100.0), square(256.0, -10.0, 50.0)]
+        );
+    }
+
+    #[test]
+    fn resolve_matched_points() {
+        let table = compound_table(&[
+            0x00, 0x22, 0x00, 0x00, 0x00, 0x00, // at the origin
+            0x00, 0x00, 0x00, 0x00, 0x02, 0x00, // first point on the first square's third
+        ]);
+
+        assert_eq!(
+            table.contours(1, 1).unwrap(),
+            vec![square(0.0, 0.0, 100.0), square(100.0, 100.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn resolve_bad_matched_point() {
+        let table = compound_table(&[
+            0x00, 0x22, 0x00, 0x00, 0x00, 0x00, // at the origin
+            0x00, 0x00, 0x00, 0x00, 0x04, 0x00, // the first square only has 4 points
+        ]);
+
+        assert_eq!(
+            table.contours(1, 1).unwrap_err(),
+            ResolveError::BadPointIndex(4)
+        );
+    }
+
+    #[test]
+    fn resolve_depth_limit() {
+        // A compound glyph can't be resolved without any nesting.
+        let table = compound_table(&[0x00, 0x02, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(table.contours(1, 0).unwrap_err(), ResolveError::TooDeep);
+        assert_eq!(table.contours(1, 1).unwrap(), vec![square(0.0, 0.0, 100.0)]);
+    }
+
+    #[test]
+    fn resolve_self_reference() {
+        // A glyph which contains itself, however deep the font says components can go.
+        let table = compound_table(&[0x00, 0x02, 0x00, 0x01, 0x00, 0x00]);
+
+        assert_eq!(
+            table.contours(1, 0xFFFF).unwrap_err(),
+            ResolveError::Recursive(1)
+        );
+    }
+
+    #[test]
+    fn resolve_limits() {
+        // Glyph 1 is two squares, and each glyph after it is two copies of the one before.
+        let doubled = |glyph_id: u16| {
+            let [high, low] = glyph_id.to_be_bytes();
+            let mut data = vec![0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+            data.extend_from_slice(&[0x00, 0x22, high, low, 0x00, 0x00]);
+            data.extend_from_slice(&[0x00, 0x02, high, low, 0x00, 0x00]);
+            Some(Glyph::deserialize(&data).unwrap())
+        };
+        let mut glyphs = vec![Some(Glyph::deserialize(&SQUARE).unwrap())];
+        glyphs.extend((0..40).map(doubled));
+        let table = GlyfTable { glyphs };
+
+        assert_eq!(table.contours(4, 16).unwrap().len(), 16);
+        // Deeper than the hard limit, even though the font allows it.
+        assert_eq!(
+            table.contours(40, 0xFFFF).unwrap_err(),
+            ResolveError::TooDeep
+        );
+        // Within the depth limit, but with 2^16 squares.
+        assert_eq!(
+            table.contours(16, 16).unwrap_err(),
+            ResolveError::TooComplex
+        );
+    }
+
+    #[test]
+    fn parse_glyph_header_truncated() {
+        let glyf_data = sample_table(b"glyf");
+
+        assert_eq!(
+            Glyph::deserialize(&glyf_data[..7]).unwrap_err(),
+            ReadError { offset: 6 }
+        );
+    }
+}
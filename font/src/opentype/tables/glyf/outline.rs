@@ -0,0 +1,260 @@
+//! Glyph outlines as contours of points, and a way to replay them as drawing commands.
+//!
+//! TrueType outlines are made of straight lines and quadratic curves. Points are either on the
+//! outline or are the control point of a curve. Two control points in a row imply an on-curve
+//! point halfway between them, which saves space in the font but must be filled back in before
+//! drawing.
+
+/// Receives an outline as a sequence of drawing commands.
+///
+/// Each contour starts with `move_to` and ends with `close`. Closing a contour implies a straight
+/// line back to its first point, so that line isn't sent separately.
+pub trait OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32);
+    fn line_to(&mut self, x: f32, y: f32);
+    /// A quadratic curve through the control point `(x1, y1)`.
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32);
+    /// A cubic curve through the control points `(x1, y1)` and `(x2, y2)`. TrueType outlines never
+    /// contain these.
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32);
+    fn close(&mut self);
+}
+
+/// A point on a glyph's outline, in font units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+    /// Whether the point lies on the outline, rather than being the control point of a quadratic
+    /// curve.
+    pub on_curve: bool,
+}
+
+impl Point {
+    /// The implied on-curve point between two control points.
+    fn midpoint(a: &Point, b: &Point) -> Self {
+        Self {
+            x: (a.x + b.x) / 2.0,
+            y: (a.y + b.y) / 2.0,
+            on_curve: true,
+        }
+    }
+}
+
+/// A closed loop of points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Point>,
+}
+
+impl Contour {
+    /// The same contour with the implied on-curve point inserted between each pair of
+    /// consecutive control points, including the pair which wraps around from the last point to
+    /// the first.
+    pub fn with_implied_points(&self) -> Contour {
+        let num_points = self.points.len();
+        let mut points = Vec::with_capacity(num_points * 2);
+        for (idx, point) in self.points.iter().enumerate() {
+            points.push(*point);
+
+            let next = &self.points[(idx + 1) % num_points];
+            if num_points > 1 && !point.on_curve && !next.on_curve {
+                points.push(Point::midpoint(point, next));
+            }
+        }
+
+        Contour { points }
+    }
+
+    pub fn replay<B: OutlineBuilder>(&self, builder: &mut B) {
+        let points = self.with_implied_points().points;
+
+        // With the implied points filled in, only a contour of a single control point lacks an
+        // on-curve point to start from.
+        let start = match points.iter().position(|point| point.on_curve) {
+            Some(start) => start,
+            None => {
+                if let Some(point) = points.first() {
+                    builder.move_to(point.x, point.y);
+                    builder.close();
+                }
+                return;
+            }
+        };
+        builder.move_to(points[start].x, points[start].y);
+
+        let mut control: Option<&Point> = None;
+        for step in 1..=points.len() {
+            let point = &points[(start + step) % points.len()];
+            if !point.on_curve {
+                control = Some(point);
+                continue;
+            }
+
+            match control.take() {
+                Some(control) => builder.quad_to(control.x, control.y, point.x, point.y),
+                // The line back to the start is implied by closing the contour.
+                None if step == points.len() => {}
+                None => builder.line_to(point.x, point.y),
+            }
+        }
+
+        builder.close();
+    }
+}
+
+/// The complete outline of a glyph, with the components of compound glyphs already in place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outline {
+    pub contours: Vec<Contour>,
+}
+
+impl Outline {
+    /// Send every contour of the outline to `builder`, in order.
+    pub fn replay<B: OutlineBuilder>(&self, builder: &mut B) {
+        for contour in &self.contours {
+            if !contour.points.is_empty() {
+                contour.replay(builder);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records commands in the style of SVG path data.
+    struct Recorder(Vec<String>);
+
+    impl OutlineBuilder for Recorder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0.push(format!("M{} {}", x, y));
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0.push(format!("L{} {}", x, y));
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0.push(format!("Q{} {} {} {}", x1, y1, x, y));
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.0
+                .push(format!("C{} {} {} {} {} {}", x1, y1, x2, y2, x, y));
+        }
+
+        fn close(&mut self) {
+            self.0.push("Z".to_string());
+        }
+    }
+
+    fn contour(points: &[(f32, f32, bool)]) -> Contour {
+        Contour {
+            points: points
+                .iter()
+                .map(|&(x, y, on_curve)| Point { x, y, on_curve })
+                .collect(),
+        }
+    }
+
+    fn replay(contours: Vec<Contour>) -> Vec<String> {
+        let mut recorder = Recorder(vec![]);
+        Outline { contours }.replay(&mut recorder);
+        recorder.0
+    }
+
+    #[test]
+    fn insert_implied_points() {
+        let contour = contour(&[(0.0, 0.0, false), (10.0, 0.0, false), (10.0, 10.0, true)]);
+
+        assert_eq!(
+            contour.with_implied_points().points,
+            vec![
+                Point {
+                    x: 0.0,
+                    y: 0.0,
+                    on_curve: false
+                },
+                Point {
+                    x: 5.0,
+                    y: 0.0,
+                    on_curve: true
+                },
+                Point {
+                    x: 10.0,
+                    y: 0.0,
+                    on_curve: false
+                },
+                Point {
+                    x: 10.0,
+                    y: 10.0,
+                    on_curve: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_lines() {
+        let square = contour(&[
+            (0.0, 0.0, true),
+            (10.0, 0.0, true),
+            (10.0, 10.0, true),
+            (0.0, 10.0, true),
+        ]);
+
+        assert_eq!(
+            replay(vec![square]),
+            vec!["M0 0", "L10 0", "L10 10", "L0 10", "Z"]
+        );
+    }
+
+    #[test]
+    fn replay_curves() {
+        // Starts with a control point and ends with a curve back to the first on-curve point.
+        let contour = contour(&[
+            (0.0, 10.0, false),
+            (0.0, 0.0, true),
+            (10.0, 0.0, true),
+            (10.0, 10.0, false),
+        ]);
+
+        assert_eq!(
+            replay(vec![contour]),
+            vec!["M0 0", "L10 0", "Q10 10 5 10", "Q0 10 0 0", "Z"]
+        );
+    }
+
+    #[test]
+    fn replay_only_control_points() {
+        // A circle drawn with four control points, and no explicit on-curve points at all.
+        let circle = contour(&[
+            (0.0, 0.0, false),
+            (10.0, 0.0, false),
+            (10.0, 10.0, false),
+            (0.0, 10.0, false),
+        ]);
+
+        assert_eq!(
+            replay(vec![circle]),
+            vec![
+                "M5 0",
+                "Q10 0 10 5",
+                "Q10 10 5 10",
+                "Q0 10 0 5",
+                "Q0 0 5 0",
+                "Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_degenerate_contours() {
+        let empty = contour(&[]);
+        let single = contour(&[(3.0, 4.0, false)]);
+
+        assert_eq!(replay(vec![empty, single]), vec!["M3 4", "Z"]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Converts glyph outlines into SVG path data.
+
+use super::outline::{Outline, OutlineBuilder};
+use std::fmt::Write;
+
+/// How outline coordinates are mapped into SVG coordinates.
+#[derive(Clone, Copy, Debug)]
+pub struct SvgOptions {
+    /// The size of an em in the output. Font units are scaled by `em_size / units_per_em`.
+    pub em_size: f32,
+    /// Make the y axis point down, as it does in SVG, rather than up from the baseline as it does
+    /// in fonts. The baseline stays at y = 0.
+    pub flip_y: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            em_size: 1.0,
+            flip_y: true,
+        }
+    }
+}
+
+impl Outline {
+    /// Write the outline as SVG path data using only the `M`, `L`, `Q` and `Z` commands.
+    pub fn to_svg_path(&self, units_per_em: u16, options: &SvgOptions) -> String {
+        let scale = options.em_size / f32::from(units_per_em.max(1));
+        let mut builder = SvgPathBuilder {
+            path: String::new(),
+            x_scale: scale,
+            y_scale: if options.flip_y { -scale } else { scale },
+        };
+        self.replay(&mut builder);
+        builder.path
+    }
+
+    /// The smallest box, as `(min_x, min_y, max_x, max_y)`, containing every point of the outline
+    /// including control points. Returns `None` for an empty outline.
+    pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        self.contours
+            .iter()
+            .flat_map(|contour| contour.points.iter())
+            .fold(None, |bounds, point| {
+                let (min_x, min_y, max_x, max_y) =
+                    bounds.unwrap_or((point.x, point.y, point.x, point.y));
+                Some((
+                    min_x.min(point.x),
+                    min_y.min(point.y),
+                    max_x.max(point.x),
+                    max_y.max(point.y),
+                ))
+            })
+    }
+}
+
+struct SvgPathBuilder {
+    path: String,
+    x_scale: f32,
+    y_scale: f32,
+}
+
+impl SvgPathBuilder {
+    fn push_command(&mut self, command: char, coordinates: &[f32]) {
+        self.path.push(command);
+        for (idx, pair) in coordinates.chunks(2).enumerate() {
+            if idx > 0 {
+                self.path.push(' ');
+            }
+            let x = format_number(pair[0] * self.x_scale);
+            let y = format_number(pair[1] * self.y_scale);
+            // Writing to a String can't fail.
+            let _ = write!(self.path, "{} {}", x, y);
+        }
+    }
+}
+
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.push_command('M', &[x, y]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push_command('L', &[x, y]);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push_command('Q', &[x1, y1, x, y]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.push_command('C', &[x1, y1, x2, y2, x, y]);
+    }
+
+    fn close(&mut self) {
+        self.path.push('Z');
+    }
+}
+
+/// Format a coordinate with at most three decimal places and no trailing zeros.
+pub fn format_number(value: f32) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    // Avoid writing "-0".
+    let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+    let formatted = format!("{:.3}", rounded);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::glyf::{Contour, Point};
+
+    fn outline(points: &[(f32, f32, bool)]) -> Outline {
+        Outline {
+            contours: vec![Contour {
+                points: points
+                    .iter()
+                    .map(|&(x, y, on_curve)| Point { x, y, on_curve })
+                    .collect(),
+            }],
+        }
+    }
+
+    #[test]
+    fn path_in_font_units() {
+        let outline = outline(&[(0.0, 0.0, true), (100.0, 0.0, true), (50.0, 100.0, false)]);
+        let options = SvgOptions {
+            em_size: 1000.0,
+            flip_y: false,
+        };
+
+        assert_eq!(
+            outline.to_svg_path(1000, &options),
+            "M0 0L100 0Q50 100 0 0Z"
+        );
+    }
+
+    #[test]
+    fn path_scaled_and_flipped() {
+        let outline = outline(&[(0.0, 0.0, true), (1024.0, 0.0, true), (0.0, 683.0, true)]);
+
+        assert_eq!(
+            outline.to_svg_path(2048, &SvgOptions::default()),
+            "M0 0L0.5 0L0 -0.333Z"
+        );
+    }
+
+    #[test]
+    fn outline_bounds() {
+        let outline = outline(&[(10.0, -5.0, true), (100.0, 0.0, true), (50.0, 120.0, false)]);
+
+        assert_eq!(outline.bounds(), Some((10.0, -5.0, 100.0, 120.0)));
+        assert_eq!(Outline { contours: vec![] }.bounds(), None);
+    }
+
+    #[test]
+    fn format_numbers() {
+        assert_eq!(format_number(12.0), "12");
+        assert_eq!(format_number(-0.25), "-0.25");
+        assert_eq!(format_number(1.0 / 3.0), "0.333");
+        assert_eq!(format_number(-0.0001), "0");
+    }
+}
@@ -0,0 +1,50 @@
+//! Cursive attachment joins each glyph's exit point to the entry point of the glyph after it, as
+//! in connected scripts such as Arabic.
+
+use super::value_record::Anchor;
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader};
+
+#[derive(Debug)]
+pub struct CursiveAttachment {
+    coverage: Coverage,
+    /// The entry and exit anchors of each covered glyph, by coverage index.
+    entry_exits: Vec<(Option<Anchor>, Option<Anchor>)>,
+}
+
+impl CursiveAttachment {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        if reader.read_u16()? != 1 {
+            return Err(ParseError::UnknownFormat);
+        }
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let entry_exit_count = reader.read_u16()?;
+        let mut entry_exits = Vec::with_capacity(entry_exit_count as usize);
+        for _ in 0..entry_exit_count {
+            let entry_offset = reader.read::<usize, Offset16>()?;
+            let exit_offset = reader.read::<usize, Offset16>()?;
+            entry_exits.push((
+                Anchor::parse_optional(table_data, offset, entry_offset)?,
+                Anchor::parse_optional(table_data, offset, exit_offset)?,
+            ));
+        }
+
+        Ok(Self {
+            coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+            entry_exits,
+        })
+    }
+
+    pub fn entry(&self, glyph_id: u16) -> Option<Anchor> {
+        self.entry_exits
+            .get(self.coverage.index(glyph_id)? as usize)?
+            .0
+    }
+
+    pub fn exit(&self, glyph_id: u16) -> Option<Anchor> {
+        self.entry_exits
+            .get(self.coverage.index(glyph_id)? as usize)?
+            .1
+    }
+}
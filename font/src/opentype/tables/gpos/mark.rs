@@ -0,0 +1,172 @@
+//! Mark attachment positions marks, such as accents, by lining up an anchor on the mark with an
+//! anchor for the mark's class on the glyph it attaches to. The glyph can be a base, a component
+//! of a ligature, or another mark.
+
+use super::value_record::Anchor;
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader};
+
+/// The marks a subtable positions, with their classes and anchors.
+#[derive(Debug)]
+struct MarkArray {
+    coverage: Coverage,
+    /// The class and anchor of each covered mark, by coverage index.
+    marks: Vec<(u16, Anchor)>,
+}
+
+/// Mark-to-base and mark-to-mark attachment, which differ only in the glyphs they attach to.
+#[derive(Debug)]
+pub struct MarkAttachment {
+    marks: MarkArray,
+    base_coverage: Coverage,
+    /// The anchor for each mark class on each covered base, by coverage index.
+    base_anchors: Vec<Vec<Option<Anchor>>>,
+}
+
+#[derive(Debug)]
+pub struct MarkToLigature {
+    marks: MarkArray,
+    ligature_coverage: Coverage,
+    /// For each covered ligature, by coverage index, the anchor for each mark class on each of its
+    /// components.
+    ligature_anchors: Vec<Vec<Vec<Option<Anchor>>>>,
+}
+
+impl MarkArray {
+    fn parse(table_data: &[u8], coverage_offset: usize, offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let mark_count = reader.read_u16()?;
+        let mut marks = Vec::with_capacity(mark_count as usize);
+        for _ in 0..mark_count {
+            let class = reader.read_u16()?;
+            let anchor_offset = offset + reader.read::<usize, Offset16>()?;
+            marks.push((class, Anchor::parse(table_data, anchor_offset)?));
+        }
+
+        Ok(Self {
+            coverage: Coverage::parse(table_data, coverage_offset)?,
+            marks,
+        })
+    }
+
+    fn get(&self, glyph_id: u16) -> Option<(u16, Anchor)> {
+        self.marks
+            .get(self.coverage.index(glyph_id)? as usize)
+            .cloned()
+    }
+}
+
+/// Parse an array of records which each hold an anchor for every mark class, as used for bases,
+/// ligature components and marks to attach to.
+fn parse_anchor_records(
+    table_data: &[u8],
+    offset: usize,
+    mark_class_count: usize,
+) -> Result<Vec<Vec<Option<Anchor>>>, ParseError> {
+    let mut reader = Reader::at(table_data, offset);
+    let record_count = reader.read_u16()?;
+    let mut records = Vec::with_capacity(record_count as usize);
+    for _ in 0..record_count {
+        let anchor_offsets = reader.read_array::<usize, Offset16>(mark_class_count)?;
+        records.push(
+            anchor_offsets
+                .into_iter()
+                .map(|anchor_offset| Anchor::parse_optional(table_data, offset, anchor_offset))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+    Ok(records)
+}
+
+/// Read the header shared by all three mark attachment formats. Returns the marks, the offset
+/// of the coverage table for what they attach to, and the number of mark classes and offset
+/// of the array of what they attach to.
+fn parse_header(
+    table_data: &[u8],
+    offset: usize,
+) -> Result<(MarkArray, usize, usize, usize), ParseError> {
+    let mut reader = Reader::at(table_data, offset);
+    if reader.read_u16()? != 1 {
+        return Err(ParseError::UnknownFormat);
+    }
+    let mark_coverage_offset = offset + reader.read::<usize, Offset16>()?;
+    let base_coverage_offset = offset + reader.read::<usize, Offset16>()?;
+    let mark_class_count = reader.read_u16()? as usize;
+    let mark_array_offset = offset + reader.read::<usize, Offset16>()?;
+    let base_array_offset = offset + reader.read::<usize, Offset16>()?;
+
+    let marks = MarkArray::parse(table_data, mark_coverage_offset, mark_array_offset)?;
+    Ok((
+        marks,
+        base_coverage_offset,
+        mark_class_count,
+        base_array_offset,
+    ))
+}
+
+impl MarkAttachment {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let (marks, base_coverage_offset, mark_class_count, base_array_offset) =
+            parse_header(table_data, offset)?;
+
+        Ok(Self {
+            marks,
+            base_coverage: Coverage::parse(table_data, base_coverage_offset)?,
+            base_anchors: parse_anchor_records(table_data, base_array_offset, mark_class_count)?,
+        })
+    }
+
+    /// The anchor on the mark, and the one on the base which it lines up with.
+    pub fn anchors(&self, mark: u16, base: u16) -> Option<(Anchor, Anchor)> {
+        let (class, mark_anchor) = self.marks.get(mark)?;
+        let base_idx = self.base_coverage.index(base)?;
+        let base_anchor = (*self
+            .base_anchors
+            .get(base_idx as usize)?
+            .get(class as usize)?)?;
+        Some((mark_anchor, base_anchor))
+    }
+}
+
+impl MarkToLigature {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let (marks, ligature_coverage_offset, mark_class_count, ligature_array_offset) =
+            parse_header(table_data, offset)?;
+
+        let mut reader = Reader::at(table_data, ligature_array_offset);
+        let ligature_count = reader.read_u16()? as usize;
+        let ligature_attach_offsets = reader.read_array::<usize, Offset16>(ligature_count)?;
+        let mut ligature_anchors = Vec::with_capacity(ligature_count);
+        for ligature_attach_offset in ligature_attach_offsets {
+            ligature_anchors.push(parse_anchor_records(
+                table_data,
+                ligature_array_offset + ligature_attach_offset,
+                mark_class_count,
+            )?);
+        }
+
+        Ok(Self {
+            marks,
+            ligature_coverage: Coverage::parse(table_data, ligature_coverage_offset)?,
+            ligature_anchors,
+        })
+    }
+
+    /// The anchor on the mark, and the one on the ligature which it lines up with. The mark
+    /// attaches to the given component, or to the last one when it isn't known.
+    pub fn anchors(
+        &self,
+        mark: u16,
+        ligature: u16,
+        component: Option<u16>,
+    ) -> Option<(Anchor, Anchor)> {
+        let (class, mark_anchor) = self.marks.get(mark)?;
+        let ligature_idx = self.ligature_coverage.index(ligature)?;
+        let components = self.ligature_anchors.get(ligature_idx as usize)?;
+        let component = component
+            .and_then(|component| components.get(component as usize))
+            .or_else(|| components.last())?;
+        let ligature_anchor = (*component.get(class as usize)?)?;
+        Some((mark_anchor, ligature_anchor))
+    }
+}
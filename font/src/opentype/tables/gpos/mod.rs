@@ -0,0 +1,646 @@
+//! The glyph positioning table moves glyphs relative to each other, for kerning, placing marks on
+//! the glyphs they belong to, and joining the letters of cursive scripts.
+
+mod cursive;
+mod mark;
+mod pair;
+mod single;
+mod value_record;
+
+use self::cursive::CursiveAttachment;
+use self::mark::{MarkAttachment, MarkToLigature};
+use self::pair::PairAdjustment;
+use self::single::SingleAdjustment;
+use self::value_record::Anchor;
+use opentype::tables::gdef::{GdefTable, GlyphClass};
+use opentype::tables::layout::buffer::Matcher;
+use opentype::tables::layout::context::Context;
+use opentype::tables::layout::lookup_list::{Lookup, LookupSubtable, RIGHT_TO_LEFT};
+use opentype::tables::layout::{Direction, GlyphInfo, GlyphPosition, LayoutTable, ParseError};
+use opentype::types::{Offset32, Reader};
+
+#[derive(Debug)]
+pub struct GposTable {
+    table: LayoutTable<Subtable>,
+}
+
+#[derive(Debug)]
+enum Subtable {
+    Single(SingleAdjustment),
+    Pair(PairAdjustment),
+    Cursive(CursiveAttachment),
+    MarkToBase(MarkAttachment),
+    MarkToLigature(MarkToLigature),
+    MarkToMark(MarkAttachment),
+    /// Both contextual and chained contextual positioning.
+    Context(Context),
+}
+
+impl LookupSubtable for Subtable {
+    fn parse(table_data: &[u8], offset: usize, lookup_type: u16) -> Result<Self, ParseError> {
+        Ok(match lookup_type {
+            1 => Subtable::Single(SingleAdjustment::parse(table_data, offset)?),
+            2 => Subtable::Pair(PairAdjustment::parse(table_data, offset)?),
+            3 => Subtable::Cursive(CursiveAttachment::parse(table_data, offset)?),
+            4 => Subtable::MarkToBase(MarkAttachment::parse(table_data, offset)?),
+            5 => Subtable::MarkToLigature(MarkToLigature::parse(table_data, offset)?),
+            6 => Subtable::MarkToMark(MarkAttachment::parse(table_data, offset)?),
+            7 => Subtable::Context(Context::parse(table_data, offset)?),
+            8 => Subtable::Context(Context::parse_chained(table_data, offset)?),
+            9 => {
+                // An extension holds a subtable of another type at a 32 bit offset, for tables
+                // too large for 16 bit offsets.
+                let mut reader = Reader::at(table_data, offset);
+                if reader.read_u16()? != 1 {
+                    return Err(ParseError::UnknownFormat);
+                }
+                let extension_type = reader.read_u16()?;
+                let extension_offset = reader.read::<usize, Offset32>()?;
+                if extension_type == 9 {
+                    return Err(ParseError::UnknownFormat);
+                }
+                Self::parse(table_data, offset + extension_offset, extension_type)?
+            }
+            _ => return Err(ParseError::UnknownFormat),
+        })
+    }
+}
+
+impl GposTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        Ok(Self {
+            table: LayoutTable::parse(table_data)?,
+        })
+    }
+
+    /// Whether the table has positioning for the script itself, rather than only for the
+    /// default script. See `LayoutTable::has_script`.
+    pub fn has_script(&self, script: [char; 4]) -> bool {
+        self.table.has_script(script)
+    }
+
+    /// The lookups for the given features, with their masks. See `LayoutTable::lookups_for`.
+    pub fn lookups_for(
+        &self,
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[([char; 4], u32)],
+        required: bool,
+        coords: &[f32],
+    ) -> Vec<(u16, u32)> {
+        self.table
+            .lookups_for(script, language, features, required, coords)
+    }
+
+    /// Apply lookups, as chosen by `lookups_for`, to the positions of the glyphs. The positions
+    /// should start out with each glyph's advance.
+    pub fn apply(
+        &self,
+        gdef: Option<&GdefTable>,
+        glyphs: &[GlyphInfo],
+        positions: &mut [GlyphPosition],
+        lookups: &[(u16, u32)],
+        direction: Direction,
+    ) {
+        let mut positioner = Positioner {
+            gpos: self,
+            gdef,
+            glyphs,
+            direction,
+            attachments: vec![None; glyphs.len()],
+        };
+        for &(lookup_index, mask) in lookups {
+            positioner.apply_lookup(positions, lookup_index, mask);
+        }
+        positioner.resolve_attachments(positions);
+    }
+}
+
+/// A glyph whose position depends on another's, which is only known once every lookup has been
+/// applied.
+#[derive(Clone, Copy, Debug)]
+struct Attachment {
+    to: usize,
+    kind: AttachmentKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum AttachmentKind {
+    /// A mark, whose offset so far is from the glyph it attaches to.
+    Mark,
+    /// A glyph in a cursive chain, whose vertical offset so far is from the glyph it joins.
+    Cursive,
+}
+
+/// Applies lookups from one GPOS table to the positions of a run of glyphs.
+struct Positioner<'a> {
+    gpos: &'a GposTable,
+    gdef: Option<&'a GdefTable>,
+    glyphs: &'a [GlyphInfo],
+    direction: Direction,
+    attachments: Vec<Option<Attachment>>,
+}
+
+impl<'a> Positioner<'a> {
+    /// How deeply contextual lookups may apply other contextual lookups.
+    const MAX_NESTING_LEVEL: usize = 6;
+
+    /// Apply a lookup to every glyph whose mask shares a bit with `mask`.
+    fn apply_lookup(&mut self, positions: &mut [GlyphPosition], lookup_index: u16, mask: u32) {
+        let lookup = match self.gpos.table.lookups.get(lookup_index) {
+            Some(lookup) => lookup,
+            None => return,
+        };
+        let matcher = Matcher::new(self.gdef, lookup);
+
+        let mut pos = 0;
+        while pos < self.glyphs.len() {
+            let glyph = self.glyphs[pos];
+            if glyph.mask & mask != 0 && !matcher.skips(glyph.glyph_id) {
+                if let Some(next) = self.apply_at(lookup, &matcher, positions, pos, 0) {
+                    pos = next;
+                    continue;
+                }
+            }
+            pos += 1;
+        }
+    }
+
+    /// Apply the first of the lookup's subtables which matches at `pos`. Returns the position to
+    /// carry on from.
+    fn apply_at(
+        &mut self,
+        lookup: &'a Lookup<Subtable>,
+        matcher: &Matcher,
+        positions: &mut [GlyphPosition],
+        pos: usize,
+        depth: usize,
+    ) -> Option<usize> {
+        for subtable in &lookup.subtables {
+            let next = self.apply_subtable(subtable, lookup.flag, matcher, positions, pos, depth);
+            if next.is_some() {
+                return next;
+            }
+        }
+        None
+    }
+
+    fn apply_subtable(
+        &mut self,
+        subtable: &'a Subtable,
+        flag: u16,
+        matcher: &Matcher,
+        positions: &mut [GlyphPosition],
+        pos: usize,
+        depth: usize,
+    ) -> Option<usize> {
+        let glyphs = self.glyphs;
+        let glyph_id = glyphs[pos].glyph_id;
+        match subtable {
+            Subtable::Single(single) => {
+                single.adjustment(glyph_id)?.apply_to(&mut positions[pos]);
+                Some(pos + 1)
+            }
+            Subtable::Pair(pair) => {
+                let second = matcher.next(glyphs, pos)?;
+                let (first_value, second_value, adjusts_second) =
+                    pair.adjustments(glyph_id, glyphs[second].glyph_id)?;
+                first_value.apply_to(&mut positions[pos]);
+                second_value.apply_to(&mut positions[second]);
+                // A second glyph which wasn't adjusted can still start a pair of its own.
+                Some(if adjusts_second { second + 1 } else { second })
+            }
+            Subtable::Cursive(cursive) => {
+                let entry = cursive.entry(glyph_id)?;
+                let prev = matcher.prev(glyphs, pos)?;
+                let exit = cursive.exit(glyphs[prev].glyph_id)?;
+                self.attach_cursive(positions, prev, exit, pos, entry, flag);
+                Some(pos + 1)
+            }
+            Subtable::MarkToBase(mark_to_base) => {
+                let base = self.find_base(pos)?;
+                let (mark_anchor, base_anchor) =
+                    mark_to_base.anchors(glyph_id, glyphs[base].glyph_id)?;
+                self.attach_mark(positions, pos, mark_anchor, base, base_anchor);
+                Some(pos + 1)
+            }
+            Subtable::MarkToLigature(mark_to_ligature) => {
+                let ligature = self.find_base(pos)?;
+                let (mark_anchor, ligature_anchor) = mark_to_ligature.anchors(
+                    glyph_id,
+                    glyphs[ligature].glyph_id,
+                    glyphs[pos].ligature_component,
+                )?;
+                self.attach_mark(positions, pos, mark_anchor, ligature, ligature_anchor);
+                Some(pos + 1)
+            }
+            Subtable::MarkToMark(mark_to_mark) => {
+                let prev = matcher.prev(glyphs, pos)?;
+                if !self.is_mark(glyphs[prev].glyph_id) {
+                    return None;
+                }
+                let (mark_anchor, prev_anchor) =
+                    mark_to_mark.anchors(glyph_id, glyphs[prev].glyph_id)?;
+                self.attach_mark(positions, pos, mark_anchor, prev, prev_anchor);
+                Some(pos + 1)
+            }
+            Subtable::Context(context) => {
+                let (sequence, lookup_records) = context.match_at(matcher, glyphs, pos)?;
+                if depth < Self::MAX_NESTING_LEVEL {
+                    for record in lookup_records {
+                        let seq_pos = match sequence.get(record.sequence_index as usize) {
+                            Some(&seq_pos) => seq_pos,
+                            None => continue,
+                        };
+                        let lookup = match self.gpos.table.lookups.get(record.lookup_index) {
+                            Some(lookup) => lookup,
+                            None => continue,
+                        };
+                        let matcher = Matcher::new(self.gdef, lookup);
+                        self.apply_at(lookup, &matcher, positions, seq_pos, depth + 1);
+                    }
+                }
+                Some(sequence[sequence.len() - 1] + 1)
+            }
+        }
+    }
+
+    fn is_mark(&self, glyph_id: u16) -> bool {
+        match self.gdef {
+            Some(gdef) => gdef.glyph_class(glyph_id) == Some(GlyphClass::Mark),
+            None => false,
+        }
+    }
+
+    /// The glyph a mark at `pos` attaches to: the nearest before it which isn't a mark.
+    fn find_base(&self, pos: usize) -> Option<usize> {
+        (0..pos)
+            .rev()
+            .find(|&idx| !self.is_mark(self.glyphs[idx].glyph_id))
+    }
+
+    /// Line up the mark at `pos` with an anchor on the glyph at `to`.
+    fn attach_mark(
+        &mut self,
+        positions: &mut [GlyphPosition],
+        pos: usize,
+        mark_anchor: Anchor,
+        to: usize,
+        to_anchor: Anchor,
+    ) {
+        positions[pos].x_offset = i32::from(to_anchor.x) - i32::from(mark_anchor.x);
+        positions[pos].y_offset = i32::from(to_anchor.y) - i32::from(mark_anchor.y);
+        self.attachments[pos] = Some(Attachment {
+            to,
+            kind: AttachmentKind::Mark,
+        });
+    }
+
+    /// Join the exit anchor of the glyph at `prev` to the entry anchor of the one at `pos`.
+    fn attach_cursive(
+        &mut self,
+        positions: &mut [GlyphPosition],
+        prev: usize,
+        exit: Anchor,
+        pos: usize,
+        entry: Anchor,
+        flag: u16,
+    ) {
+        let (exit_x, exit_y) = (i32::from(exit.x), i32::from(exit.y));
+        let (entry_x, entry_y) = (i32::from(entry.x), i32::from(entry.y));
+
+        // Close up the gap between the two glyphs by adjusting their advances.
+        match self.direction {
+            Direction::LeftToRight => {
+                positions[prev].x_advance = exit_x + positions[prev].x_offset;
+                let delta = entry_x + positions[pos].x_offset;
+                positions[pos].x_advance -= delta;
+                positions[pos].x_offset -= delta;
+            }
+            Direction::RightToLeft => {
+                let delta = exit_x + positions[prev].x_offset;
+                positions[prev].x_advance -= delta;
+                positions[prev].x_offset -= delta;
+                positions[pos].x_advance = entry_x + positions[pos].x_offset;
+            }
+        }
+
+        // The later glyph moves up or down to meet the earlier one, unless the lookup says the
+        // chain hangs from its last glyph.
+        let (child, parent, y_offset) = if flag & RIGHT_TO_LEFT != 0 {
+            (prev, pos, entry_y - exit_y)
+        } else {
+            (pos, prev, exit_y - entry_y)
+        };
+        positions[child].y_offset = y_offset;
+        self.attachments[child] = Some(Attachment {
+            to: parent,
+            kind: AttachmentKind::Cursive,
+        });
+    }
+
+    /// Turn the offsets of attached glyphs, which are relative to the glyphs they attach to, into
+    /// offsets from their own pen positions.
+    fn resolve_attachments(&self, positions: &mut [GlyphPosition]) {
+        let mut resolved = vec![false; positions.len()];
+        for start in 0..positions.len() {
+            // Follow the chain of attachments to a glyph which is already in place, then work back
+            // along it.
+            let mut chain = vec![];
+            let mut idx = start;
+            while !resolved[idx] {
+                resolved[idx] = true;
+                chain.push(idx);
+                match self.attachments[idx] {
+                    Some(attachment) => idx = attachment.to,
+                    None => break,
+                }
+            }
+            for &idx in chain.iter().rev() {
+                if let Some(attachment) = self.attachments[idx] {
+                    self.resolve_attachment(positions, idx, attachment);
+                }
+            }
+        }
+    }
+
+    fn resolve_attachment(
+        &self,
+        positions: &mut [GlyphPosition],
+        idx: usize,
+        attachment: Attachment,
+    ) {
+        let parent = positions[attachment.to];
+        positions[idx].y_offset += parent.y_offset;
+        if attachment.kind == AttachmentKind::Cursive {
+            return;
+        }
+
+        positions[idx].x_offset += parent.x_offset;
+        // Take away the distance the pen moves between the glyph the mark attaches to and the
+        // mark itself.
+        if attachment.to < idx {
+            match self.direction {
+                Direction::LeftToRight => {
+                    for k in attachment.to..idx {
+                        positions[idx].x_offset -= positions[k].x_advance;
+                    }
+                }
+                Direction::RightToLeft => {
+                    for k in attachment.to + 1..=idx {
+                        positions[idx].x_offset += positions[k].x_advance;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A table for 'latn' whose 'kern' moves glyph 2 closer to 1 by glyph, and 4 closer to 3 by
+    /// class, whose 'mark' and 'mkmk' attach mark 10 to base 1 and mark 11 to mark 10, whose
+    /// 'curs' joins glyphs 20, and whose 'sups' raises glyph 1 through an extension.
+    const SAMPLE_TABLE: [u8; 384] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x26, 0x00, 0x66,
+        // 10: script list: latn with features 0 to 4
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04,
+        // 38: feature list: curs, kern, mark, mkmk and sups
+        0x00, 0x05, 0x63, 0x75, 0x72, 0x73, 0x00, 0x20, 0x6B, 0x65, 0x72, 0x6E, 0x00, 0x26, 0x6D,
+        0x61, 0x72, 0x6B, 0x00, 0x2E, 0x6D, 0x6B, 0x6D, 0x6B, 0x00, 0x34, 0x73, 0x75, 0x70, 0x73,
+        0x00, 0x3A, 0x00, 0x00, 0x00, 0x01, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x05, // 102: lookup list
+        0x00, 0x06, 0x00, 0x0E, 0x00, 0x2E, 0x00, 0x6C, 0x00, 0xA2, 0x00, 0xD8, 0x00, 0xFC,
+        // 116: lookup 0: pair adjustment by glyph, 1 then 2 moves closer by 50
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0C, 0x00, 0x04, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x12, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02,
+        0xFF, 0xCE,
+        // 148: lookup 1: pair adjustment by class, 3 then 4 moves closer by 20 and the 4 right by 5
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x20, 0x00, 0x04, 0x00,
+        0x01, 0x00, 0x26, 0x00, 0x2E, 0x00, 0x02, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xEC, 0x00, 0x05, 0x00, 0x01, 0x00, 0x01, 0x00,
+        0x03, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x04, 0x00, 0x01,
+        0x00, 0x01, // 210: lookup 2: mark 10 to base 1
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0C, 0x00, 0x12, 0x00,
+        0x01, 0x00, 0x18, 0x00, 0x24, 0x00, 0x01, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x01,
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x64, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x04, 0x00, 0x01, 0x01, 0x2C, 0x01, 0xF4,
+        // 264: lookup 3: mark 11 to mark 10
+        0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0C, 0x00, 0x12, 0x00,
+        0x01, 0x00, 0x18, 0x00, 0x24, 0x00, 0x01, 0x00, 0x01, 0x00, 0x0B, 0x00, 0x01, 0x00, 0x01,
+        0x00, 0x0A, 0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x32, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x04, 0x00, 0x01, 0x00, 0x64, 0x02, 0xBC,
+        // 318: lookup 4: cursive attachment of glyph 20
+        0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x01, 0x00,
+        0x10, 0x00, 0x16, 0x00, 0x01, 0x00, 0x01, 0x00, 0x14, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x01, 0xF4, 0x00, 0x64,
+        // 354: lookup 5: extension holding a single adjustment raising glyph 1 by 300
+        0x00, 0x09, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x01, 0x2C, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01,
+    ];
+
+    /// Glyphs 1 to 4 and 20 are base glyphs, and 10 and 11 are marks.
+    const GDEF_TABLE: [u8; 34] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // header
+        0x00, 0x02, 0x00, 0x03, 0x00, 0x01, 0x00, 0x04, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x0B, 0x00,
+        0x03, 0x00, 0x14, 0x00, 0x14, 0x00, 0x01,
+    ];
+
+    /// A table for 'latn' whose 'mark' attaches mark 10 to ligature 30, at (200, 700) on its first
+    /// component and (800, 700) on its second.
+    const LIGATURE_TABLE: [u8; 114] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1E, 0x00, 0x2C,
+        // 10: script list: latn with every feature
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x01, 0x00, 0x00, // 30: feature list: mark uses lookup 0
+        0x00, 0x01, 0x6D, 0x61, 0x72, 0x6B, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 44: lookup list
+        0x00, 0x01, 0x00, 0x04, // 48: lookup 0: mark 10 to the components of ligature 30
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0C, 0x00, 0x12, 0x00,
+        0x01, 0x00, 0x18, 0x00, 0x24, 0x00, 0x01, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x01,
+        0x00, 0x1E, 0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x64, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x04, 0x00, 0x02, 0x00, 0x06, 0x00, 0x0C, 0x00, 0x01, 0x00, 0xC8, 0x02, 0xBC,
+        0x00, 0x01, 0x03, 0x20, 0x02, 0xBC,
+    ];
+
+    const LATN: [char; 4] = ['l', 'a', 't', 'n'];
+
+    /// Position glyphs which each start with an advance of 600, or none for marks.
+    fn position(
+        glyph_ids: &[u16],
+        features: &[[char; 4]],
+        direction: Direction,
+    ) -> Vec<(i32, i32, i32)> {
+        let gpos = GposTable::parse(&SAMPLE_TABLE).unwrap();
+        let gdef = GdefTable::parse(&GDEF_TABLE).unwrap();
+        let features: Vec<_> = features.iter().map(|&tag| (tag, !0)).collect();
+        let lookups = gpos.lookups_for(LATN, None, &features, true, &[]);
+        let glyphs: Vec<_> = glyph_ids
+            .iter()
+            .enumerate()
+            .map(|(cluster, &glyph_id)| GlyphInfo::new(glyph_id, cluster))
+            .collect();
+        let mut positions: Vec<_> = glyph_ids
+            .iter()
+            .map(|&glyph_id| GlyphPosition {
+                x_advance: if glyph_id == 10 || glyph_id == 11 {
+                    0
+                } else {
+                    600
+                },
+                ..GlyphPosition::default()
+            })
+            .collect();
+        gpos.apply(Some(&gdef), &glyphs, &mut positions, &lookups, direction);
+        positions
+            .iter()
+            .map(|position| (position.x_advance, position.x_offset, position.y_offset))
+            .collect()
+    }
+
+    #[test]
+    fn pair_kerning() {
+        let kern = ['k', 'e', 'r', 'n'];
+
+        assert_eq!(
+            position(&[1, 2, 3, 4], &[kern], Direction::LeftToRight),
+            vec![(550, 0, 0), (600, 0, 0), (580, 0, 0), (600, 5, 0)]
+        );
+        assert_eq!(
+            position(&[2, 1, 4, 3], &[kern], Direction::LeftToRight),
+            vec![(600, 0, 0); 4]
+        );
+    }
+
+    #[test]
+    fn single_through_extension() {
+        assert_eq!(
+            position(&[1, 2], &[['s', 'u', 'p', 's']], Direction::LeftToRight),
+            vec![(600, 0, 300), (600, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn marks() {
+        let features = [['m', 'a', 'r', 'k'], ['m', 'k', 'm', 'k']];
+
+        // Mark 10's anchor lines up with the base's at (300, 500), and mark 11's with mark 10's
+        // at (300, 1200).
+        assert_eq!(
+            position(&[1, 10, 11], &features, Direction::LeftToRight),
+            vec![(600, 0, 0), (0, -400, 500), (0, -350, 1200)]
+        );
+        assert_eq!(
+            position(&[1, 10, 11], &features, Direction::RightToLeft),
+            vec![(600, 0, 0), (0, 200, 500), (0, 250, 1200)]
+        );
+        // Mark 11 only attaches to mark 10.
+        assert_eq!(
+            position(&[1, 11], &features, Direction::LeftToRight),
+            vec![(600, 0, 0), (0, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn marks_on_ligature_components() {
+        let gpos = GposTable::parse(&LIGATURE_TABLE).unwrap();
+        let gdef = GdefTable::parse(&GDEF_TABLE).unwrap();
+        let lookups = gpos.lookups_for(LATN, None, &[(['m', 'a', 'r', 'k'], !0)], true, &[]);
+        let glyphs = [
+            GlyphInfo::new(30, 0),
+            GlyphInfo {
+                ligature_component: Some(0),
+                ..GlyphInfo::new(10, 0)
+            },
+            GlyphInfo::new(10, 0),
+        ];
+        let mut positions = [
+            GlyphPosition {
+                x_advance: 1200,
+                ..GlyphPosition::default()
+            },
+            GlyphPosition::default(),
+            GlyphPosition::default(),
+        ];
+        gpos.apply(
+            Some(&gdef),
+            &glyphs,
+            &mut positions,
+            &lookups,
+            Direction::LeftToRight,
+        );
+
+        // The mark's anchor at (100, 0) lines up with the first component's when it followed
+        // that component, and with the last component's otherwise.
+        assert_eq!(
+            positions
+                .iter()
+                .map(|position| (position.x_offset, position.y_offset))
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (-1100, 700), (-500, 700)]
+        );
+    }
+
+    #[test]
+    fn cursive() {
+        // Each glyph's entry at (0, 0) joins the previous one's exit at (500, 100).
+        assert_eq!(
+            position(
+                &[20, 20, 20],
+                &[['c', 'u', 'r', 's']],
+                Direction::LeftToRight
+            ),
+            vec![(500, 0, 0), (500, 0, 100), (600, 0, 200)]
+        );
+    }
+
+    #[test]
+    fn skip_unknown_lookup_type() {
+        // Give lookup 1, the pair adjustment by class, a type which isn't defined.
+        let mut data = SAMPLE_TABLE;
+        data[149] = 10;
+        let gpos = GposTable::parse(&data).unwrap();
+        let lookups = gpos.lookups_for(LATN, None, &[(['k', 'e', 'r', 'n'], !0)], true, &[]);
+        let glyphs: Vec<_> = [1, 2, 3, 4]
+            .iter()
+            .enumerate()
+            .map(|(cluster, &glyph_id)| GlyphInfo::new(glyph_id, cluster))
+            .collect();
+        let mut positions = vec![
+            GlyphPosition {
+                x_advance: 600,
+                ..GlyphPosition::default()
+            };
+            4
+        ];
+        gpos.apply(
+            None,
+            &glyphs,
+            &mut positions,
+            &lookups,
+            Direction::LeftToRight,
+        );
+
+        assert_eq!(
+            positions
+                .iter()
+                .map(|position| position.x_advance)
+                .collect::<Vec<_>>(),
+            vec![550, 600, 600, 600]
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert_eq!(
+            GposTable::parse(&SAMPLE_TABLE[..200]).unwrap_err(),
+            ParseError::Truncated(200)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Pair adjustment moves two glyphs which appear next to each other, most often to kern them.
+
+use super::value_record::ValueRecord;
+use opentype::tables::layout::{ClassDef, Coverage, ParseError};
+use opentype::types::{Offset16, Reader};
+
+#[derive(Debug)]
+pub struct PairAdjustment {
+    coverage: Coverage,
+    /// Whether there are adjustments for the second glyph. If not, the second glyph can start
+    /// another pair.
+    adjusts_second: bool,
+    pairs: Pairs,
+}
+
+#[derive(Debug)]
+enum Pairs {
+    /// Format 1: for each covered first glyph by coverage index, the second glyphs it pairs with,
+    /// sorted by glyph ID.
+    Glyphs(Vec<Vec<(u16, ValueRecord, ValueRecord)>>),
+    /// Format 2: the adjustments for each pair of classes, row by row for the first glyph's
+    /// class. Empty if neither glyph is adjusted.
+    Classes {
+        first_classes: ClassDef,
+        second_classes: ClassDef,
+        second_class_count: usize,
+        values: Vec<(ValueRecord, ValueRecord)>,
+    },
+}
+
+impl PairAdjustment {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let format = reader.read_u16()?;
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let coverage = Coverage::parse(table_data, offset + coverage_offset)?;
+        let value_format_1 = reader.read_u16()?;
+        let value_format_2 = reader.read_u16()?;
+
+        let pairs = match format {
+            1 => {
+                let pair_set_count = reader.read_u16()? as usize;
+                let pair_set_offsets = reader.read_array::<usize, Offset16>(pair_set_count)?;
+                let mut pair_sets = Vec::with_capacity(pair_set_count);
+                for pair_set_offset in pair_set_offsets {
+                    let mut reader = Reader::at(table_data, offset + pair_set_offset);
+                    let pair_value_count = reader.read_u16()?;
+                    let mut pairs = Vec::with_capacity(pair_value_count as usize);
+                    for _ in 0..pair_value_count {
+                        pairs.push((
+                            reader.read_u16()?,
+                            ValueRecord::parse(&mut reader, value_format_1)?,
+                            ValueRecord::parse(&mut reader, value_format_2)?,
+                        ));
+                    }
+                    pair_sets.push(pairs);
+                }
+                Pairs::Glyphs(pair_sets)
+            }
+            2 => {
+                let class_def_1_offset = reader.read::<usize, Offset16>()?;
+                let class_def_2_offset = reader.read::<usize, Offset16>()?;
+                let first_class_count = reader.read_u16()? as usize;
+                let second_class_count = reader.read_u16()? as usize;
+
+                // Records without any values take up no space, so the counts can't be trusted to
+                // describe data which is really there.
+                let record_size =
+                    ValueRecord::size(value_format_1) + ValueRecord::size(value_format_2);
+                let remaining = table_data.len().saturating_sub(reader.offset());
+                let mut values = vec![];
+                if let Some(available) = remaining.checked_div(record_size) {
+                    let count = first_class_count * second_class_count;
+                    values.reserve(count.min(available));
+                    for _ in 0..count {
+                        values.push((
+                            ValueRecord::parse(&mut reader, value_format_1)?,
+                            ValueRecord::parse(&mut reader, value_format_2)?,
+                        ));
+                    }
+                }
+                Pairs::Classes {
+                    first_classes: ClassDef::parse(table_data, offset + class_def_1_offset)?,
+                    second_classes: ClassDef::parse(table_data, offset + class_def_2_offset)?,
+                    second_class_count,
+                    values,
+                }
+            }
+            _ => return Err(ParseError::UnknownFormat),
+        };
+
+        Ok(Self {
+            coverage,
+            adjusts_second: value_format_2 != 0,
+            pairs,
+        })
+    }
+
+    /// The adjustments for a pair of glyphs, and whether the second glyph is adjusted.
+    pub fn adjustments(&self, first: u16, second: u16) -> Option<(ValueRecord, ValueRecord, bool)> {
+        let idx = self.coverage.index(first)?;
+        let (first_value, second_value) = match &self.pairs {
+            Pairs::Glyphs(pair_sets) => {
+                let pairs = pair_sets.get(idx as usize)?;
+                let idx = pairs
+                    .binary_search_by_key(&second, |&(glyph, _, _)| glyph)
+                    .ok()?;
+                (pairs[idx].1, pairs[idx].2)
+            }
+            Pairs::Classes {
+                first_classes,
+                second_classes,
+                second_class_count,
+                values,
+            } => {
+                let first_class = first_classes.class(first) as usize;
+                let second_class = second_classes.class(second) as usize;
+                if second_class >= *second_class_count {
+                    return None;
+                }
+                values
+                    .get(first_class * second_class_count + second_class)
+                    .cloned()
+                    .unwrap_or_default()
+            }
+        };
+        Some((first_value, second_value, self.adjusts_second))
+    }
+}
@@ -0,0 +1,56 @@
+//! Single adjustment moves or resizes individual glyphs, such as raising a glyph for superscript.
+
+use super::value_record::ValueRecord;
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader};
+
+#[derive(Debug)]
+pub enum SingleAdjustment {
+    /// Format 1: the same adjustment for every covered glyph.
+    Same {
+        coverage: Coverage,
+        value: ValueRecord,
+    },
+    /// Format 2: an adjustment for each covered glyph, by coverage index.
+    Each {
+        coverage: Coverage,
+        values: Vec<ValueRecord>,
+    },
+}
+
+impl SingleAdjustment {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let format = reader.read_u16()?;
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let coverage = Coverage::parse(table_data, offset + coverage_offset)?;
+        let value_format = reader.read_u16()?;
+        match format {
+            1 => Ok(SingleAdjustment::Same {
+                coverage,
+                value: ValueRecord::parse(&mut reader, value_format)?,
+            }),
+            2 => {
+                let value_count = reader.read_u16()?;
+                let mut values = Vec::with_capacity(value_count as usize);
+                for _ in 0..value_count {
+                    values.push(ValueRecord::parse(&mut reader, value_format)?);
+                }
+                Ok(SingleAdjustment::Each { coverage, values })
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    pub fn adjustment(&self, glyph_id: u16) -> Option<ValueRecord> {
+        match self {
+            SingleAdjustment::Same { coverage, value } => {
+                coverage.index(glyph_id)?;
+                Some(*value)
+            }
+            SingleAdjustment::Each { coverage, values } => {
+                values.get(coverage.index(glyph_id)? as usize).cloned()
+            }
+        }
+    }
+}
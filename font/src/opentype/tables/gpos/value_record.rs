@@ -0,0 +1,127 @@
+//! Value records adjust a glyph's position and advance, and anchors mark the points where glyphs
+//! attach to each other.
+
+use opentype::tables::layout::{GlyphPosition, ParseError};
+use opentype::types::Reader;
+
+/// An adjustment to a glyph's position, in font units.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ValueRecord {
+    pub x_placement: i16,
+    pub y_placement: i16,
+    pub x_advance: i16,
+    pub y_advance: i16,
+}
+
+/// A point on a glyph, in font units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anchor {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl ValueRecord {
+    const X_PLACEMENT: u16 = 0x0001;
+    const Y_PLACEMENT: u16 = 0x0002;
+    const X_ADVANCE: u16 = 0x0004;
+    const Y_ADVANCE: u16 = 0x0008;
+    /// The device and variation tables which can follow the values, which only adjust them for
+    /// particular sizes or variable font instances.
+    const DEVICE_OFFSETS: u16 = 0x00F0;
+
+    /// Read a value record holding the fields which `value_format` gives.
+    pub fn parse(reader: &mut Reader, value_format: u16) -> Result<Self, ParseError> {
+        let mut field = |flag| -> Result<i16, ParseError> {
+            if value_format & flag != 0 {
+                Ok(reader.read_i16()?)
+            } else {
+                Ok(0)
+            }
+        };
+        let record = Self {
+            x_placement: field(Self::X_PLACEMENT)?,
+            y_placement: field(Self::Y_PLACEMENT)?,
+            x_advance: field(Self::X_ADVANCE)?,
+            y_advance: field(Self::Y_ADVANCE)?,
+        };
+        reader.skip(2 * (value_format & Self::DEVICE_OFFSETS).count_ones() as usize)?;
+        Ok(record)
+    }
+
+    /// The number of bytes a value record with the given format takes up.
+    pub fn size(value_format: u16) -> usize {
+        2 * (value_format & 0x00FF).count_ones() as usize
+    }
+
+    pub fn apply_to(&self, position: &mut GlyphPosition) {
+        position.x_offset += i32::from(self.x_placement);
+        position.y_offset += i32::from(self.y_placement);
+        position.x_advance += i32::from(self.x_advance);
+        position.y_advance += i32::from(self.y_advance);
+    }
+}
+
+impl Anchor {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        match reader.read_u16()? {
+            // Format 2 adds a contour point, and format 3 device tables, which only matter for
+            // hinted glyphs.
+            1..=3 => Ok(Self {
+                x: reader.read_i16()?,
+                y: reader.read_i16()?,
+            }),
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    /// Parse the anchor at `offset` from `base`, or give `None` if the offset is null.
+    pub fn parse_optional(
+        table_data: &[u8],
+        base: usize,
+        offset: usize,
+    ) -> Result<Option<Self>, ParseError> {
+        match offset {
+            0 => Ok(None),
+            offset => Self::parse(table_data, base + offset).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_value_record() {
+        // X placement and Y advance, with an X advance device table.
+        let data = [0xFF, 0xF6, 0x00, 0x14, 0x00, 0x00, 0x00, 0x07];
+        let mut reader = Reader::new(&data);
+        let record = ValueRecord::parse(&mut reader, 0x0049).unwrap();
+
+        assert_eq!(
+            record,
+            ValueRecord {
+                x_placement: -10,
+                y_placement: 0,
+                x_advance: 0,
+                y_advance: 20,
+            }
+        );
+        assert_eq!(reader.offset(), ValueRecord::size(0x0049));
+    }
+
+    #[test]
+    fn parse_anchor_formats() {
+        let data = [
+            0x00, 0x01, 0x00, 0x0A, 0xFF, 0xEC, // format 1
+            0x00, 0x02, 0x00, 0x0B, 0x00, 0x0C, 0x00, 0x03, // format 2
+            0x00, 0x03, 0x00, 0x0D, 0x00, 0x0E, 0x00, 0x00, 0x00, 0x00, // format 3
+        ];
+
+        assert_eq!(Anchor::parse(&data, 0), Ok(Anchor { x: 10, y: -20 }));
+        assert_eq!(Anchor::parse(&data, 6), Ok(Anchor { x: 11, y: 12 }));
+        assert_eq!(Anchor::parse(&data, 14), Ok(Anchor { x: 13, y: 14 }));
+        assert_eq!(Anchor::parse_optional(&data, 0, 0), Ok(None));
+    }
+}
@@ -0,0 +1,104 @@
+//! Ligature substitution replaces a sequence of glyphs with a single glyph, such as "f", "f" and
+//! "i" with "ffi".
+
+use opentype::tables::layout::buffer::{GlyphInfo, Matcher};
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub struct LigatureSubst {
+    coverage: Coverage,
+    /// The ligatures which start with each covered glyph, by coverage index, in order of
+    /// preference.
+    ligature_sets: Vec<Vec<Ligature>>,
+}
+
+#[derive(Debug)]
+struct Ligature {
+    glyph_id: u16,
+    /// The components after the first.
+    components: Vec<u16>,
+}
+
+impl LigatureSubst {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        if reader.read_u16()? != 1 {
+            return Err(ParseError::UnknownFormat);
+        }
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let ligature_set_count = reader.read_u16()? as usize;
+        let ligature_set_offsets = reader.read_array::<usize, Offset16>(ligature_set_count)?;
+
+        let mut ligature_sets = Vec::with_capacity(ligature_set_count);
+        for ligature_set_offset in ligature_set_offsets {
+            let ligature_set_offset = offset + ligature_set_offset;
+            let mut reader = Reader::at(table_data, ligature_set_offset);
+            let ligature_count = reader.read_u16()? as usize;
+            let ligature_offsets = reader.read_array::<usize, Offset16>(ligature_count)?;
+
+            let mut ligatures = Vec::with_capacity(ligature_count);
+            for ligature_offset in ligature_offsets {
+                let mut reader = Reader::at(table_data, ligature_set_offset + ligature_offset);
+                let glyph_id = reader.read_u16()?;
+                let component_count = reader.read_u16()? as usize;
+                let components =
+                    reader.read_array::<u16, U16>(component_count.saturating_sub(1))?;
+                ligatures.push(Ligature {
+                    glyph_id,
+                    components,
+                });
+            }
+            ligature_sets.push(ligatures);
+        }
+
+        Ok(Self {
+            coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+            ligature_sets,
+        })
+    }
+
+    /// Form the first ligature which matches the glyphs at `pos`. Any glyphs the lookup skipped
+    /// between its components, such as marks, are kept after it. Returns the position after the
+    /// ligature.
+    pub fn apply(
+        &self,
+        matcher: &Matcher,
+        glyphs: &mut Vec<GlyphInfo>,
+        pos: usize,
+    ) -> Option<usize> {
+        let idx = self.coverage.index(glyphs[pos].glyph_id)?;
+        let (ligature, positions) =
+            self.ligature_sets
+                .get(idx as usize)?
+                .iter()
+                .find_map(|ligature| {
+                    let positions = matcher.match_input(
+                        glyphs,
+                        pos,
+                        ligature.components.len() + 1,
+                        |idx, glyph| ligature.components[idx - 1] == glyph,
+                    )?;
+                    Some((ligature, positions))
+                })?;
+
+        // Everything the ligature covers, including skipped glyphs, now belongs to one cluster.
+        let last = *positions.last()?;
+        let cluster = glyphs[pos..=last].iter().map(|glyph| glyph.cluster).min()?;
+        for glyph in &mut glyphs[pos..=last] {
+            glyph.cluster = cluster;
+        }
+        // Glyphs skipped between components stay after the ligature, so remember which component
+        // each one followed.
+        for (component, pair) in positions.windows(2).enumerate() {
+            for glyph in &mut glyphs[pair[0] + 1..pair[1]] {
+                glyph.ligature_component = Some(component as u16);
+            }
+        }
+        glyphs[pos].glyph_id = ligature.glyph_id;
+        for &component in positions[1..].iter().rev() {
+            glyphs.remove(component);
+        }
+        Some(pos + 1)
+    }
+}
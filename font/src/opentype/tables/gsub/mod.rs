@@ -0,0 +1,448 @@
+//! The glyph substitution table replaces glyphs with others, for features such as ligatures,
+//! small caps and the positional forms of Arabic letters.
+
+mod ligature;
+mod reverse_chain;
+mod sequences;
+mod single;
+
+use self::ligature::LigatureSubst;
+use self::reverse_chain::ReverseChain;
+use self::sequences::Sequences;
+use self::single::Single;
+use opentype::tables::gdef::GdefTable;
+use opentype::tables::layout::buffer::{GlyphInfo, Matcher};
+use opentype::tables::layout::context::{Context, SequenceLookupRecord};
+use opentype::tables::layout::lookup_list::{Lookup, LookupSubtable};
+use opentype::tables::layout::{LayoutTable, ParseError};
+use opentype::types::{Offset32, Reader};
+
+#[derive(Debug)]
+pub struct GsubTable {
+    table: LayoutTable<Subtable>,
+}
+
+#[derive(Debug)]
+enum Subtable {
+    Single(Single),
+    Multiple(Sequences),
+    Alternate(Sequences),
+    Ligature(LigatureSubst),
+    /// Both contextual and chained contextual substitution.
+    Context(Context),
+    ReverseChain(ReverseChain),
+}
+
+impl LookupSubtable for Subtable {
+    fn parse(table_data: &[u8], offset: usize, lookup_type: u16) -> Result<Self, ParseError> {
+        Ok(match lookup_type {
+            1 => Subtable::Single(Single::parse(table_data, offset)?),
+            2 => Subtable::Multiple(Sequences::parse(table_data, offset)?),
+            3 => Subtable::Alternate(Sequences::parse(table_data, offset)?),
+            4 => Subtable::Ligature(LigatureSubst::parse(table_data, offset)?),
+            5 => Subtable::Context(Context::parse(table_data, offset)?),
+            6 => Subtable::Context(Context::parse_chained(table_data, offset)?),
+            7 => {
+                // An extension holds a subtable of another type at a 32 bit offset, for tables
+                // too large for 16 bit offsets.
+                let mut reader = Reader::at(table_data, offset);
+                if reader.read_u16()? != 1 {
+                    return Err(ParseError::UnknownFormat);
+                }
+                let extension_type = reader.read_u16()?;
+                let extension_offset = reader.read::<usize, Offset32>()?;
+                if extension_type == 7 {
+                    return Err(ParseError::UnknownFormat);
+                }
+                Self::parse(table_data, offset + extension_offset, extension_type)?
+            }
+            8 => Subtable::ReverseChain(ReverseChain::parse(table_data, offset)?),
+            _ => return Err(ParseError::UnknownFormat),
+        })
+    }
+}
+
+impl GsubTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        Ok(Self {
+            table: LayoutTable::parse(table_data)?,
+        })
+    }
+
+    /// Whether the table has substitutions for the script itself, rather than only for the
+    /// default script. See `LayoutTable::has_script`.
+    pub fn has_script(&self, script: [char; 4]) -> bool {
+        self.table.has_script(script)
+    }
+
+    /// The lookups for the given features, with their masks. See `LayoutTable::lookups_for`.
+    pub fn lookups_for(
+        &self,
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[([char; 4], u32)],
+        required: bool,
+        coords: &[f32],
+    ) -> Vec<(u16, u32)> {
+        self.table
+            .lookups_for(script, language, features, required, coords)
+    }
+
+    /// Apply lookups, as chosen by `lookups_for`, to the glyphs in order.
+    pub fn apply(
+        &self,
+        gdef: Option<&GdefTable>,
+        glyphs: &mut Vec<GlyphInfo>,
+        lookups: &[(u16, u32)],
+    ) {
+        let applier = Applier {
+            gsub: self,
+            gdef,
+            max_len: glyphs
+                .len()
+                .saturating_mul(Applier::MAX_LEN_FACTOR)
+                .max(Applier::MAX_LEN_MIN),
+        };
+        for &(lookup_index, mask) in lookups {
+            applier.apply_lookup(glyphs, lookup_index, mask);
+        }
+    }
+}
+
+/// Applies lookups from one GSUB table to a buffer of glyphs.
+struct Applier<'a> {
+    gsub: &'a GsubTable,
+    gdef: Option<&'a GdefTable>,
+    /// Multiple substitutions stop once the buffer reaches this length, so that a font can't grow
+    /// it without end.
+    max_len: usize,
+}
+
+impl<'a> Applier<'a> {
+    /// How deeply contextual lookups may apply other contextual lookups.
+    const MAX_NESTING_LEVEL: usize = 6;
+    const MAX_LEN_FACTOR: usize = 32;
+    const MAX_LEN_MIN: usize = 8192;
+
+    /// Apply a lookup to every glyph whose mask shares a bit with `mask`.
+    fn apply_lookup(&self, glyphs: &mut Vec<GlyphInfo>, lookup_index: u16, mask: u32) {
+        let lookup = match self.gsub.table.lookups.get(lookup_index) {
+            Some(lookup) => lookup,
+            None => return,
+        };
+        let matcher = Matcher::new(self.gdef, lookup);
+        let applies = |glyph: &GlyphInfo| glyph.mask & mask != 0 && !matcher.skips(glyph.glyph_id);
+
+        if let Some(Subtable::ReverseChain(_)) = lookup.subtables.first() {
+            for pos in (0..glyphs.len()).rev() {
+                if applies(&glyphs[pos]) {
+                    self.apply_at(lookup, &matcher, glyphs, pos, 0);
+                }
+            }
+            return;
+        }
+
+        let mut pos = 0;
+        while pos < glyphs.len() {
+            if applies(&glyphs[pos]) {
+                if let Some(next) = self.apply_at(lookup, &matcher, glyphs, pos, 0) {
+                    pos = next;
+                    continue;
+                }
+            }
+            pos += 1;
+        }
+    }
+
+    /// Apply the first of the lookup's subtables which matches at `pos`. Returns the position to
+    /// carry on from, which is only at `pos` itself if the glyph there was removed.
+    fn apply_at(
+        &self,
+        lookup: &Lookup<Subtable>,
+        matcher: &Matcher,
+        glyphs: &mut Vec<GlyphInfo>,
+        pos: usize,
+        depth: usize,
+    ) -> Option<usize> {
+        let glyph_id = glyphs[pos].glyph_id;
+        lookup.subtables.iter().find_map(|subtable| match subtable {
+            Subtable::Single(single) => {
+                glyphs[pos].glyph_id = single.substitute(glyph_id)?;
+                Some(pos + 1)
+            }
+            Subtable::Multiple(multiple) => {
+                let sequence = multiple.get(glyph_id)?;
+                if glyphs.len() + sequence.len() > self.max_len {
+                    return None;
+                }
+                let glyph = glyphs[pos];
+                let replacement = sequence
+                    .iter()
+                    .map(|&glyph_id| GlyphInfo { glyph_id, ..glyph });
+                glyphs.splice(pos..=pos, replacement);
+                Some(pos + sequence.len())
+            }
+            Subtable::Alternate(alternate) => {
+                glyphs[pos].glyph_id = *alternate.get(glyph_id)?.first()?;
+                Some(pos + 1)
+            }
+            Subtable::Ligature(ligature) => ligature.apply(matcher, glyphs, pos),
+            Subtable::Context(context) => {
+                let (positions, lookup_records) = context.match_at(matcher, glyphs, pos)?;
+                let end = self.apply_nested(glyphs, positions, lookup_records, depth);
+                Some(end.max(pos + 1))
+            }
+            Subtable::ReverseChain(reverse_chain) => reverse_chain.apply(matcher, glyphs, pos),
+        })
+    }
+
+    /// Apply the lookups of a contextual subtable to the input sequence it matched. Returns the
+    /// position after the sequence.
+    fn apply_nested(
+        &self,
+        glyphs: &mut Vec<GlyphInfo>,
+        mut positions: Vec<usize>,
+        lookup_records: &[SequenceLookupRecord],
+        depth: usize,
+    ) -> usize {
+        let mut end = positions[positions.len() - 1] + 1;
+        if depth >= Self::MAX_NESTING_LEVEL {
+            return end;
+        }
+
+        for record in lookup_records {
+            let idx = record.sequence_index as usize;
+            let pos = match positions.get(idx) {
+                Some(&pos) if pos < glyphs.len() => pos,
+                _ => continue,
+            };
+            let lookup = match self.gsub.table.lookups.get(record.lookup_index) {
+                Some(lookup) => lookup,
+                None => continue,
+            };
+            let matcher = Matcher::new(self.gdef, lookup);
+            let len = glyphs.len();
+            if self
+                .apply_at(lookup, &matcher, glyphs, pos, depth + 1)
+                .is_none()
+            {
+                continue;
+            }
+
+            // Glyphs added or removed by the lookup move the rest of the sequence along.
+            let delta = glyphs.len() as isize - len as isize;
+            if delta != 0 {
+                // If the glyph at `pos` was deleted, the next one has taken its place.
+                let first_later = if delta < 0 { pos } else { pos + 1 };
+                for later in &mut positions[idx + 1..] {
+                    *later = (*later as isize + delta).max(first_later as isize) as usize;
+                }
+                end = (end as isize + delta).max(pos as isize) as usize;
+            }
+        }
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A table for 'latn' whose 'liga' forms "ffi" (21) and "fi" (20) from "f" (10) and "i" (11),
+    /// whose 'smcp' adds 100 to glyphs 1 to 5 through an extension, and whose 'calt' replaces
+    /// glyph 3 with 6 and 7 when it comes before glyph 4.
+    const SAMPLE_TABLE: [u8; 222] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x22, 0x00, 0x48,
+        // 10: script list: latn with features 0, 1 and 2
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02,
+        // 34: feature list: calt uses lookup 2, liga lookup 0 and smcp lookup 1
+        0x00, 0x03, 0x63, 0x61, 0x6C, 0x74, 0x00, 0x14, 0x6C, 0x69, 0x67, 0x61, 0x00, 0x1A, 0x73,
+        0x6D, 0x63, 0x70, 0x00, 0x20, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, // 72: lookup list
+        0x00, 0x04, 0x00, 0x0A, 0x00, 0x34, 0x00, 0x54, 0x00, 0x7A,
+        // 82: lookup 0: ligatures f f i -> 21 and f i -> 20
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x02, 0x00, 0x06, 0x00, 0x0E, 0x00, 0x15,
+        0x00, 0x03, 0x00, 0x0A, 0x00, 0x0B, 0x00, 0x14, 0x00, 0x02, 0x00, 0x0B,
+        // 124: lookup 1: extension holding a single substitution adding 100 to glyphs 1 to 5
+        0x00, 0x07, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x01, 0x00, 0x06, 0x00, 0x64, 0x00, 0x02, 0x00, 0x01, 0x00, 0x01, 0x00, 0x05,
+        0x00, 0x00,
+        // 156: lookup 2: chained context, 3 followed by 4 applies lookup 3 to the 3
+        0x00, 0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x12, 0x00, 0x01, 0x00, 0x18, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x00, 0x01, 0x00, 0x01,
+        0x00, 0x03, 0x00, 0x01, 0x00, 0x01, 0x00, 0x04,
+        // 194: lookup 3: multiple substitution of 3 with 6 and 7
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x03, 0x00, 0x02, 0x00, 0x06, 0x00, 0x07,
+    ];
+
+    /// A table for 'latn' whose 'liga' forms ligature 30 from glyphs 1 and 2, skipping any marks
+    /// between them.
+    const MARK_SKIPPING_TABLE: [u8; 80] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1E, 0x00, 0x2C,
+        // 10: script list: latn with every feature
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x01, 0x00, 0x00, // 30: feature list: liga uses lookup 0
+        0x00, 0x01, 0x6C, 0x69, 0x67, 0x61, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 44: lookup list
+        0x00, 0x01, 0x00, 0x04,
+        // 48: lookup 0: ligature of 1 and 2 as 30, skipping marks
+        0x00, 0x04, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x04, 0x00, 0x1E, 0x00, 0x02,
+        0x00, 0x02,
+    ];
+
+    /// Glyphs 1 and 2 are base glyphs, 10 is a mark and 30 is a ligature.
+    const MARK_GDEF_TABLE: [u8; 40] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // 12: glyph classes
+        0x00, 0x02, 0x00, 0x04, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x02, 0x00,
+        0x01, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x03, 0x00, 0x1E, 0x00, 0x1E, 0x00, 0x02,
+    ];
+
+    /// A table for 'latn' whose 'calt' deletes glyph 1 and replaces glyph 2 with 20 when 1 comes
+    /// before 2.
+    const DELETING_CONTEXT_TABLE: [u8; 136] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1E, 0x00, 0x2C,
+        // 10: script list: latn with every feature
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x01, 0x00, 0x00, // 30: feature list: calt uses lookup 0
+        0x00, 0x01, 0x63, 0x61, 0x6C, 0x74, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 44: lookup list
+        0x00, 0x03, 0x00, 0x08, 0x00, 0x2E, 0x00, 0x46,
+        // 52: lookup 0: context, 1 followed by 2 applies lookup 1 to the 1 and lookup 2 to the 2
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x03, 0x00, 0x02, 0x00, 0x02, 0x00,
+        0x12, 0x00, 0x18, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x01,
+        0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02,
+        // 90: lookup 1: multiple substitution deleting 1
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00,
+        // 114: lookup 2: single substitution of 2 with 20
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x14, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02,
+    ];
+
+    const LATN: [char; 4] = ['l', 'a', 't', 'n'];
+
+    fn substitute(glyph_ids: &[u16], features: &[[char; 4]]) -> Vec<(u16, usize)> {
+        let gsub = GsubTable::parse(&SAMPLE_TABLE).unwrap();
+        let features: Vec<_> = features.iter().map(|&tag| (tag, !0)).collect();
+        let lookups = gsub.lookups_for(LATN, None, &features, true, &[]);
+        let mut glyphs = glyph_ids
+            .iter()
+            .enumerate()
+            .map(|(cluster, &glyph_id)| GlyphInfo::new(glyph_id, cluster))
+            .collect();
+        gsub.apply(None, &mut glyphs, &lookups);
+        glyphs
+            .iter()
+            .map(|glyph| (glyph.glyph_id, glyph.cluster))
+            .collect()
+    }
+
+    #[test]
+    fn lookups_for() {
+        let gsub = GsubTable::parse(&SAMPLE_TABLE).unwrap();
+        let liga = ['l', 'i', 'g', 'a'];
+        let calt = ['c', 'a', 'l', 't'];
+
+        assert_eq!(
+            gsub.lookups_for(LATN, None, &[(liga, 1), (calt, 2)], true, &[]),
+            vec![(0, 1), (2, 2)]
+        );
+        assert!(gsub
+            .lookups_for(LATN, None, &[(['d', 'l', 'i', 'g'], 1)], true, &[])
+            .is_empty());
+    }
+
+    #[test]
+    fn ligatures() {
+        let liga = ['l', 'i', 'g', 'a'];
+
+        assert_eq!(
+            substitute(&[10, 10, 11, 12, 10, 11], &[liga]),
+            vec![(21, 0), (12, 3), (20, 4)]
+        );
+        assert_eq!(
+            substitute(&[10, 12, 11], &[liga]),
+            vec![(10, 0), (12, 1), (11, 2)]
+        );
+    }
+
+    #[test]
+    fn ligature_records_mark_components() {
+        let gsub = GsubTable::parse(&MARK_SKIPPING_TABLE).unwrap();
+        let gdef = GdefTable::parse(&MARK_GDEF_TABLE).unwrap();
+        let lookups = gsub.lookups_for(LATN, None, &[(['l', 'i', 'g', 'a'], !0)], true, &[]);
+        let mut glyphs = [1, 10, 2, 10]
+            .iter()
+            .enumerate()
+            .map(|(cluster, &glyph_id)| GlyphInfo::new(glyph_id, cluster))
+            .collect();
+        gsub.apply(Some(&gdef), &mut glyphs, &lookups);
+
+        // The mark between the components followed the first. The one after the ligature isn't
+        // part of it.
+        assert_eq!(
+            glyphs
+                .iter()
+                .map(|glyph| (glyph.glyph_id, glyph.cluster, glyph.ligature_component))
+                .collect::<Vec<_>>(),
+            vec![(30, 0, None), (10, 0, Some(0)), (10, 3, None)]
+        );
+    }
+
+    #[test]
+    fn single_through_extension() {
+        assert_eq!(
+            substitute(&[1, 5, 6], &[['s', 'm', 'c', 'p']]),
+            vec![(101, 0), (105, 1), (6, 2)]
+        );
+    }
+
+    #[test]
+    fn chained_context() {
+        let calt = ['c', 'a', 'l', 't'];
+
+        assert_eq!(
+            substitute(&[3, 4, 3], &[calt]),
+            vec![(6, 0), (7, 0), (4, 1), (3, 2)]
+        );
+        assert_eq!(substitute(&[3, 5], &[calt]), vec![(3, 0), (5, 1)]);
+    }
+
+    #[test]
+    fn deletion_inside_context() {
+        let gsub = GsubTable::parse(&DELETING_CONTEXT_TABLE).unwrap();
+        let lookups = gsub.lookups_for(LATN, None, &[(['c', 'a', 'l', 't'], !0)], true, &[]);
+        let mut glyphs = [1, 2, 3]
+            .iter()
+            .enumerate()
+            .map(|(cluster, &glyph_id)| GlyphInfo::new(glyph_id, cluster))
+            .collect();
+        gsub.apply(None, &mut glyphs, &lookups);
+
+        // The 2 moved back into the deleted glyph's place, and was still substituted there.
+        assert_eq!(
+            glyphs
+                .iter()
+                .map(|glyph| (glyph.glyph_id, glyph.cluster))
+                .collect::<Vec<_>>(),
+            vec![(20, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn unselected_features() {
+        assert_eq!(
+            substitute(&[10, 11, 1], &[]),
+            vec![(10, 0), (11, 1), (1, 2)]
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Reverse chaining contextual single substitution replaces glyphs depending on the glyphs
+//! around them, working from the end of the text to the start so that each substitution can
+//! depend on those after it. It is mostly used for Arabic calligraphic fonts.
+
+use opentype::tables::layout::buffer::{GlyphInfo, Matcher};
+use opentype::tables::layout::context::parse_coverages;
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub struct ReverseChain {
+    coverage: Coverage,
+    /// Nearest first.
+    backtrack: Vec<Coverage>,
+    lookahead: Vec<Coverage>,
+    /// The substitute for each covered glyph, by coverage index.
+    substitutes: Vec<u16>,
+}
+
+impl ReverseChain {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        if reader.read_u16()? != 1 {
+            return Err(ParseError::UnknownFormat);
+        }
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let backtrack_count = reader.read_u16()? as usize;
+        let backtrack = parse_coverages(table_data, offset, &mut reader, backtrack_count)?;
+        let lookahead_count = reader.read_u16()? as usize;
+        let lookahead = parse_coverages(table_data, offset, &mut reader, lookahead_count)?;
+        let glyph_count = reader.read_u16()? as usize;
+        let substitutes = reader.read_array::<u16, U16>(glyph_count)?;
+
+        Ok(Self {
+            coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+            backtrack,
+            lookahead,
+            substitutes,
+        })
+    }
+
+    /// Substitute the glyph at `pos`, if it and the glyphs around it match.
+    pub fn apply(&self, matcher: &Matcher, glyphs: &mut [GlyphInfo], pos: usize) -> Option<usize> {
+        let idx = self.coverage.index(glyphs[pos].glyph_id)?;
+        let substitute = *self.substitutes.get(idx as usize)?;
+        let matched =
+            matcher.match_backtrack(glyphs, pos, self.backtrack.len(), |idx, glyph| {
+                self.backtrack[idx].contains(glyph)
+            }) && matcher.match_lookahead(glyphs, pos, self.lookahead.len(), |idx, glyph| {
+                self.lookahead[idx].contains(glyph)
+            });
+        if !matched {
+            return None;
+        }
+        glyphs[pos].glyph_id = substitute;
+        Some(pos + 1)
+    }
+}
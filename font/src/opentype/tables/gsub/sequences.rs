@@ -0,0 +1,43 @@
+//! Multiple and alternate substitution both give each covered glyph a list of glyphs. Multiple
+//! substitution replaces the glyph with the whole list, such as to decompose a precomposed
+//! character, while alternate substitution picks one of them, such as a swash form.
+
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub struct Sequences {
+    coverage: Coverage,
+    /// The glyphs for each covered glyph, by coverage index.
+    sequences: Vec<Vec<u16>>,
+}
+
+impl Sequences {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        if reader.read_u16()? != 1 {
+            return Err(ParseError::UnknownFormat);
+        }
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let sequence_count = reader.read_u16()? as usize;
+        let sequence_offsets = reader.read_array::<usize, Offset16>(sequence_count)?;
+
+        let mut sequences = Vec::with_capacity(sequence_count);
+        for sequence_offset in sequence_offsets {
+            let mut reader = Reader::at(table_data, offset + sequence_offset);
+            let glyph_count = reader.read_u16()? as usize;
+            sequences.push(reader.read_array::<u16, U16>(glyph_count)?);
+        }
+
+        Ok(Self {
+            coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+            sequences,
+        })
+    }
+
+    pub fn get(&self, glyph_id: u16) -> Option<&[u16]> {
+        self.sequences
+            .get(self.coverage.index(glyph_id)? as usize)
+            .map(Vec::as_slice)
+    }
+}
@@ -0,0 +1,51 @@
+//! Single substitution replaces one glyph with another, such as a letter with its small capital.
+
+use opentype::tables::layout::{Coverage, ParseError};
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub enum Single {
+    /// Format 1: add a delta to the glyph ID, modulo 65536.
+    Delta { coverage: Coverage, delta: i16 },
+    /// Format 2: the substitute for each covered glyph, by coverage index.
+    Glyphs {
+        coverage: Coverage,
+        substitutes: Vec<u16>,
+    },
+}
+
+impl Single {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let format = reader.read_u16()?;
+        let coverage_offset = reader.read::<usize, Offset16>()?;
+        let coverage = Coverage::parse(table_data, offset + coverage_offset)?;
+        match format {
+            1 => Ok(Single::Delta {
+                coverage,
+                delta: reader.read_i16()?,
+            }),
+            2 => {
+                let glyph_count = reader.read_u16()? as usize;
+                Ok(Single::Glyphs {
+                    coverage,
+                    substitutes: reader.read_array::<u16, U16>(glyph_count)?,
+                })
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    pub fn substitute(&self, glyph_id: u16) -> Option<u16> {
+        match self {
+            Single::Delta { coverage, delta } => {
+                coverage.index(glyph_id)?;
+                Some(glyph_id.wrapping_add(*delta as u16))
+            }
+            Single::Glyphs {
+                coverage,
+                substitutes,
+            } => substitutes.get(coverage.index(glyph_id)? as usize).cloned(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use opentype::types::{DataType, Datetime, Fixed, I16, U16, U32};
+use opentype::types::{DataType, Datetime, Fixed, ReadError, I16, U16, U32};
 
 const OFFSET_MAJOR_VERSION: usize = 0;
 const OFFSET_MINOR_VERSION: usize = 2;
@@ -27,7 +27,7 @@ pub struct HeadTable {
     checksum_adjustment: u32,
     magic_number: u32,
     flags: u16,
-    units_per_em: u16,
+    pub units_per_em: u16,
     created: Datetime,
     modified: Datetime,
     x_min: i16,
@@ -43,56 +43,58 @@ pub struct HeadTable {
 }
 
 impl HeadTable {
-    pub fn parse(table_data: &[u8]) -> Self {
-        Self {
-            major_version: U16::extract(table_data, OFFSET_MAJOR_VERSION),
-            minor_version: U16::extract(table_data, OFFSET_MINOR_VERSION),
-            font_revision: Fixed::extract(table_data, OFFSET_FONT_REVISION),
-            checksum_adjustment: U32::extract(table_data, OFFSET_CHECKSUM_ADJ),
-            magic_number: U32::extract(table_data, OFFSET_MAGIC_NUMBER),
-            flags: U16::extract(table_data, OFFSET_FLAGS),
-            units_per_em: U16::extract(table_data, OFFSET_UNITS_PER_EM),
-            created: Datetime::extract(table_data, OFFSET_CREATED),
-            modified: Datetime::extract(table_data, OFFSET_MODIFIED),
-            x_min: I16::extract(table_data, OFFSET_X_MIN),
-            y_min: I16::extract(table_data, OFFSET_Y_MIN),
-            x_max: I16::extract(table_data, OFFSET_X_MAX),
-            y_max: I16::extract(table_data, OFFSET_Y_MAX),
-            lowest_rec_ppem: U16::extract(table_data, OFFSET_LOWEST_PPEM),
-            mac_style: U16::extract(table_data, OFFSET_MAC_STYLE),
-            font_dir_hint: Self::parse_font_dir_hint(table_data),
-            index_to_loc_fmt: Self::parse_index_to_loc_format(table_data),
-            glyph_data_fmt: Self::parse_glyph_data_format(table_data),
-        }
+    pub const INDEX_TO_LOC_FMT_OFFSET: usize = OFFSET_INDEX_TO_LOC_FMT;
+
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        Ok(Self {
+            major_version: U16::extract(table_data, OFFSET_MAJOR_VERSION)?,
+            minor_version: U16::extract(table_data, OFFSET_MINOR_VERSION)?,
+            font_revision: Fixed::extract(table_data, OFFSET_FONT_REVISION)?,
+            checksum_adjustment: U32::extract(table_data, OFFSET_CHECKSUM_ADJ)?,
+            magic_number: U32::extract(table_data, OFFSET_MAGIC_NUMBER)?,
+            flags: U16::extract(table_data, OFFSET_FLAGS)?,
+            units_per_em: U16::extract(table_data, OFFSET_UNITS_PER_EM)?,
+            created: Datetime::extract(table_data, OFFSET_CREATED)?,
+            modified: Datetime::extract(table_data, OFFSET_MODIFIED)?,
+            x_min: I16::extract(table_data, OFFSET_X_MIN)?,
+            y_min: I16::extract(table_data, OFFSET_Y_MIN)?,
+            x_max: I16::extract(table_data, OFFSET_X_MAX)?,
+            y_max: I16::extract(table_data, OFFSET_Y_MAX)?,
+            lowest_rec_ppem: U16::extract(table_data, OFFSET_LOWEST_PPEM)?,
+            mac_style: U16::extract(table_data, OFFSET_MAC_STYLE)?,
+            font_dir_hint: Self::parse_font_dir_hint(table_data)?,
+            index_to_loc_fmt: Self::parse_index_to_loc_format(table_data)?,
+            glyph_data_fmt: Self::parse_glyph_data_format(table_data)?,
+        })
     }
 
-    fn parse_font_dir_hint(table_data: &[u8]) -> FontDirectionHint {
-        let value = I16::extract(table_data, OFFSET_FONT_DIR_HINT);
-        match value {
+    fn parse_font_dir_hint(table_data: &[u8]) -> Result<FontDirectionHint, ReadError> {
+        let value = I16::extract(table_data, OFFSET_FONT_DIR_HINT)?;
+        Ok(match value {
             -2 => FontDirectionHint::RightToLeftWithNeutrals,
             -1 => FontDirectionHint::RightToLeft,
             0 => FontDirectionHint::MixedDirection,
             1 => FontDirectionHint::LeftToRight,
             2 => FontDirectionHint::LeftToRightWithNeutrals,
             _ => FontDirectionHint::Unknown(value),
-        }
+        })
     }
 
-    fn parse_index_to_loc_format(table_data: &[u8]) -> IndexToLocFormat {
-        let value = I16::extract(table_data, OFFSET_INDEX_TO_LOC_FMT);
-        match value {
+    fn parse_index_to_loc_format(table_data: &[u8]) -> Result<IndexToLocFormat, ReadError> {
+        let value = I16::extract(table_data, OFFSET_INDEX_TO_LOC_FMT)?;
+        Ok(match value {
             0 => IndexToLocFormat::ShortOffset,
             1 => IndexToLocFormat::LongOffset,
             _ => IndexToLocFormat::Unknown(value),
-        }
+        })
     }
 
-    fn parse_glyph_data_format(table_data: &[u8]) -> GlyphDataFormat {
-        let value = I16::extract(table_data, OFFSET_GLYPH_DATA_FMT);
-        match value {
+    fn parse_glyph_data_format(table_data: &[u8]) -> Result<GlyphDataFormat, ReadError> {
+        let value = I16::extract(table_data, OFFSET_GLYPH_DATA_FMT)?;
+        Ok(match value {
             0 => GlyphDataFormat::CurrentFormat,
             _ => GlyphDataFormat::Unknown(value),
-        }
+        })
     }
 }
 
@@ -0,0 +1,60 @@
+use opentype::types::{DataType, ReadError, I16, U16};
+
+const OFFSET_ASCENDER: usize = 4;
+const OFFSET_DESCENDER: usize = 6;
+const OFFSET_LINE_GAP: usize = 8;
+const OFFSET_ADVANCE_WIDTH_MAX: usize = 10;
+const OFFSET_NUM_H_METRICS: usize = 34;
+
+/// The horizontal header, holding metrics which apply to the whole font when laid out
+/// horizontally.
+#[derive(Debug)]
+pub struct HheaTable {
+    /// The distance from the baseline up to the top of the tallest glyphs, in font units.
+    pub ascender: i16,
+    /// The distance from the baseline to the bottom of the lowest glyphs. This is usually
+    /// negative, as it is measured upwards.
+    pub descender: i16,
+    /// The extra space to leave between the descender of one line and the ascender of the next.
+    pub line_gap: i16,
+    pub advance_width_max: u16,
+    /// The number of glyphs with their own advance width in the hmtx table.
+    pub num_h_metrics: u16,
+}
+
+impl HheaTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        Ok(Self {
+            ascender: I16::extract(table_data, OFFSET_ASCENDER)?,
+            descender: I16::extract(table_data, OFFSET_DESCENDER)?,
+            line_gap: I16::extract(table_data, OFFSET_LINE_GAP)?,
+            advance_width_max: U16::extract(table_data, OFFSET_ADVANCE_WIDTH_MAX)?,
+            num_h_metrics: U16::extract(table_data, OFFSET_NUM_H_METRICS)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::sample_table;
+
+    #[test]
+    fn parse_sample() {
+        let hhea = HheaTable::parse(sample_table(b"hhea")).unwrap();
+
+        assert_eq!(hhea.ascender, 666);
+        assert_eq!(hhea.descender, 0);
+        assert_eq!(hhea.line_gap, 90);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = &sample_table(b"hhea")[..34];
+
+        assert_eq!(
+            HheaTable::parse(table).unwrap_err(),
+            ReadError { offset: 34 }
+        );
+    }
+}
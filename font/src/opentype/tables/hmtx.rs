@@ -0,0 +1,108 @@
+use opentype::types::{ReadError, Reader, I16};
+
+/// The advance width and left side bearing of every glyph.
+///
+/// Only the first `num_h_metrics` glyphs have their own advance width. The rest, typically a run
+/// of monospaced glyphs at the end of the font, share the last advance and store only their left
+/// side bearing.
+#[derive(Debug)]
+pub struct HmtxTable {
+    h_metrics: Vec<HorizontalMetric>,
+    left_side_bearings: Vec<i16>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HorizontalMetric {
+    pub advance_width: u16,
+    pub left_side_bearing: i16,
+}
+
+impl HmtxTable {
+    pub fn parse(
+        table_data: &[u8],
+        num_h_metrics: u16,
+        num_glyphs: u16,
+    ) -> Result<Self, ReadError> {
+        let mut reader = Reader::new(table_data);
+        let mut h_metrics = Vec::with_capacity(num_h_metrics as usize);
+        for _ in 0..num_h_metrics {
+            h_metrics.push(HorizontalMetric {
+                advance_width: reader.read_u16()?,
+                left_side_bearing: reader.read_i16()?,
+            });
+        }
+        let num_bearings = num_glyphs.saturating_sub(num_h_metrics) as usize;
+        let left_side_bearings = reader.read_array::<i16, I16>(num_bearings)?;
+
+        Ok(Self {
+            h_metrics,
+            left_side_bearings,
+        })
+    }
+
+    /// The metrics for a glyph, or `None` if the glyph is beyond the end of the table.
+    pub fn metric(&self, glyph_id: u16) -> Option<HorizontalMetric> {
+        let idx = glyph_id as usize;
+        if let Some(metric) = self.h_metrics.get(idx) {
+            return Some(*metric);
+        }
+
+        let last = self.h_metrics.last()?;
+        self.left_side_bearings
+            .get(idx - self.h_metrics.len())
+            .map(|&left_side_bearing| HorizontalMetric {
+                advance_width: last.advance_width,
+                left_side_bearing,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_trailing_bearings() {
+        let table = [
+            0x01u8, 0xF4, 0x00, 0x0A, // 500, 10
+            0x02, 0x58, 0xFF, 0xEC, // 600, -20
+            0x00, 0x05, 0xFF, 0xFB, // bearings 5, -5
+        ];
+        let hmtx = HmtxTable::parse(&table, 2, 4).unwrap();
+
+        assert_eq!(
+            hmtx.metric(1),
+            Some(HorizontalMetric {
+                advance_width: 600,
+                left_side_bearing: -20
+            })
+        );
+        assert_eq!(
+            hmtx.metric(3),
+            Some(HorizontalMetric {
+                advance_width: 600,
+                left_side_bearing: -5
+            })
+        );
+        assert_eq!(hmtx.metric(4), None);
+    }
+
+    #[test]
+    fn parse_without_long_metrics() {
+        // Without a single advance width, no glyph can have one.
+        let table = [0x00u8, 0x05];
+        let hmtx = HmtxTable::parse(&table, 0, 1).unwrap();
+
+        assert_eq!(hmtx.metric(0), None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = [0x01u8, 0xF4, 0x00, 0x0A, 0x00];
+
+        assert_eq!(
+            HmtxTable::parse(&table, 1, 2).unwrap_err(),
+            ReadError { offset: 4 }
+        );
+    }
+}
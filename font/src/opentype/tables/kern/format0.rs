@@ -0,0 +1,52 @@
+//! Format 0 lists kerning pairs, sorted so they can be found by binary search.
+
+use super::ParseError;
+use opentype::types::Reader;
+
+#[derive(Debug)]
+pub struct Format0 {
+    /// Each pair's left and right glyphs, combined as `left << 16 | right`, with its value.
+    pairs: Vec<(u32, i16)>,
+}
+
+impl Format0 {
+    pub fn parse(kern_data: &[u8], data_offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(kern_data, data_offset);
+        let num_pairs = reader.read_u16()? as usize;
+        // Skip the search range, entry selector and range shift.
+        reader.skip(6)?;
+
+        let mut pairs = Vec::with_capacity(num_pairs.min(kern_data.len() / 6));
+        for _ in 0..num_pairs {
+            let left = reader.read_u16()?;
+            let right = reader.read_u16()?;
+            let value = reader.read_i16()?;
+            pairs.push((u32::from(left) << 16 | u32::from(right), value));
+        }
+
+        Ok(Self { pairs })
+    }
+
+    pub fn kerning(&self, left: u16, right: u16) -> Option<i16> {
+        let key = u32::from(left) << 16 | u32::from(right);
+        self.pairs
+            .binary_search_by_key(&key, |&(pair, _)| pair)
+            .ok()
+            .map(|idx| self.pairs[idx].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_truncated() {
+        let data = [0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01];
+
+        assert_eq!(
+            Format0::parse(&data, 0).unwrap_err(),
+            ParseError::Truncated(10)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Format 2 assigns the left and right glyphs to classes, and looks up each pair of classes in a
+//! two-dimensional array of values.
+
+use super::ParseError;
+use opentype::types::{Reader, I16, U16};
+
+#[derive(Debug)]
+pub struct Format2 {
+    left_classes: ClassTable,
+    right_classes: ClassTable,
+    /// The values, from the start of the array up to the furthest that any pair of classes reaches.
+    array: Vec<i16>,
+    /// The offset of the array from the start of the subtable, in bytes.
+    array_offset: usize,
+}
+
+/// The class values for a contiguous range of glyphs. These are byte offsets: the left classes
+/// are multiples of the row width, and the right classes multiples of two.
+#[derive(Debug)]
+struct ClassTable {
+    first_glyph: u16,
+    offsets: Vec<u16>,
+}
+
+impl Format2 {
+    pub fn parse(
+        kern_data: &[u8],
+        subtable_offset: usize,
+        data_offset: usize,
+    ) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(kern_data, data_offset);
+        let _row_width = reader.read_u16()?;
+        let left_class_offset = reader.read_u16()? as usize;
+        let right_class_offset = reader.read_u16()? as usize;
+        let array_offset = reader.read_u16()? as usize;
+
+        let left_classes = ClassTable::parse(kern_data, subtable_offset + left_class_offset)?;
+        let right_classes = ClassTable::parse(kern_data, subtable_offset + right_class_offset)?;
+
+        // The array has no length of its own, so keep whatever the largest pair of classes can
+        // reach, up to the end of the data.
+        let max_offset = left_classes.max_offset() + right_classes.max_offset();
+        let len = (max_offset + 2).saturating_sub(array_offset) / 2;
+        let available = kern_data
+            .len()
+            .saturating_sub(subtable_offset + array_offset)
+            / 2;
+        let array = Reader::at(kern_data, subtable_offset + array_offset)
+            .read_array::<i16, I16>(len.min(available))?;
+
+        Ok(Self {
+            left_classes,
+            right_classes,
+            array,
+            array_offset,
+        })
+    }
+
+    pub fn kerning(&self, left: u16, right: u16) -> Option<i16> {
+        let offset = self.left_classes.offset(left)? + self.right_classes.offset(right)?;
+        let idx = offset.checked_sub(self.array_offset)? / 2;
+        self.array.get(idx).cloned()
+    }
+}
+
+impl ClassTable {
+    fn parse(kern_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(kern_data, offset);
+        let first_glyph = reader.read_u16()?;
+        let num_glyphs = reader.read_u16()? as usize;
+        let offsets = reader.read_array::<u16, U16>(num_glyphs)?;
+        Ok(Self {
+            first_glyph,
+            offsets,
+        })
+    }
+
+    fn offset(&self, glyph: u16) -> Option<usize> {
+        let idx = glyph.checked_sub(self.first_glyph)?;
+        self.offsets
+            .get(idx as usize)
+            .map(|&offset| offset as usize)
+    }
+
+    fn max_offset(&self) -> usize {
+        self.offsets.iter().cloned().max().unwrap_or(0) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Glyphs 1 and 2 are left classes 0 and 1, glyphs 3 and 4 right classes 0 and 1, and the
+    /// array holds -10, -20, -30 and -40.
+    const SAMPLE_SUBTABLE: [u8; 38] = [
+        0x00, 0x00, 0x00, 0x26, 0x02, 0x00, // version, length and coverage
+        0x00, 0x04, 0x00, 0x0E, 0x00, 0x16, 0x00, 0x1E, // row width and offsets
+        0x00, 0x01, 0x00, 0x02, 0x00, 0x1E, 0x00, 0x22, // left classes
+        0x00, 0x03, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, // right classes
+        0xFF, 0xF6, 0xFF, 0xEC, 0xFF, 0xE2, 0xFF, 0xD8, // array
+    ];
+
+    #[test]
+    fn kerning() {
+        let format2 = Format2::parse(&SAMPLE_SUBTABLE, 0, 6).unwrap();
+
+        assert_eq!(format2.kerning(1, 3), Some(-10));
+        assert_eq!(format2.kerning(1, 4), Some(-20));
+        assert_eq!(format2.kerning(2, 3), Some(-30));
+        assert_eq!(format2.kerning(2, 4), Some(-40));
+        assert_eq!(format2.kerning(3, 3), None);
+        assert_eq!(format2.kerning(1, 5), None);
+    }
+}
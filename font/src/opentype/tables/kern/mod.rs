@@ -0,0 +1,231 @@
+//! The kern table holds the legacy pair kerning which fonts used before GPOS. Microsoft and Apple
+//! each defined their own header for it, but share the subtable formats.
+
+mod format0;
+mod format2;
+
+use self::format0::Format0;
+use self::format2::Format2;
+use opentype::types::{ReadError, Reader};
+
+#[derive(Debug)]
+pub struct KernTable {
+    subtables: Vec<Subtable>,
+}
+
+#[derive(Debug)]
+struct Subtable {
+    /// The subtable holds ordinary horizontal kerning which can be applied to pairs of glyphs.
+    applies: bool,
+    /// The values replace the kerning accumulated from earlier subtables, rather than adding to
+    /// it.
+    overrides: bool,
+    format: Format,
+}
+
+#[derive(Debug)]
+enum Format {
+    Format0(Format0),
+    Format2(Format2),
+    /// Apple's state machine and class index formats, which need more than a pair of glyphs.
+    Unsupported,
+}
+
+impl KernTable {
+    const MS_COVERAGE_HORIZONTAL: u16 = 0x0001;
+    const MS_COVERAGE_MINIMUM: u16 = 0x0002;
+    const MS_COVERAGE_CROSS_STREAM: u16 = 0x0004;
+    const MS_COVERAGE_OVERRIDE: u16 = 0x0008;
+
+    const APPLE_COVERAGE_VERTICAL: u16 = 0x8000;
+    const APPLE_COVERAGE_CROSS_STREAM: u16 = 0x4000;
+    const APPLE_COVERAGE_VARIATION: u16 = 0x2000;
+
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(table_data);
+        // Microsoft's version is a 16 bit 0, while Apple's is the 32 bit fixed point number 1.0.
+        match reader.read_u16()? {
+            0 => Self::parse_microsoft(table_data, &mut reader),
+            1 if reader.read_u16()? == 0 => Self::parse_apple(table_data, &mut reader),
+            _ => Err(ParseError::UnknownVersion),
+        }
+    }
+
+    fn parse_microsoft(table_data: &[u8], reader: &mut Reader) -> Result<Self, ParseError> {
+        let num_tables = reader.read_u16()?;
+        let mut subtables = vec![];
+        for _ in 0..num_tables {
+            let subtable_offset = reader.offset();
+            let _version = reader.read_u16()?;
+            let length = reader.read_u16()? as usize;
+            let coverage = reader.read_u16()?;
+
+            let format = Self::parse_format(
+                table_data,
+                subtable_offset,
+                reader.offset(),
+                (coverage >> 8) as u8,
+            )?;
+            subtables.push(Subtable {
+                applies: coverage & Self::MS_COVERAGE_HORIZONTAL != 0
+                    && coverage & (Self::MS_COVERAGE_MINIMUM | Self::MS_COVERAGE_CROSS_STREAM) == 0,
+                overrides: coverage & Self::MS_COVERAGE_OVERRIDE != 0,
+                format,
+            });
+            // A large format 0 subtable can't give its real length in 16 bits, but any later
+            // subtables are lost anyway if that happens.
+            reader.seek(subtable_offset + length);
+        }
+
+        Ok(Self { subtables })
+    }
+
+    fn parse_apple(table_data: &[u8], reader: &mut Reader) -> Result<Self, ParseError> {
+        let num_tables = reader.read_u32()?;
+        let mut subtables = vec![];
+        for _ in 0..num_tables {
+            let subtable_offset = reader.offset();
+            let length = reader.read_u32()? as usize;
+            let coverage = reader.read_u16()?;
+            let _tuple_index = reader.read_u16()?;
+
+            let format =
+                Self::parse_format(table_data, subtable_offset, reader.offset(), coverage as u8)?;
+            let excluded = Self::APPLE_COVERAGE_VERTICAL
+                | Self::APPLE_COVERAGE_CROSS_STREAM
+                | Self::APPLE_COVERAGE_VARIATION;
+            subtables.push(Subtable {
+                applies: coverage & excluded == 0,
+                overrides: false,
+                format,
+            });
+            if length == 0 {
+                break;
+            }
+            reader.seek(subtable_offset.saturating_add(length));
+        }
+
+        Ok(Self { subtables })
+    }
+
+    fn parse_format(
+        table_data: &[u8],
+        subtable_offset: usize,
+        data_offset: usize,
+        format: u8,
+    ) -> Result<Format, ParseError> {
+        Ok(match format {
+            0 => Format::Format0(Format0::parse(table_data, data_offset)?),
+            2 => Format::Format2(Format2::parse(table_data, subtable_offset, data_offset)?),
+            _ => Format::Unsupported,
+        })
+    }
+
+    /// The adjustment to the space between two glyphs, in font units. Negative values move the
+    /// glyphs closer together.
+    pub fn kerning(&self, left: u16, right: u16) -> i16 {
+        let mut kerning: i16 = 0;
+        for subtable in self.subtables.iter().filter(|subtable| subtable.applies) {
+            let value = match &subtable.format {
+                Format::Format0(format) => format.kerning(left, right),
+                Format::Format2(format) => format.kerning(left, right),
+                Format::Unsupported => None,
+            };
+            if let Some(value) = value {
+                kerning = if subtable.overrides {
+                    value
+                } else {
+                    kerning.saturating_add(value)
+                };
+            }
+        }
+        kerning
+    }
+}
+
+/// Errors which result from unexpected data.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The table's version is neither Microsoft's 0 nor Apple's 1.0.
+    UnknownVersion,
+    /// The entry at the given offset runs past the end of the table.
+    Truncated(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A format 0 subtable body kerning glyph pairs (1, 2) by -50 and (1, 3) by 20.
+    const FORMAT_0_PAIRS: [u8; 20] = [
+        0x00, 0x02, 0x00, 0x0C, 0x00, 0x01, 0x00, 0x00, // 2 pairs, search hints
+        0x00, 0x01, 0x00, 0x02, 0xFF, 0xCE, // (1, 2): -50
+        0x00, 0x01, 0x00, 0x03, 0x00, 0x14, // (1, 3): 20
+    ];
+
+    fn microsoft_table(coverages: &[u16]) -> Vec<u8> {
+        let mut data = vec![0x00, 0x00, 0x00, coverages.len() as u8];
+        for coverage in coverages {
+            data.extend_from_slice(&[0x00, 0x00, 0x00, 26]);
+            data.extend_from_slice(&[(coverage >> 8) as u8, *coverage as u8]);
+            data.extend_from_slice(&FORMAT_0_PAIRS);
+        }
+        data
+    }
+
+    #[test]
+    fn parse_microsoft() {
+        let kern = KernTable::parse(&microsoft_table(&[0x0001])).unwrap();
+
+        assert_eq!(kern.kerning(1, 2), -50);
+        assert_eq!(kern.kerning(1, 3), 20);
+        assert_eq!(kern.kerning(2, 1), 0);
+    }
+
+    #[test]
+    fn combine_subtables() {
+        // Two subtables add together, unless the second overrides the first.
+        let added = KernTable::parse(&microsoft_table(&[0x0001, 0x0001])).unwrap();
+        let overridden = KernTable::parse(&microsoft_table(&[0x0001, 0x0009])).unwrap();
+
+        assert_eq!(added.kerning(1, 2), -100);
+        assert_eq!(overridden.kerning(1, 2), -50);
+    }
+
+    #[test]
+    fn skip_vertical_and_cross_stream() {
+        let kern = KernTable::parse(&microsoft_table(&[0x0000, 0x0005])).unwrap();
+
+        assert_eq!(kern.kerning(1, 2), 0);
+    }
+
+    #[test]
+    fn parse_apple() {
+        let mut data = vec![
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, // version 1.0, 2 subtables
+            0x00, 0x00, 0x00, 0x1C, 0x00, 0x00, 0x00, 0x00, // horizontal format 0
+        ];
+        data.extend_from_slice(&FORMAT_0_PAIRS);
+        data.extend_from_slice(&[0x00, 0x00, 0x00, 0x1C, 0x80, 0x00, 0x00, 0x00]); // vertical
+        data.extend_from_slice(&FORMAT_0_PAIRS);
+        let kern = KernTable::parse(&data).unwrap();
+
+        assert_eq!(kern.kerning(1, 2), -50);
+    }
+
+    #[test]
+    fn parse_unknown_version() {
+        let data = [0x00u8, 0x02, 0x00, 0x00];
+
+        assert_eq!(
+            KernTable::parse(&data).unwrap_err(),
+            ParseError::UnknownVersion
+        );
+    }
+}
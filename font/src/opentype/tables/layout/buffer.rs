@@ -0,0 +1,207 @@
+//! The glyphs which lookups are applied to, and the rules for which glyphs a lookup skips over.
+
+use super::lookup_list::{
+    Lookup, IGNORE_BASE_GLYPHS, IGNORE_LIGATURES, IGNORE_MARKS, MARK_ATTACHMENT_TYPE,
+};
+use opentype::tables::gdef::{GdefTable, GlyphClass};
+
+/// A glyph being laid out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphInfo {
+    pub glyph_id: u16,
+    /// The index of the first character in the text which the glyph came from. A ligature takes
+    /// the cluster of its first component, and every glyph a multiple substitution produces keeps
+    /// the cluster of the glyph it replaced.
+    pub cluster: usize,
+    /// Selects which features apply to the glyph. A feature given with a mask only applies to
+    /// glyphs whose mask shares a bit with it.
+    pub mask: u32,
+    /// For a mark which a ligature formed around, the index of the component it followed, so
+    /// that it can be positioned over that component. `None` if it isn't known.
+    pub ligature_component: Option<u16>,
+}
+
+impl GlyphInfo {
+    /// A glyph which every feature applies to.
+    pub fn new(glyph_id: u16, cluster: usize) -> Self {
+        Self {
+            glyph_id,
+            cluster,
+            mask: !0,
+            ligature_component: None,
+        }
+    }
+}
+
+/// Where to draw a glyph, in font units. The pen moves by the advance after each glyph, and the
+/// glyph is drawn away from the pen by the offset.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GlyphPosition {
+    pub x_advance: i32,
+    pub y_advance: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+}
+
+/// The direction text runs in. Glyphs are always kept in the order of the text, so in right to
+/// left text the pen moves left by each glyph's advance before it is drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    /// Scripts which are written from right to left.
+    const RIGHT_TO_LEFT_SCRIPTS: [[char; 4]; 12] = [
+        ['a', 'd', 'l', 'm'],
+        ['a', 'r', 'a', 'b'],
+        ['a', 'r', 'm', 'i'],
+        ['h', 'e', 'b', 'r'],
+        ['m', 'a', 'n', 'd'],
+        ['m', 'e', 'n', 'd'],
+        ['n', 'k', 'o', ' '],
+        ['p', 'h', 'n', 'x'],
+        ['r', 'o', 'h', 'g'],
+        ['s', 'a', 'm', 'r'],
+        ['s', 'y', 'r', 'c'],
+        ['t', 'h', 'a', 'a'],
+    ];
+
+    /// The direction of a script, given its OpenType tag.
+    pub fn from_script(script: [char; 4]) -> Self {
+        if Self::RIGHT_TO_LEFT_SCRIPTS.contains(&script) {
+            Direction::RightToLeft
+        } else {
+            Direction::LeftToRight
+        }
+    }
+}
+
+/// Decides which glyphs a lookup considers, and finds the sequences of glyphs that its subtables
+/// match.
+pub struct Matcher<'a> {
+    gdef: Option<&'a GdefTable>,
+    flag: u16,
+    mark_filtering_set: Option<u16>,
+}
+
+impl<'a> Matcher<'a> {
+    pub fn new<T>(gdef: Option<&'a GdefTable>, lookup: &Lookup<T>) -> Self {
+        Self {
+            gdef,
+            flag: lookup.flag,
+            mark_filtering_set: lookup.mark_filtering_set,
+        }
+    }
+
+    /// Whether the lookup's flags say to pass over the glyph, as though it weren't there.
+    pub fn skips(&self, glyph_id: u16) -> bool {
+        let gdef = match self.gdef {
+            Some(gdef) => gdef,
+            None => return false,
+        };
+        match gdef.glyph_class(glyph_id) {
+            Some(GlyphClass::Base) => self.flag & IGNORE_BASE_GLYPHS != 0,
+            Some(GlyphClass::Ligature) => self.flag & IGNORE_LIGATURES != 0,
+            Some(GlyphClass::Mark) => {
+                if self.flag & IGNORE_MARKS != 0 {
+                    return true;
+                }
+                if let Some(set) = self.mark_filtering_set {
+                    return !gdef.is_in_mark_glyph_set(set, glyph_id);
+                }
+                let mark_attachment_type = (self.flag & MARK_ATTACHMENT_TYPE) >> 8;
+                mark_attachment_type != 0
+                    && gdef.mark_attachment_class(glyph_id) != mark_attachment_type
+            }
+            Some(GlyphClass::Component) | None => false,
+        }
+    }
+
+    /// The position of the first glyph after `pos` which isn't skipped.
+    pub fn next(&self, glyphs: &[GlyphInfo], pos: usize) -> Option<usize> {
+        (pos + 1..glyphs.len()).find(|&idx| !self.skips(glyphs[idx].glyph_id))
+    }
+
+    /// The position of the last glyph before `pos` which isn't skipped.
+    pub fn prev(&self, glyphs: &[GlyphInfo], pos: usize) -> Option<usize> {
+        (0..pos)
+            .rev()
+            .find(|&idx| !self.skips(glyphs[idx].glyph_id))
+    }
+
+    /// Match the `len` glyphs of an input sequence which starts at `pos`, calling `matches` with
+    /// the index in the sequence and the glyph for each glyph after the first. Returns the
+    /// position of every glyph in the sequence.
+    pub fn match_input<F>(
+        &self,
+        glyphs: &[GlyphInfo],
+        pos: usize,
+        len: usize,
+        mut matches: F,
+    ) -> Option<Vec<usize>>
+    where
+        F: FnMut(usize, u16) -> bool,
+    {
+        let mut positions = Vec::with_capacity(len);
+        positions.push(pos);
+        let mut last = pos;
+        for idx in 1..len {
+            last = self.next(glyphs, last)?;
+            if !matches(idx, glyphs[last].glyph_id) {
+                return None;
+            }
+            positions.push(last);
+        }
+        Some(positions)
+    }
+
+    /// Match the `len` glyphs before `pos`, nearest first.
+    pub fn match_backtrack<F>(
+        &self,
+        glyphs: &[GlyphInfo],
+        pos: usize,
+        len: usize,
+        mut matches: F,
+    ) -> bool
+    where
+        F: FnMut(usize, u16) -> bool,
+    {
+        let mut last = pos;
+        for idx in 0..len {
+            last = match self.prev(glyphs, last) {
+                Some(prev) => prev,
+                None => return false,
+            };
+            if !matches(idx, glyphs[last].glyph_id) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Match the `len` glyphs after `pos`, nearest first.
+    pub fn match_lookahead<F>(
+        &self,
+        glyphs: &[GlyphInfo],
+        pos: usize,
+        len: usize,
+        mut matches: F,
+    ) -> bool
+    where
+        F: FnMut(usize, u16) -> bool,
+    {
+        let mut last = pos;
+        for idx in 0..len {
+            last = match self.next(glyphs, last) {
+                Some(next) => next,
+                None => return false,
+            };
+            if !matches(idx, glyphs[last].glyph_id) {
+                return false;
+            }
+        }
+        true
+    }
+}
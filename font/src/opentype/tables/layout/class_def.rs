@@ -0,0 +1,106 @@
+//! A class definition table sorts glyphs into numbered classes. Every glyph it doesn't list is in
+//! class 0.
+
+use super::ParseError;
+use opentype::types::{Reader, U16};
+use std::cmp::Ordering;
+
+#[derive(Debug)]
+pub enum ClassDef {
+    /// Format 1: the classes of a contiguous run of glyphs.
+    Array { start_glyph: u16, classes: Vec<u16> },
+    /// Format 2: ranges of consecutive glyphs which share a class, in order.
+    Ranges(Vec<ClassRange>),
+}
+
+#[derive(Debug)]
+pub struct ClassRange {
+    start_glyph: u16,
+    end_glyph: u16,
+    class: u16,
+}
+
+impl ClassDef {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        match reader.read_u16()? {
+            1 => {
+                let start_glyph = reader.read_u16()?;
+                let glyph_count = reader.read_u16()? as usize;
+                let classes = reader.read_array::<u16, U16>(glyph_count)?;
+                Ok(ClassDef::Array {
+                    start_glyph,
+                    classes,
+                })
+            }
+            2 => {
+                let range_count = reader.read_u16()?;
+                let mut ranges = Vec::with_capacity(range_count as usize);
+                for _ in 0..range_count {
+                    ranges.push(ClassRange {
+                        start_glyph: reader.read_u16()?,
+                        end_glyph: reader.read_u16()?,
+                        class: reader.read_u16()?,
+                    });
+                }
+                Ok(ClassDef::Ranges(ranges))
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    pub fn class(&self, glyph_id: u16) -> u16 {
+        match self {
+            ClassDef::Array {
+                start_glyph,
+                classes,
+            } => glyph_id
+                .checked_sub(*start_glyph)
+                .and_then(|idx| classes.get(idx as usize))
+                .cloned()
+                .unwrap_or(0),
+            ClassDef::Ranges(ranges) => ranges
+                .binary_search_by(|range| {
+                    if range.end_glyph < glyph_id {
+                        Ordering::Less
+                    } else if range.start_glyph > glyph_id {
+                        Ordering::Greater
+                    } else {
+                        Ordering::Equal
+                    }
+                })
+                .map(|idx| ranges[idx].class)
+                .unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_format_1() {
+        let data = [0x00, 0x01, 0x00, 0x04, 0x00, 0x02, 0x00, 0x03, 0x00, 0x01];
+        let class_def = ClassDef::parse(&data, 0).unwrap();
+
+        assert_eq!(class_def.class(3), 0);
+        assert_eq!(class_def.class(4), 3);
+        assert_eq!(class_def.class(5), 1);
+        assert_eq!(class_def.class(6), 0);
+    }
+
+    #[test]
+    fn parse_format_2() {
+        let data = [
+            0x00, 0x02, 0x00, 0x02, // format 2, 2 ranges
+            0x00, 0x0A, 0x00, 0x0C, 0x00, 0x01, // 10-12 in class 1
+            0x00, 0x14, 0x00, 0x15, 0x00, 0x03, // 20-21 in class 3
+        ];
+        let class_def = ClassDef::parse(&data, 0).unwrap();
+
+        assert_eq!(class_def.class(11), 1);
+        assert_eq!(class_def.class(21), 3);
+        assert_eq!(class_def.class(15), 0);
+    }
+}
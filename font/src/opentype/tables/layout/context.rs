@@ -0,0 +1,339 @@
+//! Contextual subtables apply other lookups to a sequence of glyphs, but only where it matches a
+//! pattern, optionally with glyphs before (the backtrack) and after (the lookahead) it. GSUB and
+//! GPOS share the formats, as well as their chained versions which add the backtrack and
+//! lookahead.
+
+use super::buffer::{GlyphInfo, Matcher};
+use super::{ClassDef, Coverage, ParseError};
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub enum Context {
+    /// Format 1: rules list glyphs, and are chosen by the first glyph's coverage index.
+    Glyphs {
+        coverage: Coverage,
+        rule_sets: Vec<Vec<Rule>>,
+    },
+    /// Format 2: rules list classes, and are chosen by the first glyph's input class.
+    Classes {
+        coverage: Coverage,
+        backtrack_classes: Option<ClassDef>,
+        input_classes: ClassDef,
+        lookahead_classes: Option<ClassDef>,
+        rule_sets: Vec<Vec<Rule>>,
+    },
+    /// Format 3: a single rule with a coverage table for each position.
+    Coverages {
+        backtrack: Vec<Coverage>,
+        input: Vec<Coverage>,
+        lookahead: Vec<Coverage>,
+        lookup_records: Vec<SequenceLookupRecord>,
+    },
+}
+
+/// A pattern of glyphs or classes. The backtrack is stored nearest first, and the input leaves
+/// out the first glyph, which the coverage table has already matched.
+#[derive(Debug)]
+pub struct Rule {
+    backtrack: Vec<u16>,
+    input: Vec<u16>,
+    lookahead: Vec<u16>,
+    lookup_records: Vec<SequenceLookupRecord>,
+}
+
+/// A lookup to apply at a position in the matched input sequence.
+#[derive(Debug, PartialEq)]
+pub struct SequenceLookupRecord {
+    pub sequence_index: u16,
+    pub lookup_index: u16,
+}
+
+impl Context {
+    /// Parse a sequence context subtable, which has no backtrack or lookahead.
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        match reader.read_u16()? {
+            1 => {
+                let coverage_offset = reader.read::<usize, Offset16>()?;
+                let rule_sets = Self::parse_rule_sets(table_data, offset, &mut reader, false)?;
+                Ok(Context::Glyphs {
+                    coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+                    rule_sets,
+                })
+            }
+            2 => {
+                let coverage_offset = reader.read::<usize, Offset16>()?;
+                let class_def_offset = reader.read::<usize, Offset16>()?;
+                let rule_sets = Self::parse_rule_sets(table_data, offset, &mut reader, false)?;
+                Ok(Context::Classes {
+                    coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+                    backtrack_classes: None,
+                    input_classes: ClassDef::parse(table_data, offset + class_def_offset)?,
+                    lookahead_classes: None,
+                    rule_sets,
+                })
+            }
+            3 => {
+                let glyph_count = reader.read_u16()? as usize;
+                let seq_lookup_count = reader.read_u16()? as usize;
+                let input = parse_coverages(table_data, offset, &mut reader, glyph_count)?;
+                let lookup_records = parse_lookup_records(&mut reader, seq_lookup_count)?;
+                Ok(Context::Coverages {
+                    backtrack: vec![],
+                    input,
+                    lookahead: vec![],
+                    lookup_records,
+                })
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    /// Parse a chained sequence context subtable.
+    pub fn parse_chained(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        match reader.read_u16()? {
+            1 => {
+                let coverage_offset = reader.read::<usize, Offset16>()?;
+                let rule_sets = Self::parse_rule_sets(table_data, offset, &mut reader, true)?;
+                Ok(Context::Glyphs {
+                    coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+                    rule_sets,
+                })
+            }
+            2 => {
+                let coverage_offset = reader.read::<usize, Offset16>()?;
+                let class_def = |class_def_offset| match class_def_offset {
+                    0 => Ok(None),
+                    class_def_offset => {
+                        ClassDef::parse(table_data, offset + class_def_offset).map(Some)
+                    }
+                };
+                let backtrack_classes = class_def(reader.read::<usize, Offset16>()?)?;
+                let input_class_def_offset = reader.read::<usize, Offset16>()?;
+                let lookahead_classes = class_def(reader.read::<usize, Offset16>()?)?;
+                let rule_sets = Self::parse_rule_sets(table_data, offset, &mut reader, true)?;
+                Ok(Context::Classes {
+                    coverage: Coverage::parse(table_data, offset + coverage_offset)?,
+                    backtrack_classes,
+                    input_classes: ClassDef::parse(table_data, offset + input_class_def_offset)?,
+                    lookahead_classes,
+                    rule_sets,
+                })
+            }
+            3 => {
+                let backtrack_count = reader.read_u16()? as usize;
+                let backtrack = parse_coverages(table_data, offset, &mut reader, backtrack_count)?;
+                let input_count = reader.read_u16()? as usize;
+                let input = parse_coverages(table_data, offset, &mut reader, input_count)?;
+                let lookahead_count = reader.read_u16()? as usize;
+                let lookahead = parse_coverages(table_data, offset, &mut reader, lookahead_count)?;
+                let seq_lookup_count = reader.read_u16()? as usize;
+                let lookup_records = parse_lookup_records(&mut reader, seq_lookup_count)?;
+                Ok(Context::Coverages {
+                    backtrack,
+                    input,
+                    lookahead,
+                    lookup_records,
+                })
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    /// Parse the count and offsets of a format 1 or 2 subtable's rule sets, and the rule sets
+    /// themselves. A null offset is an empty rule set.
+    fn parse_rule_sets(
+        table_data: &[u8],
+        offset: usize,
+        reader: &mut Reader,
+        chained: bool,
+    ) -> Result<Vec<Vec<Rule>>, ParseError> {
+        let rule_set_count = reader.read_u16()? as usize;
+        let rule_set_offsets = reader.read_array::<usize, Offset16>(rule_set_count)?;
+        let mut rule_sets = Vec::with_capacity(rule_set_count);
+        for rule_set_offset in rule_set_offsets {
+            if rule_set_offset == 0 {
+                rule_sets.push(vec![]);
+                continue;
+            }
+            let rule_set_offset = offset + rule_set_offset;
+            let mut reader = Reader::at(table_data, rule_set_offset);
+            let rule_count = reader.read_u16()? as usize;
+            let rule_offsets = reader.read_array::<usize, Offset16>(rule_count)?;
+            let mut rules = Vec::with_capacity(rule_count);
+            for rule_offset in rule_offsets {
+                let reader = Reader::at(table_data, rule_set_offset + rule_offset);
+                rules.push(Rule::parse(reader, chained)?);
+            }
+            rule_sets.push(rules);
+        }
+        Ok(rule_sets)
+    }
+
+    /// Match the subtable against the glyphs at `pos`. Returns the positions of the input
+    /// sequence, and the lookups to apply to it.
+    pub fn match_at(
+        &self,
+        matcher: &Matcher,
+        glyphs: &[GlyphInfo],
+        pos: usize,
+    ) -> Option<(Vec<usize>, &[SequenceLookupRecord])> {
+        let glyph_id = glyphs[pos].glyph_id;
+        match self {
+            Context::Glyphs {
+                coverage,
+                rule_sets,
+            } => {
+                let rule_set = rule_sets.get(coverage.index(glyph_id)? as usize)?;
+                let same = |value: &[u16], idx: usize, glyph: u16| value[idx] == glyph;
+                rule_set
+                    .iter()
+                    .find_map(|rule| rule.match_at(matcher, glyphs, pos, &same, &same, &same))
+            }
+            Context::Classes {
+                coverage,
+                backtrack_classes,
+                input_classes,
+                lookahead_classes,
+                rule_sets,
+            } => {
+                coverage.index(glyph_id)?;
+                let rule_set = rule_sets.get(input_classes.class(glyph_id) as usize)?;
+                let class_of = |class_def: &Option<ClassDef>, glyph| match class_def {
+                    Some(class_def) => class_def.class(glyph),
+                    None => 0,
+                };
+                let backtrack = |value: &[u16], idx: usize, glyph| {
+                    value[idx] == class_of(backtrack_classes, glyph)
+                };
+                let input =
+                    |value: &[u16], idx: usize, glyph| value[idx] == input_classes.class(glyph);
+                let lookahead = |value: &[u16], idx: usize, glyph| {
+                    value[idx] == class_of(lookahead_classes, glyph)
+                };
+                rule_set.iter().find_map(|rule| {
+                    rule.match_at(matcher, glyphs, pos, &backtrack, &input, &lookahead)
+                })
+            }
+            Context::Coverages {
+                backtrack,
+                input,
+                lookahead,
+                lookup_records,
+            } => {
+                input.first()?.index(glyph_id)?;
+                let positions = matcher.match_input(glyphs, pos, input.len(), |idx, glyph| {
+                    input[idx].contains(glyph)
+                })?;
+                let last = *positions.last()?;
+                if matcher.match_backtrack(glyphs, pos, backtrack.len(), |idx, glyph| {
+                    backtrack[idx].contains(glyph)
+                }) && matcher.match_lookahead(glyphs, last, lookahead.len(), |idx, glyph| {
+                    lookahead[idx].contains(glyph)
+                }) {
+                    Some((positions, lookup_records.as_slice()))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl Rule {
+    fn parse(mut reader: Reader, chained: bool) -> Result<Self, ParseError> {
+        let backtrack = if chained {
+            let backtrack_count = reader.read_u16()? as usize;
+            reader.read_array::<u16, U16>(backtrack_count)?
+        } else {
+            vec![]
+        };
+        let input_count = reader.read_u16()? as usize;
+        let seq_lookup_count = if chained {
+            None
+        } else {
+            Some(reader.read_u16()? as usize)
+        };
+        let input = reader.read_array::<u16, U16>(input_count.saturating_sub(1))?;
+        let lookahead = if chained {
+            let lookahead_count = reader.read_u16()? as usize;
+            reader.read_array::<u16, U16>(lookahead_count)?
+        } else {
+            vec![]
+        };
+        let seq_lookup_count = match seq_lookup_count {
+            Some(count) => count,
+            None => reader.read_u16()? as usize,
+        };
+        let lookup_records = parse_lookup_records(&mut reader, seq_lookup_count)?;
+
+        Ok(Self {
+            backtrack,
+            input,
+            lookahead,
+            lookup_records,
+        })
+    }
+
+    /// Match the rule at `pos`, comparing each of its values with a glyph using the function for
+    /// that part of the rule.
+    fn match_at<B, I, L>(
+        &self,
+        matcher: &Matcher,
+        glyphs: &[GlyphInfo],
+        pos: usize,
+        backtrack: &B,
+        input: &I,
+        lookahead: &L,
+    ) -> Option<(Vec<usize>, &[SequenceLookupRecord])>
+    where
+        B: Fn(&[u16], usize, u16) -> bool,
+        I: Fn(&[u16], usize, u16) -> bool,
+        L: Fn(&[u16], usize, u16) -> bool,
+    {
+        let positions = matcher.match_input(glyphs, pos, self.input.len() + 1, |idx, glyph| {
+            input(&self.input, idx - 1, glyph)
+        })?;
+        let last = *positions.last()?;
+        let matched =
+            matcher.match_backtrack(glyphs, pos, self.backtrack.len(), |idx, glyph| {
+                backtrack(&self.backtrack, idx, glyph)
+            }) && matcher.match_lookahead(glyphs, last, self.lookahead.len(), |idx, glyph| {
+                lookahead(&self.lookahead, idx, glyph)
+            });
+        if matched {
+            Some((positions, self.lookup_records.as_slice()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Read `count` offsets to coverage tables, and parse the tables.
+pub fn parse_coverages(
+    table_data: &[u8],
+    offset: usize,
+    reader: &mut Reader,
+    count: usize,
+) -> Result<Vec<Coverage>, ParseError> {
+    reader
+        .read_array::<usize, Offset16>(count)?
+        .into_iter()
+        .map(|coverage_offset| Coverage::parse(table_data, offset + coverage_offset))
+        .collect()
+}
+
+fn parse_lookup_records(
+    reader: &mut Reader,
+    count: usize,
+) -> Result<Vec<SequenceLookupRecord>, ParseError> {
+    let mut lookup_records = Vec::with_capacity(count);
+    for _ in 0..count {
+        lookup_records.push(SequenceLookupRecord {
+            sequence_index: reader.read_u16()?,
+            lookup_index: reader.read_u16()?,
+        });
+    }
+    Ok(lookup_records)
+}
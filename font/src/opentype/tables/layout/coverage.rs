@@ -0,0 +1,118 @@
+//! A coverage table lists the glyphs a subtable applies to, and gives each one an index into the
+//! subtable's own arrays.
+
+use super::ParseError;
+use opentype::types::{Reader, U16};
+use std::cmp::Ordering;
+
+#[derive(Debug)]
+pub enum Coverage {
+    /// Format 1: the glyphs in order, each covered by its position in the list.
+    Glyphs(Vec<u16>),
+    /// Format 2: ranges of consecutive glyphs, in order.
+    Ranges(Vec<RangeRecord>),
+}
+
+#[derive(Debug)]
+pub struct RangeRecord {
+    start_glyph: u16,
+    end_glyph: u16,
+    /// The coverage index of `start_glyph`. The rest of the range follows on from it.
+    start_coverage_index: u16,
+}
+
+impl Coverage {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        match reader.read_u16()? {
+            1 => {
+                let glyph_count = reader.read_u16()? as usize;
+                Ok(Coverage::Glyphs(
+                    reader.read_array::<u16, U16>(glyph_count)?,
+                ))
+            }
+            2 => {
+                let range_count = reader.read_u16()?;
+                let mut ranges = Vec::with_capacity(range_count as usize);
+                for _ in 0..range_count {
+                    ranges.push(RangeRecord {
+                        start_glyph: reader.read_u16()?,
+                        end_glyph: reader.read_u16()?,
+                        start_coverage_index: reader.read_u16()?,
+                    });
+                }
+                Ok(Coverage::Ranges(ranges))
+            }
+            _ => Err(ParseError::UnknownFormat),
+        }
+    }
+
+    /// The glyph's coverage index, or `None` if the glyph isn't covered.
+    pub fn index(&self, glyph_id: u16) -> Option<u16> {
+        match self {
+            Coverage::Glyphs(glyphs) => glyphs.binary_search(&glyph_id).ok().map(|idx| idx as u16),
+            Coverage::Ranges(ranges) => {
+                let idx = ranges
+                    .binary_search_by(|range| {
+                        if range.end_glyph < glyph_id {
+                            Ordering::Less
+                        } else if range.start_glyph > glyph_id {
+                            Ordering::Greater
+                        } else {
+                            Ordering::Equal
+                        }
+                    })
+                    .ok()?;
+                let range = &ranges[idx];
+                range
+                    .start_coverage_index
+                    .checked_add(glyph_id - range.start_glyph)
+            }
+        }
+    }
+
+    pub fn contains(&self, glyph_id: u16) -> bool {
+        self.index(glyph_id).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_format_1() {
+        let data = [0x00, 0x01, 0x00, 0x03, 0x00, 0x05, 0x00, 0x09, 0x00, 0x20];
+        let coverage = Coverage::parse(&data, 0).unwrap();
+
+        assert_eq!(coverage.index(5), Some(0));
+        assert_eq!(coverage.index(32), Some(2));
+        assert_eq!(coverage.index(6), None);
+    }
+
+    #[test]
+    fn parse_format_2() {
+        let data = [
+            0x00, 0x02, 0x00, 0x02, // format 2, 2 ranges
+            0x00, 0x0A, 0x00, 0x0C, 0x00, 0x00, // 10-12 from 0
+            0x00, 0x14, 0x00, 0x14, 0x00, 0x03, // 20 at 3
+        ];
+        let coverage = Coverage::parse(&data, 0).unwrap();
+
+        assert_eq!(coverage.index(10), Some(0));
+        assert_eq!(coverage.index(12), Some(2));
+        assert_eq!(coverage.index(20), Some(3));
+        assert!(!coverage.contains(13));
+        assert!(!coverage.contains(9));
+    }
+
+    #[test]
+    fn parse_unknown_format() {
+        let data = [0x00, 0x03, 0x00, 0x00];
+
+        assert_eq!(
+            Coverage::parse(&data, 0).unwrap_err(),
+            ParseError::UnknownFormat
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! The feature list names each typographic feature a layout table offers, such as ligatures or
+//! small caps, and gives the lookups which implement it.
+
+use super::ParseError;
+use opentype::types::{Offset16, Reader, U16};
+
+#[derive(Debug)]
+pub struct FeatureList {
+    features: Vec<([char; 4], Feature)>,
+}
+
+#[derive(Debug)]
+pub struct Feature {
+    /// Indices into the lookup list.
+    pub lookup_indices: Vec<u16>,
+}
+
+impl FeatureList {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let feature_count = reader.read_u16()?;
+        let mut features = Vec::with_capacity(feature_count as usize);
+        for _ in 0..feature_count {
+            let tag = reader.read_tag()?;
+            let feature_offset = offset + reader.read::<usize, Offset16>()?;
+            features.push((tag, Feature::parse(table_data, feature_offset)?));
+        }
+
+        Ok(Self { features })
+    }
+
+    /// The tag and feature at an index from a language's list of features.
+    pub fn get(&self, idx: u16) -> Option<(&[char; 4], &Feature)> {
+        self.features
+            .get(idx as usize)
+            .map(|(tag, feature)| (tag, feature))
+    }
+}
+
+impl Feature {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        // Skip the offset to the feature parameters, which only a few features have.
+        let mut reader = Reader::at(table_data, offset + 2);
+        let lookup_index_count = reader.read_u16()? as usize;
+        let lookup_indices = reader.read_array::<u16, U16>(lookup_index_count)?;
+
+        Ok(Self { lookup_indices })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let data = [
+            0x00, 0x02, // 2 features
+            b'l', b'i', b'g', b'a', 0x00, 0x0E, // liga at 14
+            b's', b'm', b'c', b'p', 0x00, 0x0E, // smcp shares it
+            0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x04, // lookups 1 and 4
+        ];
+        let list = FeatureList::parse(&data, 0).unwrap();
+
+        let (tag, feature) = list.get(1).unwrap();
+        assert_eq!(tag, &['s', 'm', 'c', 'p']);
+        assert_eq!(feature.lookup_indices, vec![1, 4]);
+        assert!(list.get(2).is_none());
+    }
+}
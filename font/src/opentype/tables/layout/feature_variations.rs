@@ -0,0 +1,154 @@
+//! Feature variations swap in different lookups for some features in particular regions of a
+//! variable font's design space, such as a heavier weight.
+
+use super::feature_list::Feature;
+use super::ParseError;
+use opentype::types::{F2Dot14, Offset32, Reader};
+
+#[derive(Debug)]
+pub struct FeatureVariations {
+    /// Tried in order. The first whose conditions all hold is used.
+    records: Vec<FeatureVariationRecord>,
+}
+
+#[derive(Debug)]
+struct FeatureVariationRecord {
+    conditions: Vec<Condition>,
+    /// Features which replace those at the given index in the feature list.
+    substitutions: Vec<(u16, Feature)>,
+}
+
+#[derive(Debug)]
+enum Condition {
+    /// The normalized coordinate on an axis must lie within a range.
+    AxisRange { axis_index: u16, min: f32, max: f32 },
+    /// A condition this version doesn't understand, which is never met.
+    Unknown,
+}
+
+impl FeatureVariations {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let major_version = reader.read_u16()?;
+        let _minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(ParseError::UnknownVersion);
+        }
+
+        let record_count = reader.read_u32()?;
+        let mut records = vec![];
+        for _ in 0..record_count {
+            let condition_set_offset = reader.read::<usize, Offset32>()?;
+            let substitution_offset = reader.read::<usize, Offset32>()?;
+            let conditions = match condition_set_offset {
+                0 => vec![],
+                set_offset => Self::parse_condition_set(table_data, offset + set_offset)?,
+            };
+            let substitutions = match substitution_offset {
+                0 => vec![],
+                substitution_offset => {
+                    Self::parse_substitutions(table_data, offset + substitution_offset)?
+                }
+            };
+            records.push(FeatureVariationRecord {
+                conditions,
+                substitutions,
+            });
+        }
+
+        Ok(Self { records })
+    }
+
+    fn parse_condition_set(table_data: &[u8], offset: usize) -> Result<Vec<Condition>, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let condition_count = reader.read_u16()? as usize;
+        let condition_offsets = reader.read_array::<usize, Offset32>(condition_count)?;
+        let mut conditions = Vec::with_capacity(condition_count);
+        for condition_offset in condition_offsets {
+            let mut reader = Reader::at(table_data, offset + condition_offset);
+            conditions.push(match reader.read_u16()? {
+                1 => Condition::AxisRange {
+                    axis_index: reader.read_u16()?,
+                    min: reader.read::<f32, F2Dot14>()?,
+                    max: reader.read::<f32, F2Dot14>()?,
+                },
+                _ => Condition::Unknown,
+            });
+        }
+        Ok(conditions)
+    }
+
+    fn parse_substitutions(
+        table_data: &[u8],
+        offset: usize,
+    ) -> Result<Vec<(u16, Feature)>, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let major_version = reader.read_u16()?;
+        let _minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(ParseError::UnknownVersion);
+        }
+
+        let substitution_count = reader.read_u16()?;
+        let mut substitutions = Vec::with_capacity(substitution_count as usize);
+        for _ in 0..substitution_count {
+            let feature_index = reader.read_u16()?;
+            let feature_offset = offset + reader.read::<usize, Offset32>()?;
+            substitutions.push((feature_index, Feature::parse(table_data, feature_offset)?));
+        }
+        Ok(substitutions)
+    }
+
+    /// The feature to use in place of the one at `feature_index`, at the given normalized
+    /// coordinates. Axes without a coordinate are at their default, 0.
+    pub fn substitute(&self, feature_index: u16, coords: &[f32]) -> Option<&Feature> {
+        let record = self.records.iter().find(|record| {
+            record.conditions.iter().all(|condition| match *condition {
+                Condition::AxisRange {
+                    axis_index,
+                    min,
+                    max,
+                } => {
+                    let coord = coords.get(axis_index as usize).cloned().unwrap_or(0.0);
+                    min <= coord && coord <= max
+                }
+                Condition::Unknown => false,
+            })
+        })?;
+        record
+            .substitutions
+            .iter()
+            .find(|(idx, _)| *idx == feature_index)
+            .map(|(_, feature)| feature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces feature 0 with one using lookup 3 when axis 0 is between 0.5 and 1.
+    const SAMPLE_VARIATIONS: [u8; 50] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, // version 1.0, one record
+        0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x20, // condition set and substitutions
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x06, // 16: one condition
+        0x00, 0x01, 0x00, 0x00, 0x20, 0x00, 0x40, 0x00, // 22: axis 0 from 0.5 to 1
+        0x00, 0x00, // padding
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, // 32: one substitution
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, // feature 0
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x03, // 44: lookup 3
+    ];
+
+    #[test]
+    fn substitute() {
+        let variations = FeatureVariations::parse(&SAMPLE_VARIATIONS, 0).unwrap();
+
+        assert!(variations.substitute(0, &[]).is_none());
+        assert!(variations.substitute(0, &[0.25]).is_none());
+        assert_eq!(
+            variations.substitute(0, &[0.75]).unwrap().lookup_indices,
+            vec![3]
+        );
+        assert!(variations.substitute(1, &[0.75]).is_none());
+    }
+}
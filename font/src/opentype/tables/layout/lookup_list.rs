@@ -0,0 +1,92 @@
+//! The lookup list holds every lookup of a layout table. Each lookup is a list of subtables of the
+//! same type, which GSUB and GPOS each define for themselves.
+
+use super::ParseError;
+use opentype::types::{Offset16, Reader};
+
+/// Positions cursive attachments from right to left. Only used by GPOS.
+pub const RIGHT_TO_LEFT: u16 = 0x0001;
+pub const IGNORE_BASE_GLYPHS: u16 = 0x0002;
+pub const IGNORE_LIGATURES: u16 = 0x0004;
+pub const IGNORE_MARKS: u16 = 0x0008;
+/// Skip marks which aren't in the lookup's mark glyph set.
+pub const USE_MARK_FILTERING_SET: u16 = 0x0010;
+/// Skip marks whose mark attachment class isn't in the high byte of the flag, if it is nonzero.
+pub const MARK_ATTACHMENT_TYPE: u16 = 0xFF00;
+
+/// A subtable of a GSUB or GPOS lookup.
+pub trait LookupSubtable: Sized {
+    /// Parse the subtable at `offset` in the table, which has the lookup type given.
+    fn parse(table_data: &[u8], offset: usize, lookup_type: u16) -> Result<Self, ParseError>;
+}
+
+#[derive(Debug)]
+pub struct LookupList<T> {
+    lookups: Vec<Lookup<T>>,
+}
+
+#[derive(Debug)]
+pub struct Lookup<T> {
+    /// The `IGNORE_` and other flags which affect how the lookup is applied.
+    pub flag: u16,
+    /// An index into the GDEF table's mark glyph sets.
+    pub mark_filtering_set: Option<u16>,
+    pub subtables: Vec<T>,
+}
+
+impl<T: LookupSubtable> LookupList<T> {
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let lookup_count = reader.read_u16()?;
+        let mut lookups = Vec::with_capacity(lookup_count as usize);
+        for _ in 0..lookup_count {
+            let lookup_offset = offset + reader.read::<usize, Offset16>()?;
+            // A lookup of a type which isn't defined, perhaps from a later version of the table,
+            // is left empty rather than failing the rest. It keeps its place in the list, since
+            // features refer to lookups by index.
+            let lookup = match Lookup::parse(table_data, lookup_offset) {
+                Err(ParseError::UnknownFormat) => Lookup {
+                    flag: 0,
+                    mark_filtering_set: None,
+                    subtables: vec![],
+                },
+                result => result?,
+            };
+            lookups.push(lookup);
+        }
+
+        Ok(Self { lookups })
+    }
+}
+
+impl<T> LookupList<T> {
+    pub fn get(&self, idx: u16) -> Option<&Lookup<T>> {
+        self.lookups.get(idx as usize)
+    }
+}
+
+impl<T: LookupSubtable> Lookup<T> {
+    fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let lookup_type = reader.read_u16()?;
+        let flag = reader.read_u16()?;
+        let subtable_count = reader.read_u16()? as usize;
+        let subtable_offsets = reader.read_array::<usize, Offset16>(subtable_count)?;
+        let mark_filtering_set = if flag & USE_MARK_FILTERING_SET != 0 {
+            Some(reader.read_u16()?)
+        } else {
+            None
+        };
+
+        let mut subtables = Vec::with_capacity(subtable_count);
+        for subtable_offset in subtable_offsets {
+            subtables.push(T::parse(table_data, offset + subtable_offset, lookup_type)?);
+        }
+
+        Ok(Self {
+            flag,
+            mark_filtering_set,
+            subtables,
+        })
+    }
+}
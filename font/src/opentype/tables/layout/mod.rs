@@ -0,0 +1,146 @@
+//! Structures shared by the OpenType layout tables: GDEF, GSUB and GPOS.
+//!
+//! GSUB and GPOS have the same layout. A script list gives the features for each writing system
+//! and language, the feature list gives the lookups for each feature, and the lookup list holds
+//! the lookups, whose subtables are specific to each table.
+
+pub mod buffer;
+pub mod class_def;
+pub mod context;
+pub mod coverage;
+pub mod feature_list;
+pub mod feature_variations;
+pub mod lookup_list;
+pub mod script_list;
+
+pub use self::buffer::{Direction, GlyphInfo, GlyphPosition};
+pub use self::class_def::ClassDef;
+pub use self::coverage::Coverage;
+
+use self::feature_list::FeatureList;
+use self::feature_variations::FeatureVariations;
+use self::lookup_list::{LookupList, LookupSubtable};
+use self::script_list::ScriptList;
+use opentype::types::{Offset16, Offset32, ReadError, Reader};
+use std::collections::BTreeMap;
+
+/// The parts of a GSUB or GPOS table, whose lookups have subtables of type `T`.
+#[derive(Debug)]
+pub struct LayoutTable<T> {
+    scripts: ScriptList,
+    features: FeatureList,
+    /// From version 1.1.
+    feature_variations: Option<FeatureVariations>,
+    pub lookups: LookupList<T>,
+}
+
+impl<T: LookupSubtable> LayoutTable<T> {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(table_data);
+        let major_version = reader.read_u16()?;
+        let minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(ParseError::UnknownVersion);
+        }
+
+        let script_list_offset = reader.read::<usize, Offset16>()?;
+        let feature_list_offset = reader.read::<usize, Offset16>()?;
+        let lookup_list_offset = reader.read::<usize, Offset16>()?;
+        let feature_variations_offset = if minor_version >= 1 {
+            reader.read::<usize, Offset32>()?
+        } else {
+            0
+        };
+
+        let feature_variations = match feature_variations_offset {
+            0 => None,
+            offset => Some(FeatureVariations::parse(table_data, offset)?),
+        };
+
+        Ok(Self {
+            scripts: ScriptList::parse(table_data, script_list_offset)?,
+            features: FeatureList::parse(table_data, feature_list_offset)?,
+            feature_variations,
+            lookups: LookupList::parse(table_data, lookup_list_offset)?,
+        })
+    }
+}
+
+impl<T> LayoutTable<T> {
+    /// Whether the table has features for the script, not counting the default script it falls
+    /// back to.
+    pub fn has_script(&self, script: [char; 4]) -> bool {
+        self.scripts.has_script(script)
+    }
+
+    /// The lookups which implement the given features for a script and language, in the order
+    /// they should be applied.
+    ///
+    /// Each feature comes with a mask, which limits it to the glyphs whose mask shares a bit with
+    /// it. A lookup used by several features gets all of their masks. `required` adds the
+    /// language's required feature, if it has one, which applies to every glyph. Shapers which
+    /// apply features in stages should only add it to one of them, so that its lookups run once.
+    /// `coords` are the normalized coordinates of a variable font's instance, which may be empty
+    /// for the default instance.
+    pub fn lookups_for(
+        &self,
+        script: [char; 4],
+        language: Option<[char; 4]>,
+        features: &[([char; 4], u32)],
+        required: bool,
+        coords: &[f32],
+    ) -> Vec<(u16, u32)> {
+        let lang_sys = match self.scripts.lang_sys(script, language) {
+            Some(lang_sys) => lang_sys,
+            None => return vec![],
+        };
+
+        let mut lookups = BTreeMap::new();
+        let required = lang_sys
+            .required_feature_index
+            .filter(|_| required)
+            .map(|idx| (idx, !0));
+        let chosen = lang_sys.feature_indices.iter().filter_map(|&idx| {
+            let (tag, _) = self.features.get(idx)?;
+            let mask = features
+                .iter()
+                .filter(|(feature_tag, _)| feature_tag == tag)
+                .fold(0, |mask, (_, feature_mask)| mask | feature_mask);
+            Some((idx, mask))
+        });
+        for (idx, mask) in required.into_iter().chain(chosen) {
+            if mask == 0 {
+                continue;
+            }
+            let substitute = self
+                .feature_variations
+                .as_ref()
+                .and_then(|variations| variations.substitute(idx, coords));
+            let feature = match substitute.or_else(|| self.features.get(idx).map(|(_, f)| f)) {
+                Some(feature) => feature,
+                None => continue,
+            };
+            for &lookup_index in &feature.lookup_indices {
+                *lookups.entry(lookup_index).or_insert(0) |= mask;
+            }
+        }
+        lookups.into_iter().collect()
+    }
+}
+
+/// Errors which result from unexpected data in a layout table.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The table's major version isn't 1.
+    UnknownVersion,
+    /// A subtable has a format or lookup type which isn't defined.
+    UnknownFormat,
+    /// The entry at the given offset runs past the end of the table.
+    Truncated(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
+}
@@ -0,0 +1,154 @@
+//! The script list gives, for each writing system and language, the features a layout table
+//! offers for it.
+
+use super::ParseError;
+use opentype::types::{Offset16, Reader, U16};
+use std::iter;
+
+#[derive(Debug)]
+pub struct ScriptList {
+    scripts: Vec<([char; 4], Script)>,
+}
+
+#[derive(Debug)]
+pub struct Script {
+    default_lang_sys: Option<LangSys>,
+    lang_sys: Vec<([char; 4], LangSys)>,
+}
+
+/// The features which apply to one language.
+#[derive(Debug)]
+pub struct LangSys {
+    /// A feature which must always be applied, as an index into the feature list.
+    pub required_feature_index: Option<u16>,
+    pub feature_indices: Vec<u16>,
+}
+
+impl ScriptList {
+    /// The scripts to fall back to, in order, when a font has nothing for the one asked for.
+    const FALLBACK_SCRIPTS: [[char; 4]; 3] = [
+        ['D', 'F', 'L', 'T'],
+        ['d', 'f', 'l', 't'],
+        ['l', 'a', 't', 'n'],
+    ];
+
+    pub fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let script_count = reader.read_u16()?;
+        let mut scripts = Vec::with_capacity(script_count as usize);
+        for _ in 0..script_count {
+            let tag = reader.read_tag()?;
+            let script_offset = offset + reader.read::<usize, Offset16>()?;
+            scripts.push((tag, Script::parse(table_data, script_offset)?));
+        }
+
+        Ok(Self { scripts })
+    }
+
+    /// Whether the list has the script itself, rather than only one to fall back to.
+    pub fn has_script(&self, script: [char; 4]) -> bool {
+        self.scripts.iter().any(|(tag, _)| *tag == script)
+    }
+
+    /// The features for a script and language. Scripts the font doesn't have fall back to its
+    /// default script, and languages to the script's default language.
+    pub fn lang_sys(&self, script: [char; 4], language: Option<[char; 4]>) -> Option<&LangSys> {
+        let script = iter::once(script)
+            .chain(Self::FALLBACK_SCRIPTS.iter().cloned())
+            .filter_map(|tag| {
+                self.scripts
+                    .iter()
+                    .find(|(script_tag, _)| *script_tag == tag)
+            })
+            .map(|(_, script)| script)
+            .next()?;
+
+        let lang_sys = language.and_then(|language| {
+            script
+                .lang_sys
+                .iter()
+                .find(|(tag, _)| *tag == language)
+                .map(|(_, lang_sys)| lang_sys)
+        });
+        lang_sys.or(script.default_lang_sys.as_ref())
+    }
+}
+
+impl Script {
+    fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        let mut reader = Reader::at(table_data, offset);
+        let default_lang_sys = match reader.read::<usize, Offset16>()? {
+            0 => None,
+            lang_sys_offset => Some(LangSys::parse(table_data, offset + lang_sys_offset)?),
+        };
+        let lang_sys_count = reader.read_u16()?;
+        let mut lang_sys = Vec::with_capacity(lang_sys_count as usize);
+        for _ in 0..lang_sys_count {
+            let tag = reader.read_tag()?;
+            let lang_sys_offset = offset + reader.read::<usize, Offset16>()?;
+            lang_sys.push((tag, LangSys::parse(table_data, lang_sys_offset)?));
+        }
+
+        Ok(Self {
+            default_lang_sys,
+            lang_sys,
+        })
+    }
+}
+
+impl LangSys {
+    fn parse(table_data: &[u8], offset: usize) -> Result<Self, ParseError> {
+        // Skip the lookup order offset, which is reserved.
+        let mut reader = Reader::at(table_data, offset + 2);
+        let required_feature_index = match reader.read_u16()? {
+            0xFFFF => None,
+            idx => Some(idx),
+        };
+        let feature_index_count = reader.read_u16()? as usize;
+        let feature_indices = reader.read_array::<u16, U16>(feature_index_count)?;
+
+        Ok(Self {
+            required_feature_index,
+            feature_indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Has 'latn' with a default language using features 0 and 1, and Turkish using feature 2 as
+    /// well as the required feature 3.
+    const SAMPLE_LIST: [u8; 38] = [
+        0x00, 0x01, b'l', b'a', b't', b'n', 0x00, 0x08, // one script at 8
+        0x00, 0x0A, 0x00, 0x01, b'T', b'R', b'K', b' ', 0x00, 0x14, // script
+        0x00, 0x00, 0xFF, 0xFF, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, // 18: default
+        0x00, 0x00, 0x00, 0x03, 0x00, 0x01, 0x00, 0x02, // 28: Turkish
+        0x00, 0x00, // padding
+    ];
+
+    #[test]
+    fn lang_sys() {
+        let list = ScriptList::parse(&SAMPLE_LIST, 0).unwrap();
+        let latn = ['l', 'a', 't', 'n'];
+
+        let default = list.lang_sys(latn, None).unwrap();
+        assert_eq!(default.required_feature_index, None);
+        assert_eq!(default.feature_indices, vec![0, 1]);
+
+        let turkish = list.lang_sys(latn, Some(['T', 'R', 'K', ' '])).unwrap();
+        assert_eq!(turkish.required_feature_index, Some(3));
+        assert_eq!(turkish.feature_indices, vec![2]);
+
+        let english = list.lang_sys(latn, Some(['E', 'N', 'G', ' '])).unwrap();
+        assert_eq!(english.feature_indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn fall_back_to_latin() {
+        let list = ScriptList::parse(&SAMPLE_LIST, 0).unwrap();
+
+        assert!(list.lang_sys(['c', 'y', 'r', 'l'], None).is_some());
+    }
+}
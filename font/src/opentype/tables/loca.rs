@@ -1,5 +1,5 @@
 use super::head::IndexToLocFormat;
-use opentype::types::{DataType, Offset, Offset16, Offset32};
+use opentype::types::{ReadError, Reader};
 
 #[derive(Debug)]
 pub struct LocaTable {
@@ -14,22 +14,35 @@ impl LocaTable {
         version: IndexToLocFormat,
         num_glyphs: u16,
         glyf_len: usize,
-    ) -> Self {
+    ) -> Result<Self, ParseError> {
+        if let IndexToLocFormat::Unknown(_) = version {
+            return Err(ParseError::UnknownFormat);
+        }
+
+        let mut reader = Reader::new(table_data);
         let mut offsets = Vec::new();
-        for i in 0..num_glyphs as usize {
-            offsets.push(Self::offset(table_data, version, i));
+        let mut previous = 0;
+        for _ in 0..num_glyphs {
+            let entry_offset = reader.offset();
+            let offset = Self::read_offset(&mut reader, version)?;
+            // Each glyph ends where the next begins, so offsets must never decrease.
+            if offset < previous || offset > glyf_len {
+                return Err(ParseError::BadOffset(entry_offset));
+            }
+            offsets.push(offset);
+            previous = offset;
         }
 
-        Self {
+        Ok(Self {
             num_glyphs,
             offsets,
             glyf_len,
-        }
+        })
     }
 
     pub fn index(&self, idx: usize) -> Option<Location> {
-        if idx as u16 >= self.num_glyphs {
-            panic!("Index out of range.");
+        if idx >= self.num_glyphs as usize {
+            return None;
         }
 
         calc_location(&self.offsets, self.glyf_len, idx)
@@ -39,21 +52,33 @@ impl LocaTable {
         LocationIter::new(self.offsets.clone(), self.glyf_len)
     }
 
-    fn offset(table_data: &[u8], version: IndexToLocFormat, idx: usize) -> usize {
-        Self::value_at(table_data, version, idx)
-    }
-
-    fn value_at(table_data: &[u8], version: IndexToLocFormat, idx: usize) -> Offset {
+    fn read_offset(reader: &mut Reader, version: IndexToLocFormat) -> Result<usize, ReadError> {
         match version {
-            IndexToLocFormat::ShortOffset => Offset16::extract(table_data, idx * 2usize) * 2usize,
-            IndexToLocFormat::LongOffset => Offset32::extract(table_data, idx * 4usize),
-            _ => {
-                panic!("Unknown loca format {:?}", version);
-            }
+            // Short offsets store the actual offset divided by two.
+            IndexToLocFormat::ShortOffset => reader.read_offset16().map(|offset| offset * 2),
+            IndexToLocFormat::LongOffset => reader.read_offset32(),
+            IndexToLocFormat::Unknown(_) => unreachable!("format is checked before parsing."),
         }
     }
 }
 
+/// Errors which result from unexpected data.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The index-to-location format given by the head table isn't recognised.
+    UnknownFormat,
+    /// The entry at the given offset runs past the end of the table.
+    Truncated(usize),
+    /// The entry at the given offset points outside the glyf table or before the previous glyph.
+    BadOffset(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub struct Location {
     pub(crate) offset: usize,
@@ -99,3 +124,54 @@ fn calc_location(offsets: &Vec<usize>, glyf_len: usize, idx: usize) -> Option<Lo
         (None, _) => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_short_offsets() {
+        let table = [0x00u8, 0x00, 0x00, 0x02, 0x00, 0x06];
+        let loca = LocaTable::parse(&table, IndexToLocFormat::ShortOffset, 3, 20).unwrap();
+
+        assert_eq!(
+            loca.index(1),
+            Some(Location {
+                offset: 4,
+                length: 8
+            })
+        );
+        assert_eq!(
+            loca.index(2),
+            Some(Location {
+                offset: 12,
+                length: 8
+            })
+        );
+        assert_eq!(loca.index(3), None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = [0x00u8, 0x00, 0x00, 0x02, 0x00];
+        let result = LocaTable::parse(&table, IndexToLocFormat::ShortOffset, 3, 20);
+
+        assert_eq!(result.unwrap_err(), ParseError::Truncated(4));
+    }
+
+    #[test]
+    fn parse_offset_beyond_glyf() {
+        let table = [0x00u8, 0x00, 0x00, 0x10];
+        let result = LocaTable::parse(&table, IndexToLocFormat::LongOffset, 1, 8);
+
+        assert_eq!(result.unwrap_err(), ParseError::BadOffset(0));
+    }
+
+    #[test]
+    fn parse_decreasing_offsets() {
+        let table = [0x00u8, 0x04, 0x00, 0x02];
+        let result = LocaTable::parse(&table, IndexToLocFormat::ShortOffset, 2, 20);
+
+        assert_eq!(result.unwrap_err(), ParseError::BadOffset(2));
+    }
+}
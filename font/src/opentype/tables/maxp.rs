@@ -1,6 +1,6 @@
 use fixed::{frac, FixedI32};
 
-use opentype::types::{DataType, I32, U16};
+use opentype::types::{DataType, ReadError, I32, U16};
 
 const OFFSET_VERSION: usize = 0;
 const OFFSET_NUM_GLYPHS: usize = 4;
@@ -36,12 +36,12 @@ pub struct MaxpTable {
     max_stack_elements: Option<u16>,
     max_size_of_instructions: Option<u16>,
     max_comp_elements: Option<u16>,
-    max_comp_depth: Option<u16>,
+    pub max_comp_depth: Option<u16>,
 }
 
 impl MaxpTable {
-    pub fn parse(table_data: &[u8]) -> Self {
-        let version = Self::parse_version(table_data);
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        let version = Self::parse_version(table_data)?;
 
         let mut max_points = None;
         let mut max_contours = None;
@@ -57,25 +57,25 @@ impl MaxpTable {
         let mut max_comp_elements = None;
         let mut max_comp_depth = None;
         if version == Version::V1_0 {
-            max_points = Some(U16::extract(table_data, OFFSET_MAX_POINTS));
-            max_contours = Some(U16::extract(table_data, OFFSET_MAX_CONTOURS));
-            max_comp_pts = Some(U16::extract(table_data, OFFSET_MAX_COMPOSITE_POINTS));
-            max_comp_contours = Some(U16::extract(table_data, OFFSET_MAX_COMPOSITE_CONTOURS));
-            max_zones = Some(U16::extract(table_data, OFFSET_MAX_ZONES));
-            max_twilight_points = Some(U16::extract(table_data, OFFSET_MAX_TWILIGHT_POINTS));
-            max_storage = Some(U16::extract(table_data, OFFSET_MAX_STORAGE));
-            max_func_defs = Some(U16::extract(table_data, OFFSET_MAX_FUNC_DEFS));
-            max_instruction_defs = Some(U16::extract(table_data, OFFSET_MAX_INSTRUCTION_DEFS));
-            max_stack_elements = Some(U16::extract(table_data, OFFSET_MAX_STACK_ELEMENTS));
+            max_points = Some(U16::extract(table_data, OFFSET_MAX_POINTS)?);
+            max_contours = Some(U16::extract(table_data, OFFSET_MAX_CONTOURS)?);
+            max_comp_pts = Some(U16::extract(table_data, OFFSET_MAX_COMPOSITE_POINTS)?);
+            max_comp_contours = Some(U16::extract(table_data, OFFSET_MAX_COMPOSITE_CONTOURS)?);
+            max_zones = Some(U16::extract(table_data, OFFSET_MAX_ZONES)?);
+            max_twilight_points = Some(U16::extract(table_data, OFFSET_MAX_TWILIGHT_POINTS)?);
+            max_storage = Some(U16::extract(table_data, OFFSET_MAX_STORAGE)?);
+            max_func_defs = Some(U16::extract(table_data, OFFSET_MAX_FUNC_DEFS)?);
+            max_instruction_defs = Some(U16::extract(table_data, OFFSET_MAX_INSTRUCTION_DEFS)?);
+            max_stack_elements = Some(U16::extract(table_data, OFFSET_MAX_STACK_ELEMENTS)?);
             max_size_of_instructions =
-                Some(U16::extract(table_data, OFFSET_MAX_SIZE_OF_INSTRUCTIONS));
-            max_comp_elements = Some(U16::extract(table_data, OFFSET_MAX_COMPONENT_ELEMENTS));
-            max_comp_depth = Some(U16::extract(table_data, OFFSET_MAX_COMPONENT_DEPTH));
+                Some(U16::extract(table_data, OFFSET_MAX_SIZE_OF_INSTRUCTIONS)?);
+            max_comp_elements = Some(U16::extract(table_data, OFFSET_MAX_COMPONENT_ELEMENTS)?);
+            max_comp_depth = Some(U16::extract(table_data, OFFSET_MAX_COMPONENT_DEPTH)?);
         }
 
-        Self {
+        Ok(Self {
             version,
-            num_glyphs: U16::extract(table_data, OFFSET_NUM_GLYPHS),
+            num_glyphs: U16::extract(table_data, OFFSET_NUM_GLYPHS)?,
             max_points,
             max_contours,
             max_comp_pts,
@@ -89,16 +89,16 @@ impl MaxpTable {
             max_size_of_instructions,
             max_comp_elements,
             max_comp_depth,
-        }
+        })
     }
 
-    fn parse_version(table_data: &[u8]) -> Version {
-        let value = I32::extract(table_data, OFFSET_VERSION);
-        match value {
+    fn parse_version(table_data: &[u8]) -> Result<Version, ReadError> {
+        let value = I32::extract(table_data, OFFSET_VERSION)?;
+        Ok(match value {
             0x00005000 => Version::V0_5,
             0x00010000 => Version::V1_0,
             _ => Version::Unknown(Fixed::from_bits(value)),
-        }
+        })
     }
 }
 
@@ -1,6 +1,105 @@
 pub mod cmap;
+pub mod gdef;
 pub mod glyf;
+pub mod gpos;
+pub mod gsub;
 pub mod head;
+pub mod hhea;
+pub mod hmtx;
+pub mod kern;
+pub mod layout;
 pub mod loca;
 pub mod maxp;
 pub mod name;
+pub mod os2;
+pub mod post;
+pub mod vhea;
+pub mod vmtx;
+pub mod vorg;
+
+#[cfg(test)]
+pub(crate) mod test_data {
+    use opentype::types::{DataType, Offset32};
+
+    /// A small but complete font which table parsers can take real data from.
+    pub const SAMPLE_FONT: &[u8] = include_bytes!("../../../samples/empty.ttf");
+
+    /// Returns the data for the sample font's table with the given tag.
+    pub fn sample_table(tag: &[u8; 4]) -> &'static [u8] {
+        let num_tables = SAMPLE_FONT[5] as usize;
+        for n in 0..num_tables {
+            let record = &SAMPLE_FONT[12 + n * 16..12 + (n + 1) * 16];
+            if &record[..4] == tag {
+                let offset = Offset32::extract(record, 8).unwrap();
+                let length = Offset32::extract(record, 12).unwrap();
+                return &SAMPLE_FONT[offset..offset + length];
+            }
+        }
+        panic!("Sample font has no {:?} table.", tag);
+    }
+
+    /// Builds a font with 1000 units per em which maps each character to a glyph and gives each
+    /// glyph, by ID, an advance width. `tables` adds others, such as GSUB or kern.
+    pub fn build_font(
+        mappings: &[(char, u16)],
+        advances: &[u16],
+        tables: &[(&[u8; 4], &[u8])],
+    ) -> Vec<u8> {
+        let mut head = sample_table(b"head").to_vec();
+        head[18..20].clone_from_slice(&1000u16.to_be_bytes());
+        let mut hhea = sample_table(b"hhea").to_vec();
+        hhea[34..36].clone_from_slice(&(advances.len() as u16).to_be_bytes());
+        let mut maxp = vec![0x00, 0x00, 0x50, 0x00];
+        maxp.extend_from_slice(&(advances.len() as u16).to_be_bytes());
+        let mut hmtx = Vec::new();
+        for advance in advances {
+            hmtx.extend_from_slice(&advance.to_be_bytes());
+            hmtx.extend_from_slice(&[0x00, 0x00]);
+        }
+
+        // A format 12 subtable for Windows Unicode with a group for each character.
+        let mut mappings = mappings.to_vec();
+        mappings.sort();
+        let mut cmap = vec![
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x03, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x0C,
+            0x00, 0x00,
+        ];
+        cmap.extend_from_slice(&(16 + 12 * mappings.len() as u32).to_be_bytes());
+        cmap.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+        cmap.extend_from_slice(&(mappings.len() as u32).to_be_bytes());
+        for &(c, glyph_id) in &mappings {
+            cmap.extend_from_slice(&(c as u32).to_be_bytes());
+            cmap.extend_from_slice(&(c as u32).to_be_bytes());
+            cmap.extend_from_slice(&u32::from(glyph_id).to_be_bytes());
+        }
+
+        let mut tables: Vec<(&[u8; 4], &[u8])> = tables.to_vec();
+        tables.extend_from_slice(&[
+            (b"cmap", &cmap),
+            (b"head", &head),
+            (b"hhea", &hhea),
+            (b"hmtx", &hmtx),
+            (b"maxp", &maxp),
+        ]);
+        tables.sort_by_key(|&(tag, _)| tag);
+
+        let mut content = vec![0x00, 0x01, 0x00, 0x00];
+        content.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+        // The search range fields only speed up lookups, so they can be left empty.
+        content.extend_from_slice(&[0x00; 6]);
+        let mut offset = 12 + 16 * tables.len();
+        for &(tag, data) in &tables {
+            content.extend_from_slice(tag);
+            content.extend_from_slice(&[0x00; 4]);
+            content.extend_from_slice(&(offset as u32).to_be_bytes());
+            content.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            offset += (data.len() + 3) & !3;
+        }
+        for &(_, data) in &tables {
+            content.extend_from_slice(data);
+            let padded = (content.len() + 3) & !3;
+            content.resize(padded, 0x00);
+        }
+        content
+    }
+}
@@ -3,4 +3,5 @@ mod record;
 mod table;
 
 pub use self::name::Name;
+pub use self::record::ParseError;
 pub use self::table::NameTable;
@@ -1,9 +1,7 @@
 use super::name::Name;
-use byteorder::{BigEndian, ByteOrder};
 use opentype::encoding::Encoding;
 use opentype::platform::Platform;
-
-const U16_LENGTH: usize = 2;
+use opentype::types::{slice, DataType, ReadError, U16};
 
 #[derive(Debug, PartialEq)]
 pub struct NameRecord {
@@ -23,53 +21,49 @@ impl NameRecord {
     const STRING_LENGTH_OFFSET: usize = 8;
     const STRING_OFFSET_OFFSET: usize = 10;
 
+    /// Deserialize a record, copying its string out of `string_data`.
+    ///
+    /// A `Truncated` error refers to an offset in `record_data` if the record itself is short,
+    /// or in `string_data` if the string runs past the end of the storage area.
     pub fn deserialize(record_data: &[u8], string_data: &[u8]) -> Result<Self, ParseError> {
-        let len = Self::parse_string_length(record_data);
-        let offset = Self::parse_string_offset(record_data);
+        let len = Self::parse_string_length(record_data)?;
+        let offset = Self::parse_string_offset(record_data)?;
 
         Ok(Self {
             platform: Self::parse_platform(record_data)?,
             encoding: Self::parse_encoding(record_data)?,
-            language_id: Self::parse_language_id(record_data),
-            name_id: Self::parse_name_id(record_data),
-            name: Name::lookup(Self::parse_name_id(record_data)),
-            data: string_data[offset..offset + len].to_vec(),
+            language_id: Self::parse_language_id(record_data)?,
+            name_id: Self::parse_name_id(record_data)?,
+            name: Name::lookup(Self::parse_name_id(record_data)?),
+            data: slice(string_data, offset, len)?.to_vec(),
         })
     }
 
     fn parse_platform(data: &[u8]) -> Result<Platform, ParseError> {
-        let platform_id = BigEndian::read_u16(
-            &data[Self::PLATFORM_ID_OFFSET..Self::PLATFORM_ID_OFFSET + U16_LENGTH],
-        );
+        let platform_id = U16::extract(data, Self::PLATFORM_ID_OFFSET)?;
         Platform::lookup(platform_id).ok_or(ParseError::UnknownPlatformID)
     }
 
     fn parse_encoding(data: &[u8]) -> Result<Encoding, ParseError> {
         let platform = Self::parse_platform(data)?;
-        let encoding_id = BigEndian::read_u16(
-            &data[Self::ENCODING_ID_OFFSET..Self::ENCODING_ID_OFFSET + U16_LENGTH],
-        );
+        let encoding_id = U16::extract(data, Self::ENCODING_ID_OFFSET)?;
         Encoding::lookup(platform, encoding_id).ok_or(ParseError::UnknownEncodingID)
     }
 
-    fn parse_language_id(data: &[u8]) -> u16 {
-        BigEndian::read_u16(&data[Self::LANGUAGE_ID_OFFSET..Self::LANGUAGE_ID_OFFSET + U16_LENGTH])
+    fn parse_language_id(data: &[u8]) -> Result<u16, ReadError> {
+        U16::extract(data, Self::LANGUAGE_ID_OFFSET)
     }
 
-    fn parse_name_id(data: &[u8]) -> u16 {
-        BigEndian::read_u16(&data[Self::NAME_ID_OFFSET..Self::NAME_ID_OFFSET + U16_LENGTH])
+    fn parse_name_id(data: &[u8]) -> Result<u16, ReadError> {
+        U16::extract(data, Self::NAME_ID_OFFSET)
     }
 
-    fn parse_string_length(data: &[u8]) -> usize {
-        BigEndian::read_u16(
-            &data[Self::STRING_LENGTH_OFFSET..Self::STRING_LENGTH_OFFSET + U16_LENGTH],
-        ) as usize
+    fn parse_string_length(data: &[u8]) -> Result<usize, ReadError> {
+        U16::extract(data, Self::STRING_LENGTH_OFFSET).map(|len| len as usize)
     }
 
-    fn parse_string_offset(data: &[u8]) -> usize {
-        BigEndian::read_u16(
-            &data[Self::STRING_OFFSET_OFFSET..Self::STRING_OFFSET_OFFSET + U16_LENGTH],
-        ) as usize
+    fn parse_string_offset(data: &[u8]) -> Result<usize, ReadError> {
+        U16::extract(data, Self::STRING_OFFSET_OFFSET).map(|offset| offset as usize)
     }
 }
 
@@ -83,6 +77,14 @@ pub enum ParseError {
     UnknownPlatformID,
     /// The Encoding ID hasn't been implemented yet.
     UnknownEncodingID,
+    /// A read at the given offset ran past the end of the table.
+    Truncated(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
 }
 
 #[cfg(test)]
@@ -105,4 +107,18 @@ mod tests {
         assert_eq!(record.name, Some(Name::CopyrightNotice));
         assert_eq!(record.data, vec![0u8, 24, 0, 27]);
     }
+
+    #[test]
+    fn deserialize_truncated_record() {
+        let result = NameRecord::deserialize(&SAMPLE_NAME_RECORD[..9], &SAMPLE_STRING_DATA);
+
+        assert_eq!(result, Err(ParseError::Truncated(8)));
+    }
+
+    #[test]
+    fn deserialize_string_beyond_storage() {
+        let result = NameRecord::deserialize(&SAMPLE_NAME_RECORD, &SAMPLE_STRING_DATA[..3]);
+
+        assert_eq!(result, Err(ParseError::Truncated(0)));
+    }
 }
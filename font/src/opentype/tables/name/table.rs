@@ -2,7 +2,7 @@ use super::name::Name;
 use super::record::{NameRecord, ParseError};
 use opentype::encoding::Encoding;
 use opentype::platform::Platform;
-use opentype::types::{DataType, Offset16, U16};
+use opentype::types::{DataType, Offset16, ReadError, Reader, U16};
 
 /// The name table stores strings which represent various metadata in
 /// the font (e.g., foundry name, font name, etc.).
@@ -28,13 +28,13 @@ impl NameTable {
 
     /// Deserialize the name table from font file data.
     pub fn deserialize(table_data: &[u8]) -> Result<Self, ParseError> {
-        let string_offset = Offset16::extract(table_data, Self::STRING_OFFSET_OFFSET);
-        let string_storage = Self::parse_string_storage(table_data, string_offset);
-        let name_records = Self::parse_name_records(table_data, &string_storage)?;
+        let string_offset = Offset16::extract(table_data, Self::STRING_OFFSET_OFFSET)?;
+        let string_storage = Self::parse_string_storage(table_data, string_offset)?;
+        let name_records = Self::parse_name_records(table_data, string_storage, string_offset)?;
 
         Ok(NameTable {
             format: Self::parse_format(table_data)?,
-            count: U16::extract(table_data, Self::COUNT_OFFSET),
+            count: U16::extract(table_data, Self::COUNT_OFFSET)?,
             string_offset: string_offset,
             name_records: name_records,
         })
@@ -82,13 +82,12 @@ impl NameTable {
         result
     }
 
-    fn parse_string_storage(table_data: &[u8], offset: usize) -> &[u8] {
-        let storage_length = table_data.len() - offset as usize;
-        &table_data[offset..offset + storage_length]
+    fn parse_string_storage(table_data: &[u8], offset: usize) -> Result<&[u8], ReadError> {
+        table_data.get(offset..).ok_or(ReadError { offset })
     }
 
     fn parse_format(data: &[u8]) -> Result<Format, ParseError> {
-        let value = U16::extract(data, Self::FORMAT_OFFSET);
+        let value = U16::extract(data, Self::FORMAT_OFFSET)?;
         match value {
             0 => Ok(Format::Format0),
             1 => Ok(Format::Format1),
@@ -99,14 +98,21 @@ impl NameTable {
     fn parse_name_records(
         data: &[u8],
         string_storage: &[u8],
+        string_offset: usize,
     ) -> Result<Vec<NameRecord>, ParseError> {
         let mut records = vec![];
-        let num_records = U16::extract(data, Self::COUNT_OFFSET);
-        for n in 0..num_records {
-            let offset = Self::NAME_RECORDS_OFFSET + n as usize * Self::NAME_RECORD_LENGTH;
-            let record_data = &data[offset..offset + Self::NAME_RECORD_LENGTH];
-
-            records.push(NameRecord::deserialize(record_data, string_storage)?);
+        let num_records = U16::extract(data, Self::COUNT_OFFSET)?;
+        let mut reader = Reader::at(data, Self::NAME_RECORDS_OFFSET);
+        for _ in 0..num_records {
+            let record_data = reader.read_bytes(Self::NAME_RECORD_LENGTH)?;
+
+            // The whole record is present, so a truncation can only be in the string storage.
+            let record =
+                NameRecord::deserialize(record_data, string_storage).map_err(|err| match err {
+                    ParseError::Truncated(offset) => ParseError::Truncated(string_offset + offset),
+                    err => err,
+                })?;
+            records.push(record);
         }
 
         Ok(records)
@@ -123,6 +129,7 @@ pub enum Format {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use opentype::tables::test_data::sample_table;
 
     const SAMPLE_TABLE: [u8; 32] = [
         0u8, 0, 0, 1, 0, 18, 0, 0, 0, 0, 0, 0, 0, 1, 0, 14, 0, 0, 0, 82, 0, 101, 0, 103, 0, 117, 0,
@@ -138,4 +145,24 @@ mod tests {
         const EXPECTED: [u8; 14] = [0u8, 82, 0, 101, 0, 103, 0, 117, 0, 108, 0, 97, 0, 114];
         assert_eq!(result, Some(&EXPECTED[..]));
     }
+
+    #[test]
+    fn deserialize_sample_table() {
+        let table = NameTable::deserialize(sample_table(b"name")).unwrap();
+
+        assert_eq!(table.count as usize, table.name_records.len());
+    }
+
+    #[test]
+    fn deserialize_truncated_tables() {
+        let data = sample_table(b"name");
+        // Every truncation either cuts off a name record or the strings that records point to.
+        // The sample table ends with a single byte of padding.
+        for len in 0..data.len() - 1 {
+            match NameTable::deserialize(&data[..len]) {
+                Err(ParseError::Truncated(offset)) => assert!(offset <= data.len()),
+                result => panic!("Unexpected result for {} bytes: {:?}", len, result),
+            }
+        }
+    }
 }
@@ -0,0 +1,425 @@
+//! The OS/2 table holds the metrics and classifications which Windows uses to pick and lay out
+//! fonts, and which most other platforms have adopted since.
+//!
+//! Each version adds fields to the end of the table, so later fields are `None` for fonts with an
+//! earlier version, or whose table stops short of them.
+
+use opentype::types::{ReadError, Reader};
+
+#[derive(Debug)]
+pub struct Os2Table {
+    pub weight_class: u16,
+    /// From 1 (ultra-condensed) to 9 (ultra-expanded), where 5 is normal.
+    pub width_class: u16,
+    pub fs_type: u16,
+    pub panose: Panose,
+    /// The Unicode blocks the font covers, as a 128 bit set of the bits defined by the OS/2 spec.
+    pub unicode_range: [u32; 4],
+    pub vendor_id: [char; 4],
+    pub fs_selection: u16,
+    /// Some version 0 tables, from before the typographic metrics were added, stop here.
+    pub typo_metrics: Option<TypoMetrics>,
+    /// The code pages the font covers, as a 64 bit set. Present from version 1.
+    pub code_page_range: Option<[u32; 2]>,
+    /// Present from version 2.
+    pub x_height: Option<i16>,
+    /// Present from version 2.
+    pub cap_height: Option<i16>,
+    /// The range of sizes, in twentieths of a point, the font was designed for. Present from
+    /// version 5.
+    pub optical_point_sizes: Option<(u16, u16)>,
+}
+
+/// The vertical metrics which Windows uses for line spacing and clipping, in font units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TypoMetrics {
+    pub ascender: i16,
+    pub descender: i16,
+    pub line_gap: i16,
+    pub win_ascent: u16,
+    pub win_descent: u16,
+}
+
+impl Os2Table {
+    /// The typographic metrics should be used for line spacing, rather than those in hhea.
+    pub const MASK_USE_TYPO_METRICS: u16 = 0x0080;
+
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        let mut reader = Reader::new(table_data);
+        let version = reader.read_u16()?;
+        // Skip xAvgCharWidth.
+        reader.skip(2)?;
+        let weight_class = reader.read_u16()?;
+        let width_class = reader.read_u16()?;
+        let fs_type = reader.read_u16()?;
+        // Skip the subscript, superscript and strikeout metrics, and sFamilyClass.
+        reader.skip(22)?;
+        let panose = Panose::deserialize(&mut reader)?;
+        let unicode_range = [
+            reader.read_u32()?,
+            reader.read_u32()?,
+            reader.read_u32()?,
+            reader.read_u32()?,
+        ];
+        let vendor_id = reader.read_tag()?;
+        let fs_selection = reader.read_u16()?;
+        // Skip usFirstCharIndex and usLastCharIndex.
+        reader.skip(4)?;
+
+        // Fields after these are only read if the table is long enough, as some fonts' tables
+        // are shorter than their version says. Old version 0 tables stop before the typographic
+        // metrics, and some stop partway through.
+        let typo_metrics = Self::parse_typo_metrics(&mut reader).ok();
+        let mut code_page_range = None;
+        if version >= 1 && typo_metrics.is_some() {
+            code_page_range = reader
+                .read_u32()
+                .and_then(|first| Ok([first, reader.read_u32()?]))
+                .ok();
+        }
+
+        let mut x_height = None;
+        let mut cap_height = None;
+        if version >= 2 && code_page_range.is_some() {
+            x_height = reader.read_i16().ok();
+            cap_height = reader.read_i16().ok();
+        }
+
+        let mut optical_point_sizes = None;
+        // Skip usDefaultChar, usBreakChar and usMaxContext.
+        if version >= 5 && cap_height.is_some() && reader.skip(6).is_ok() {
+            optical_point_sizes = reader
+                .read_u16()
+                .and_then(|lower| Ok((lower, reader.read_u16()?)))
+                .ok();
+        }
+
+        Ok(Self {
+            weight_class,
+            width_class,
+            fs_type,
+            panose,
+            unicode_range,
+            vendor_id,
+            fs_selection,
+            typo_metrics,
+            code_page_range,
+            x_height,
+            cap_height,
+            optical_point_sizes,
+        })
+    }
+
+    fn parse_typo_metrics(reader: &mut Reader) -> Result<TypoMetrics, ReadError> {
+        Ok(TypoMetrics {
+            ascender: reader.read_i16()?,
+            descender: reader.read_i16()?,
+            line_gap: reader.read_i16()?,
+            win_ascent: reader.read_u16()?,
+            win_descent: reader.read_u16()?,
+        })
+    }
+
+    pub fn weight(&self) -> Weight {
+        Weight::from_class(self.weight_class)
+    }
+
+    pub fn embedding_permissions(&self) -> EmbeddingPermissions {
+        EmbeddingPermissions::from_fs_type(self.fs_type)
+    }
+
+    /// The typographic metrics, if the font asks for them to be used for line spacing.
+    pub fn preferred_typo_metrics(&self) -> Option<TypoMetrics> {
+        if self.fs_selection & Self::MASK_USE_TYPO_METRICS != 0 {
+            self.typo_metrics
+        } else {
+            None
+        }
+    }
+}
+
+/// How thick the strokes of a font are, from its `usWeightClass`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Weight {
+    Thin,
+    ExtraLight,
+    Light,
+    Normal,
+    Medium,
+    SemiBold,
+    Bold,
+    ExtraBold,
+    Black,
+    /// A weight between the named ones, such as 350 from a variable font's instance, or one
+    /// outside the range 1 to 1000.
+    Other(u16),
+}
+
+impl Weight {
+    pub fn from_class(weight_class: u16) -> Self {
+        match weight_class {
+            100 => Weight::Thin,
+            200 => Weight::ExtraLight,
+            300 => Weight::Light,
+            400 => Weight::Normal,
+            500 => Weight::Medium,
+            600 => Weight::SemiBold,
+            700 => Weight::Bold,
+            800 => Weight::ExtraBold,
+            900 => Weight::Black,
+            _ => Weight::Other(weight_class),
+        }
+    }
+
+    /// The numeric weight, as used by CSS.
+    pub fn value(self) -> u16 {
+        match self {
+            Weight::Thin => 100,
+            Weight::ExtraLight => 200,
+            Weight::Light => 300,
+            Weight::Normal => 400,
+            Weight::Medium => 500,
+            Weight::SemiBold => 600,
+            Weight::Bold => 700,
+            Weight::ExtraBold => 800,
+            Weight::Black => 900,
+            Weight::Other(value) => value,
+        }
+    }
+}
+
+/// What a document which embeds the font may do with it, from the OS/2 `fsType` field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmbeddingPermissions {
+    pub usage: EmbeddingUsage,
+    /// The font must be embedded whole, rather than just the glyphs the document uses.
+    pub no_subsetting: bool,
+    /// Only the font's embedded bitmaps may be embedded, not its outlines.
+    pub bitmap_only: bool,
+}
+
+/// The ways an embedded font may be used, from most to least restrictive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EmbeddingUsage {
+    /// The font must not be embedded, or otherwise passed on, without the owner's permission.
+    Restricted,
+    /// The font may be embedded to view and print the document, but not to edit it.
+    PreviewAndPrint,
+    /// The font may be embedded in a document which can be edited.
+    Editable,
+    /// The font may be embedded and permanently installed on the recipient's system.
+    Installable,
+}
+
+impl EmbeddingPermissions {
+    const MASK_USAGE: u16 = 0x000F;
+    const MASK_RESTRICTED: u16 = 0x0002;
+    const MASK_PREVIEW_AND_PRINT: u16 = 0x0004;
+    const MASK_EDITABLE: u16 = 0x0008;
+    const MASK_NO_SUBSETTING: u16 = 0x0100;
+    const MASK_BITMAP_ONLY: u16 = 0x0200;
+
+    pub fn from_fs_type(fs_type: u16) -> Self {
+        // Only one usage bit should be set. Fonts which set several get the least restrictive.
+        let usage = if fs_type & Self::MASK_USAGE == 0 {
+            EmbeddingUsage::Installable
+        } else if fs_type & Self::MASK_EDITABLE != 0 {
+            EmbeddingUsage::Editable
+        } else if fs_type & Self::MASK_PREVIEW_AND_PRINT != 0 {
+            EmbeddingUsage::PreviewAndPrint
+        } else if fs_type & Self::MASK_RESTRICTED != 0 {
+            EmbeddingUsage::Restricted
+        } else {
+            // Bit 0 is reserved, and treated as if no usage bit was set.
+            EmbeddingUsage::Installable
+        };
+
+        Self {
+            usage,
+            no_subsetting: fs_type & Self::MASK_NO_SUBSETTING != 0,
+            bitmap_only: fs_type & Self::MASK_BITMAP_ONLY != 0,
+        }
+    }
+
+    /// Whether the font may be embedded in a document at all.
+    pub fn allows_embedding(&self) -> bool {
+        self.usage != EmbeddingUsage::Restricted
+    }
+
+    /// Whether an embedded copy of the font may be cut down to the glyphs a document uses.
+    pub fn allows_subsetting(&self) -> bool {
+        self.allows_embedding() && !self.no_subsetting
+    }
+}
+
+/// The PANOSE classification of a font's visual style.
+///
+/// The meaning of each digit after the first depends on the family kind. The field names are
+/// those used for Latin text fonts, the most common kind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Panose {
+    pub family_kind: u8,
+    pub serif_style: u8,
+    pub weight: u8,
+    pub proportion: u8,
+    pub contrast: u8,
+    pub stroke_variation: u8,
+    pub arm_style: u8,
+    pub letterform: u8,
+    pub midline: u8,
+    pub x_height: u8,
+}
+
+impl Panose {
+    fn deserialize(reader: &mut Reader) -> Result<Self, ReadError> {
+        let digits = reader.read_bytes(10)?;
+        Ok(Self {
+            family_kind: digits[0],
+            serif_style: digits[1],
+            weight: digits[2],
+            proportion: digits[3],
+            contrast: digits[4],
+            stroke_variation: digits[5],
+            arm_style: digits[6],
+            letterform: digits[7],
+            midline: digits[8],
+            x_height: digits[9],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::sample_table;
+
+    #[test]
+    fn parse_sample() {
+        let os2 = Os2Table::parse(sample_table(b"OS/2")).unwrap();
+
+        assert_eq!(os2.weight(), Weight::Normal);
+        assert_eq!(os2.width_class, 5);
+        assert_eq!(os2.vendor_id, ['P', 'f', 'E', 'd']);
+        assert_eq!(os2.panose.family_kind, 2);
+        assert_eq!(os2.panose.weight, 5);
+        assert_eq!(
+            os2.preferred_typo_metrics(),
+            Some(TypoMetrics {
+                ascender: 800,
+                descender: -200,
+                line_gap: 90,
+                win_ascent: 666,
+                win_descent: 0,
+            })
+        );
+        assert_eq!(os2.code_page_range, Some([1, 0]));
+        assert_eq!(os2.optical_point_sizes, None);
+    }
+
+    #[test]
+    fn parse_short_version_0() {
+        let mut table = sample_table(b"OS/2")[..68].to_vec();
+        table[1] = 0;
+        let os2 = Os2Table::parse(&table).unwrap();
+
+        assert_eq!(os2.typo_metrics, None);
+        assert_eq!(os2.code_page_range, None);
+        assert_eq!(os2.x_height, None);
+    }
+
+    #[test]
+    fn parse_partial_version_0() {
+        // Cut off partway through the typographic metrics.
+        let mut table = sample_table(b"OS/2")[..70].to_vec();
+        table[1] = 0;
+        let os2 = Os2Table::parse(&table).unwrap();
+
+        assert_eq!(os2.typo_metrics, None);
+        assert_eq!(os2.vendor_id, ['P', 'f', 'E', 'd']);
+
+        let mut table = sample_table(b"OS/2")[..78].to_vec();
+        table[1] = 0;
+        assert_eq!(
+            Os2Table::parse(&table)
+                .unwrap()
+                .typo_metrics
+                .unwrap()
+                .ascender,
+            800
+        );
+    }
+
+    #[test]
+    fn parse_version_5() {
+        let mut table = sample_table(b"OS/2").to_vec();
+        table[1] = 5;
+        table.extend_from_slice(&[0x00, 0xA0, 0x01, 0x40]);
+        let os2 = Os2Table::parse(&table).unwrap();
+
+        assert_eq!(os2.optical_point_sizes, Some((160, 320)));
+    }
+
+    #[test]
+    fn parse_shorter_than_version() {
+        // A version 5 table which stops where version 4 tables do.
+        let mut table = sample_table(b"OS/2").to_vec();
+        table[1] = 5;
+        let os2 = Os2Table::parse(&table).unwrap();
+
+        assert_eq!(os2.x_height, Some(0));
+        assert_eq!(os2.optical_point_sizes, None);
+
+        // A version 2 table which stops partway through the code page ranges.
+        let mut table = sample_table(b"OS/2")[..82].to_vec();
+        table[1] = 2;
+        let os2 = Os2Table::parse(&table).unwrap();
+
+        assert!(os2.typo_metrics.is_some());
+        assert_eq!(os2.code_page_range, None);
+        assert_eq!(os2.x_height, None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = &sample_table(b"OS/2")[..60];
+
+        assert_eq!(
+            Os2Table::parse(table).unwrap_err(),
+            ReadError { offset: 58 }
+        );
+    }
+
+    #[test]
+    fn weight_classes() {
+        assert_eq!(Weight::from_class(700), Weight::Bold);
+        assert_eq!(Weight::from_class(350), Weight::Other(350));
+        assert_eq!(Weight::Black.value(), 900);
+    }
+
+    #[test]
+    fn embedding_permissions() {
+        assert_eq!(
+            EmbeddingPermissions::from_fs_type(0x0000).usage,
+            EmbeddingUsage::Installable
+        );
+        assert_eq!(
+            EmbeddingPermissions::from_fs_type(0x0002).usage,
+            EmbeddingUsage::Restricted
+        );
+        // Several usage bits; the least restrictive wins.
+        assert_eq!(
+            EmbeddingPermissions::from_fs_type(0x0006).usage,
+            EmbeddingUsage::PreviewAndPrint
+        );
+        assert!(!EmbeddingPermissions::from_fs_type(0x0002).allows_embedding());
+        assert!(!EmbeddingPermissions::from_fs_type(0x0104).allows_subsetting());
+        assert_eq!(
+            EmbeddingPermissions::from_fs_type(0x0308),
+            EmbeddingPermissions {
+                usage: EmbeddingUsage::Editable,
+                no_subsetting: true,
+                bitmap_only: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! The PostScript table holds the information needed to use the font on a PostScript printer,
+//! most usefully the name of each glyph.
+
+mod mac_names;
+
+use self::mac_names::MAC_GLYPH_NAMES;
+use opentype::types::{Fixed, ReadError, Reader};
+
+#[derive(Debug)]
+pub struct PostTable {
+    italic_angle: Fixed,
+    /// The distance from the baseline to the top of the underline, in font units. Usually
+    /// negative.
+    pub underline_position: i16,
+    pub underline_thickness: i16,
+    is_fixed_pitch: u32,
+    names: GlyphNames,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Version {
+    /// Glyphs are in the standard Macintosh order and use its names.
+    V1_0,
+    /// Each glyph has an index into the standard names or the table's own.
+    V2_0,
+    /// Each glyph has an offset from its position in the standard Macintosh order. Deprecated.
+    V2_5,
+    /// There are no glyph names.
+    V3_0,
+    Unknown(Fixed),
+}
+
+#[derive(Debug)]
+enum GlyphNames {
+    None,
+    Standard,
+    Indexed {
+        indices: Vec<u16>,
+        /// Names which aren't among the standard ones, looked up by `index - 258`.
+        custom: Vec<String>,
+    },
+    Offset(Vec<i8>),
+}
+
+impl PostTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        let mut reader = Reader::new(table_data);
+        let version = match reader.read_i32()? {
+            0x00010000 => Version::V1_0,
+            0x00020000 => Version::V2_0,
+            0x00025000 => Version::V2_5,
+            0x00030000 => Version::V3_0,
+            value => Version::Unknown(Fixed::from_bits(value)),
+        };
+        let italic_angle = reader.read_fixed()?;
+        let underline_position = reader.read_i16()?;
+        let underline_thickness = reader.read_i16()?;
+        let is_fixed_pitch = reader.read_u32()?;
+        // Skip the memory usage hints for PostScript printers.
+        reader.skip(16)?;
+
+        let names = match version {
+            Version::V1_0 => GlyphNames::Standard,
+            Version::V2_0 => Self::parse_indexed_names(&mut reader),
+            Version::V2_5 => {
+                let num_glyphs = reader.read_u16().unwrap_or(0) as usize;
+                let offsets = (0..num_glyphs).map_while(|_| reader.read_u8().ok());
+                GlyphNames::Offset(offsets.map(|offset| offset as i8).collect())
+            }
+            Version::V3_0 | Version::Unknown(_) => GlyphNames::None,
+        };
+
+        Ok(Self {
+            italic_angle,
+            underline_position,
+            underline_thickness,
+            is_fixed_pitch,
+            names,
+        })
+    }
+
+    /// Read the glyph names of a version 2 table. Some fonts' tables have fewer indices or names
+    /// than they should, so they're read up to the end of the table, and the glyphs which miss
+    /// out have no name.
+    fn parse_indexed_names(reader: &mut Reader) -> GlyphNames {
+        let num_glyphs = reader.read_u16().unwrap_or(0);
+        let indices: Vec<u16> = (0..num_glyphs)
+            .map_while(|_| reader.read_u16().ok())
+            .collect();
+
+        // The custom names are Pascal strings, packed together up to the end of the table. Only
+        // as many as the largest index refers to are needed.
+        let num_custom = indices
+            .iter()
+            .filter(|&&idx| idx as usize >= MAC_GLYPH_NAMES.len())
+            .map(|&idx| idx as usize - MAC_GLYPH_NAMES.len() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut custom = Vec::with_capacity(num_custom);
+        while custom.len() < num_custom {
+            let name = match reader
+                .read_u8()
+                .and_then(|len| reader.read_bytes(len as usize))
+            {
+                Ok(name) => name,
+                Err(_) => break,
+            };
+            custom.push(String::from_utf8_lossy(name).into_owned());
+        }
+
+        GlyphNames::Indexed { indices, custom }
+    }
+
+    /// The angle of the font's vertical strokes, in degrees counter-clockwise from vertical.
+    /// Italic fonts lean to the right, so have a negative angle.
+    pub fn italic_angle(&self) -> f32 {
+        self.italic_angle.to_bits() as f32 / 65536.0
+    }
+
+    pub fn is_fixed_pitch(&self) -> bool {
+        self.is_fixed_pitch != 0
+    }
+
+    /// The PostScript name of a glyph, such as `Aacute`.
+    pub fn glyph_name(&self, glyph_id: u16) -> Option<&str> {
+        let glyph_id = glyph_id as usize;
+        match &self.names {
+            GlyphNames::None => None,
+            GlyphNames::Standard => MAC_GLYPH_NAMES.get(glyph_id).cloned(),
+            GlyphNames::Indexed { indices, custom } => {
+                let idx = *indices.get(glyph_id)? as usize;
+                match idx.checked_sub(MAC_GLYPH_NAMES.len()) {
+                    None => Some(MAC_GLYPH_NAMES[idx]),
+                    Some(custom_idx) => custom.get(custom_idx).map(String::as_str),
+                }
+            }
+            GlyphNames::Offset(offsets) => {
+                let offset = *offsets.get(glyph_id)? as isize;
+                let idx = glyph_id as isize + offset;
+                if idx < 0 {
+                    return None;
+                }
+                MAC_GLYPH_NAMES.get(idx as usize).cloned()
+            }
+        }
+    }
+
+    /// Find the first glyph with the given name.
+    pub fn glyph_by_name(&self, name: &str) -> Option<u16> {
+        let num_glyphs = match &self.names {
+            GlyphNames::None => 0,
+            GlyphNames::Standard => MAC_GLYPH_NAMES.len(),
+            GlyphNames::Indexed { indices, .. } => indices.len(),
+            GlyphNames::Offset(offsets) => offsets.len(),
+        };
+        (0..num_glyphs as u16).find(|&glyph_id| self.glyph_name(glyph_id) == Some(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::sample_table;
+
+    /// The 32 byte header shared by every version, for a table with the given version.
+    fn header(version: [u8; 4]) -> Vec<u8> {
+        let mut data = version.to_vec();
+        data.extend_from_slice(&[
+            0xFF, 0xF4, 0x80, 0x00, // italic angle -11.5
+            0xFF, 0x9C, 0x00, 0x32, // underline -100, 50
+            0x00, 0x00, 0x00, 0x01, // fixed pitch
+        ]);
+        data.extend_from_slice(&[0x00; 16]);
+        data
+    }
+
+    #[test]
+    fn parse_sample() {
+        let post = PostTable::parse(sample_table(b"post")).unwrap();
+
+        assert_eq!(post.italic_angle(), 0.0);
+        assert_eq!(post.underline_position, -125);
+        assert_eq!(post.underline_thickness, 50);
+        assert!(!post.is_fixed_pitch());
+        assert_eq!(post.glyph_name(0), None);
+    }
+
+    #[test]
+    fn parse_version_1() {
+        let post = PostTable::parse(&header([0x00, 0x01, 0x00, 0x00])).unwrap();
+
+        assert_eq!(post.italic_angle(), -11.5);
+        assert!(post.is_fixed_pitch());
+        assert_eq!(post.glyph_name(36), Some("A"));
+        assert_eq!(post.glyph_name(258), None);
+        assert_eq!(post.glyph_by_name("dcroat"), Some(257));
+    }
+
+    #[test]
+    fn parse_version_2() {
+        let mut data = header([0x00, 0x02, 0x00, 0x00]);
+        data.extend_from_slice(&[
+            0x00, 0x04, // 4 glyphs
+            0x00, 0x00, 0x01, 0x03, 0x00, 0x24, 0x01, 0x02, // indices 0, 259, 36, 258
+            0x04, b'A', b'.', b's', b'c', // 258
+            0x05, b'u', b'n', b'i', b'0', b'E', // 259
+        ]);
+        let post = PostTable::parse(&data).unwrap();
+
+        assert_eq!(post.glyph_name(0), Some(".notdef"));
+        assert_eq!(post.glyph_name(1), Some("uni0E"));
+        assert_eq!(post.glyph_name(2), Some("A"));
+        assert_eq!(post.glyph_name(3), Some("A.sc"));
+        assert_eq!(post.glyph_name(4), None);
+        assert_eq!(post.glyph_by_name("A.sc"), Some(3));
+        assert_eq!(post.glyph_by_name("B"), None);
+    }
+
+    #[test]
+    fn parse_version_2_5() {
+        let mut data = header([0x00, 0x02, 0x50, 0x00]);
+        data.extend_from_slice(&[0x00, 0x03, 0x00, 0x23, 0xFE]);
+        let post = PostTable::parse(&data).unwrap();
+
+        assert_eq!(post.glyph_name(0), Some(".notdef"));
+        assert_eq!(post.glyph_name(1), Some("A"));
+        assert_eq!(post.glyph_name(2), Some(".notdef"));
+    }
+
+    #[test]
+    fn parse_version_3() {
+        let post = PostTable::parse(&header([0x00, 0x03, 0x00, 0x00])).unwrap();
+
+        assert_eq!(post.glyph_name(0), None);
+    }
+
+    #[test]
+    fn parse_truncated_names() {
+        // Three glyphs whose names are cut off partway through the second custom name.
+        let mut data = header([0x00, 0x02, 0x00, 0x00]);
+        data.extend_from_slice(&[
+            0x00, 0x03, 0x01, 0x02, 0x01, 0x03, 0x00, 0x24, // indices 258, 259, 36
+            0x01, b'a', 0x04, b'A', b'.',
+        ]);
+        let post = PostTable::parse(&data).unwrap();
+
+        assert_eq!(post.glyph_name(0), Some("a"));
+        assert_eq!(post.glyph_name(1), None);
+        assert_eq!(post.glyph_name(2), Some("A"));
+    }
+
+    #[test]
+    fn parse_truncated_indices() {
+        // A table which claims 65535 glyphs but only has indices for two.
+        let mut data = header([0x00, 0x02, 0x00, 0x00]);
+        data.extend_from_slice(&[0xFF, 0xFF, 0x00, 0x24, 0x00, 0x25]);
+        let post = PostTable::parse(&data).unwrap();
+
+        assert_eq!(post.glyph_name(1), Some("B"));
+        assert_eq!(post.glyph_name(2), None);
+        assert_eq!(post.glyph_by_name("B"), Some(1));
+
+        let mut data = header([0x00, 0x02, 0x50, 0x00]);
+        data.extend_from_slice(&[0xFF, 0xFF, 0x00]);
+        let post = PostTable::parse(&data).unwrap();
+        assert_eq!(post.glyph_name(0), Some(".notdef"));
+        assert_eq!(post.glyph_name(1), None);
+    }
+
+    #[test]
+    fn parse_truncated_header() {
+        let data = header([0x00, 0x02, 0x00, 0x00]);
+
+        assert_eq!(
+            PostTable::parse(&data[..20]).unwrap_err(),
+            ReadError { offset: 16 }
+        );
+    }
+}
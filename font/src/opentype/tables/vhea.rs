@@ -0,0 +1,70 @@
+use opentype::types::{DataType, ReadError, I16, U16};
+
+const OFFSET_ASCENDER: usize = 4;
+const OFFSET_DESCENDER: usize = 6;
+const OFFSET_LINE_GAP: usize = 8;
+const OFFSET_ADVANCE_HEIGHT_MAX: usize = 10;
+const OFFSET_NUM_V_METRICS: usize = 34;
+
+/// The vertical header, holding metrics which apply to the whole font when laid out vertically.
+///
+/// Version 1.0 calls the first three metrics `ascent`, `descent` and `lineGap`, while version 1.1
+/// renames them to `vertTypoAscender` and so on. They're stored in the same place either way.
+#[derive(Debug)]
+pub struct VheaTable {
+    /// The distance from the vertical centerline to the right edge of the design space.
+    pub ascender: i16,
+    /// The distance from the vertical centerline to the left edge of the design space, usually
+    /// negative.
+    pub descender: i16,
+    /// The extra space to leave between columns.
+    pub line_gap: i16,
+    pub advance_height_max: u16,
+    /// The number of glyphs with their own advance height in the vmtx table.
+    pub num_v_metrics: u16,
+}
+
+impl VheaTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ReadError> {
+        Ok(Self {
+            ascender: I16::extract(table_data, OFFSET_ASCENDER)?,
+            descender: I16::extract(table_data, OFFSET_DESCENDER)?,
+            line_gap: I16::extract(table_data, OFFSET_LINE_GAP)?,
+            advance_height_max: U16::extract(table_data, OFFSET_ADVANCE_HEIGHT_MAX)?,
+            num_v_metrics: U16::extract(table_data, OFFSET_NUM_V_METRICS)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_1_1() {
+        let table = [
+            0x00u8, 0x01, 0x10, 0x00, // version 1.1
+            0x01, 0xF4, 0xFE, 0x0C, 0x00, 0x00, // ascender, descender, line gap
+            0x04, 0x00, 0x00, 0x32, 0x00, 0x14, 0x03, 0xB6, // max advance, bearings, extent
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, // caret
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // reserved
+            0x00, 0x00, 0x00, 0x05, // metric data format, number of metrics
+        ];
+        let vhea = VheaTable::parse(&table).unwrap();
+
+        assert_eq!(vhea.ascender, 500);
+        assert_eq!(vhea.descender, -500);
+        assert_eq!(vhea.advance_height_max, 1024);
+        assert_eq!(vhea.num_v_metrics, 5);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = [0x00u8, 0x01, 0x00, 0x00, 0x01];
+
+        assert_eq!(
+            VheaTable::parse(&table).unwrap_err(),
+            ReadError { offset: 4 }
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::hmtx::HmtxTable;
+use opentype::types::ReadError;
+
+/// The advance height and top side bearing of every glyph.
+///
+/// This has exactly the layout of the hmtx table, turned on its side, so it's parsed as one.
+#[derive(Debug)]
+pub struct VmtxTable {
+    metrics: HmtxTable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VerticalMetric {
+    pub advance_height: u16,
+    /// The distance from the vertical origin down to the top of the glyph's bounding box.
+    pub top_side_bearing: i16,
+}
+
+impl VmtxTable {
+    pub fn parse(
+        table_data: &[u8],
+        num_v_metrics: u16,
+        num_glyphs: u16,
+    ) -> Result<Self, ReadError> {
+        Ok(Self {
+            metrics: HmtxTable::parse(table_data, num_v_metrics, num_glyphs)?,
+        })
+    }
+
+    /// The metrics for a glyph, or `None` if the glyph is beyond the end of the table.
+    pub fn metric(&self, glyph_id: u16) -> Option<VerticalMetric> {
+        self.metrics.metric(glyph_id).map(|metric| VerticalMetric {
+            advance_height: metric.advance_width,
+            top_side_bearing: metric.left_side_bearing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_trailing_bearings() {
+        let table = [
+            0x04u8, 0x00, 0x00, 0x64, // 1024, 100
+            0x00, 0x32, // bearing 50
+        ];
+        let vmtx = VmtxTable::parse(&table, 1, 2).unwrap();
+
+        assert_eq!(
+            vmtx.metric(1),
+            Some(VerticalMetric {
+                advance_height: 1024,
+                top_side_bearing: 50
+            })
+        );
+    }
+}
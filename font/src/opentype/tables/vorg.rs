@@ -0,0 +1,97 @@
+//! The vertical origin table gives the y coordinate of each glyph's vertical origin, for fonts
+//! with CFF outlines where it can't be worked out from the glyph's bounding box.
+
+use opentype::types::{ReadError, Reader};
+
+#[derive(Debug)]
+pub struct VorgTable {
+    default_vert_origin_y: i16,
+    /// Glyphs whose origin differs from the default, sorted by glyph index.
+    vert_origin_y_metrics: Vec<(u16, i16)>,
+}
+
+impl VorgTable {
+    pub fn parse(table_data: &[u8]) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(table_data);
+        let major_version = reader.read_u16()?;
+        let _minor_version = reader.read_u16()?;
+        if major_version != 1 {
+            return Err(ParseError::UnknownVersion);
+        }
+
+        let default_vert_origin_y = reader.read_i16()?;
+        let num_metrics = reader.read_u16()?;
+        let mut vert_origin_y_metrics = Vec::with_capacity(num_metrics as usize);
+        for _ in 0..num_metrics {
+            vert_origin_y_metrics.push((reader.read_u16()?, reader.read_i16()?));
+        }
+
+        Ok(Self {
+            default_vert_origin_y,
+            vert_origin_y_metrics,
+        })
+    }
+
+    /// The y coordinate of a glyph's vertical origin, in font units.
+    pub fn vert_origin_y(&self, glyph_id: u16) -> i16 {
+        self.vert_origin_y_metrics
+            .binary_search_by_key(&glyph_id, |&(glyph, _)| glyph)
+            .map(|idx| self.vert_origin_y_metrics[idx].1)
+            .unwrap_or(self.default_vert_origin_y)
+    }
+}
+
+/// Errors which result from unexpected data.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// The table's major version isn't 1.
+    UnknownVersion,
+    /// The entry at the given offset runs past the end of the table.
+    Truncated(usize),
+}
+
+impl From<ReadError> for ParseError {
+    fn from(err: ReadError) -> Self {
+        ParseError::Truncated(err.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_overrides() {
+        let table = [
+            0x00u8, 0x01, 0x00, 0x00, 0x03, 0x70, 0x00,
+            0x02, // version, default 880, 2 metrics
+            0x00, 0x05, 0x03, 0x84, // glyph 5: 900
+            0x00, 0x09, 0xFF, 0x9C, // glyph 9: -100
+        ];
+        let vorg = VorgTable::parse(&table).unwrap();
+
+        assert_eq!(vorg.vert_origin_y(5), 900);
+        assert_eq!(vorg.vert_origin_y(9), -100);
+        assert_eq!(vorg.vert_origin_y(6), 880);
+    }
+
+    #[test]
+    fn parse_unknown_version() {
+        let table = [0x00u8, 0x02, 0x00, 0x00, 0x03, 0x70, 0x00, 0x00];
+
+        assert_eq!(
+            VorgTable::parse(&table).unwrap_err(),
+            ParseError::UnknownVersion
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let table = [0x00u8, 0x01, 0x00, 0x00, 0x03, 0x70, 0x00, 0x01, 0x00, 0x05];
+
+        assert_eq!(
+            VorgTable::parse(&table).unwrap_err(),
+            ParseError::Truncated(10)
+        );
+    }
+}
@@ -1,64 +1,127 @@
 use byteorder::{BigEndian, ByteOrder};
 use fixed::{frac, FixedI32};
 
+/// An attempt to read beyond the end of the available data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReadError {
+    /// The offset, relative to the start of the data, at which the read began.
+    pub offset: usize,
+}
+
+impl ReadError {
+    /// Shift the reported offset by `base`, for when the data was a slice of some larger table.
+    pub fn offset_by(self, base: usize) -> Self {
+        Self {
+            offset: base + self.offset,
+        }
+    }
+}
+
 pub trait DataType<T> {
-    fn extract(data: &[u8], offset: usize) -> T;
+    /// The number of bytes occupied by the type.
+    const SIZE: usize;
+
+    fn extract(data: &[u8], offset: usize) -> Result<T, ReadError>;
+}
+
+/// Returns the `len` bytes of `data` which start at `offset`, if they are all present.
+pub fn slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8], ReadError> {
+    offset
+        .checked_add(len)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(ReadError { offset })
 }
 
 pub enum U8 {}
 
 impl DataType<u8> for U8 {
-    fn extract(data: &[u8], offset: usize) -> u8 {
-        data[offset]
+    const SIZE: usize = 1;
+
+    fn extract(data: &[u8], offset: usize) -> Result<u8, ReadError> {
+        data.get(offset).cloned().ok_or(ReadError { offset })
     }
 }
 
 pub enum U16 {}
 
 impl DataType<u16> for U16 {
-    fn extract(data: &[u8], offset: usize) -> u16 {
-        BigEndian::read_u16(&data[offset..offset + 2])
+    const SIZE: usize = 2;
+
+    fn extract(data: &[u8], offset: usize) -> Result<u16, ReadError> {
+        slice(data, offset, Self::SIZE).map(BigEndian::read_u16)
     }
 }
 
 pub enum U32 {}
 
 impl DataType<u32> for U32 {
-    fn extract(data: &[u8], offset: usize) -> u32 {
-        BigEndian::read_u32(&data[offset..offset + 4])
+    const SIZE: usize = 4;
+
+    fn extract(data: &[u8], offset: usize) -> Result<u32, ReadError> {
+        slice(data, offset, Self::SIZE).map(BigEndian::read_u32)
+    }
+}
+
+/// A 24 bit unsigned integer, as used for Unicode codepoints.
+pub enum U24 {}
+
+impl DataType<u32> for U24 {
+    const SIZE: usize = 3;
+
+    fn extract(data: &[u8], offset: usize) -> Result<u32, ReadError> {
+        slice(data, offset, Self::SIZE).map(|bytes| BigEndian::read_uint(bytes, 3) as u32)
     }
 }
 
 pub type I16 = i16;
 
 impl DataType<I16> for I16 {
-    fn extract(data: &[u8], offset: usize) -> I16 {
-        BigEndian::read_i16(&data[offset..offset + 2])
+    const SIZE: usize = 2;
+
+    fn extract(data: &[u8], offset: usize) -> Result<I16, ReadError> {
+        slice(data, offset, Self::SIZE).map(BigEndian::read_i16)
     }
 }
 
 pub enum I32 {}
 
 impl DataType<i32> for I32 {
-    fn extract(data: &[u8], offset: usize) -> i32 {
-        BigEndian::read_i32(&data[offset..offset + 4])
+    const SIZE: usize = 4;
+
+    fn extract(data: &[u8], offset: usize) -> Result<i32, ReadError> {
+        slice(data, offset, Self::SIZE).map(BigEndian::read_i32)
     }
 }
 
 pub type Datetime = i64;
 
 impl DataType<Datetime> for Datetime {
-    fn extract(data: &[u8], offset: usize) -> Datetime {
-        BigEndian::read_i64(&data[offset..offset + 8])
+    const SIZE: usize = 8;
+
+    fn extract(data: &[u8], offset: usize) -> Result<Datetime, ReadError> {
+        slice(data, offset, Self::SIZE).map(BigEndian::read_i64)
     }
 }
 
 pub type Fixed = FixedI32<frac::U16>;
 
 impl DataType<Fixed> for Fixed {
-    fn extract(data: &[u8], offset: usize) -> Fixed {
-        let i = I32::extract(data, offset);
-        FixedI32::<frac::U16>::from_bits(i)
+    const SIZE: usize = 4;
+
+    fn extract(data: &[u8], offset: usize) -> Result<Fixed, ReadError> {
+        I32::extract(data, offset).map(FixedI32::<frac::U16>::from_bits)
+    }
+}
+
+/// A signed fixed point number with 2 integer bits and 14 fractional bits, as used for scales in
+/// the range [-2, 2).
+pub enum F2Dot14 {}
+
+impl DataType<f32> for F2Dot14 {
+    const SIZE: usize = 2;
+
+    fn extract(data: &[u8], offset: usize) -> Result<f32, ReadError> {
+        I16::extract(data, offset).map(|value| f32::from(value) / 16384.0)
     }
 }
 
@@ -67,15 +130,198 @@ pub type Offset = usize;
 pub enum Offset16 {}
 
 impl DataType<Offset> for Offset16 {
-    fn extract(data: &[u8], offset: usize) -> Offset {
-        U16::extract(data, offset) as usize
+    const SIZE: usize = 2;
+
+    fn extract(data: &[u8], offset: usize) -> Result<Offset, ReadError> {
+        U16::extract(data, offset).map(|value| value as usize)
     }
 }
 
 pub enum Offset32 {}
 
 impl DataType<Offset> for Offset32 {
-    fn extract(data: &[u8], offset: usize) -> Offset {
-        U32::extract(data, offset) as usize
+    const SIZE: usize = 4;
+
+    fn extract(data: &[u8], offset: usize) -> Result<Offset, ReadError> {
+        U32::extract(data, offset).map(|value| value as usize)
+    }
+}
+
+/// A cursor over big-endian table data where every read is bounds-checked.
+///
+/// A failed read leaves the cursor where it was.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::at(data, 0)
+    }
+
+    /// Create a reader positioned `offset` bytes into `data`.
+    pub fn at(data: &'a [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+
+    /// The current position, relative to the start of the data.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn seek(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<(), ReadError> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    pub fn read<T, D: DataType<T>>(&mut self) -> Result<T, ReadError> {
+        let value = D::extract(self.data, self.offset)?;
+        self.offset += D::SIZE;
+        Ok(value)
+    }
+
+    /// Read `count` consecutive values of the same type.
+    pub fn read_array<T, D: DataType<T>>(&mut self, count: usize) -> Result<Vec<T>, ReadError> {
+        // Don't trust the count for allocation; it may be far larger than the data.
+        let available = self.data.len().saturating_sub(self.offset) / D::SIZE;
+        let mut values = Vec::with_capacity(count.min(available));
+        for _ in 0..count {
+            values.push(self.read::<T, D>()?);
+        }
+        Ok(values)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, ReadError> {
+        self.read::<u8, U8>()
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, ReadError> {
+        self.read::<u16, U16>()
+    }
+
+    pub fn read_u24(&mut self) -> Result<u32, ReadError> {
+        self.read::<u32, U24>()
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, ReadError> {
+        self.read::<u32, U32>()
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16, ReadError> {
+        self.read::<i16, I16>()
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, ReadError> {
+        self.read::<i32, I32>()
+    }
+
+    pub fn read_fixed(&mut self) -> Result<Fixed, ReadError> {
+        self.read::<Fixed, Fixed>()
+    }
+
+    pub fn read_f2dot14(&mut self) -> Result<f32, ReadError> {
+        self.read::<f32, F2Dot14>()
+    }
+
+    pub fn read_offset16(&mut self) -> Result<Offset, ReadError> {
+        self.read::<Offset, Offset16>()
+    }
+
+    pub fn read_offset32(&mut self) -> Result<Offset, ReadError> {
+        self.read::<Offset, Offset32>()
+    }
+
+    /// Read a four byte tag, such as a table or feature tag.
+    pub fn read_tag(&mut self) -> Result<[char; 4], ReadError> {
+        let bytes = self.read_bytes(4)?;
+        Ok([
+            bytes[0] as char,
+            bytes[1] as char,
+            bytes[2] as char,
+            bytes[3] as char,
+        ])
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], ReadError> {
+        let bytes = slice(self.data, self.offset, len)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_in_bounds() {
+        let data = [0x12u8, 0x34, 0x56, 0x78];
+
+        assert_eq!(U16::extract(&data, 2), Ok(0x5678));
+        assert_eq!(U32::extract(&data, 0), Ok(0x12345678));
+        assert_eq!(U24::extract(&data, 1), Ok(0x345678));
+    }
+
+    #[test]
+    fn extract_f2dot14() {
+        let data = [0x40u8, 0x00, 0xC0, 0x00, 0x70, 0x00, 0x00, 0x01];
+
+        assert_eq!(F2Dot14::extract(&data, 0), Ok(1.0));
+        assert_eq!(F2Dot14::extract(&data, 2), Ok(-1.0));
+        assert_eq!(F2Dot14::extract(&data, 4), Ok(1.75));
+        assert_eq!(F2Dot14::extract(&data, 6), Ok(1.0 / 16384.0));
+    }
+
+    #[test]
+    fn extract_out_of_bounds() {
+        let data = [0x12u8, 0x34, 0x56];
+
+        assert_eq!(U8::extract(&data, 3), Err(ReadError { offset: 3 }));
+        assert_eq!(U16::extract(&data, 2), Err(ReadError { offset: 2 }));
+        assert_eq!(U32::extract(&data, 0), Err(ReadError { offset: 0 }));
+        assert_eq!(
+            Offset32::extract(&data, usize::max_value()),
+            Err(ReadError {
+                offset: usize::max_value()
+            })
+        );
+    }
+
+    #[test]
+    fn reader_advances() {
+        let data = [0x00u8, 0x01, 0xFF, 0xFE, 0x6B, 0x65, 0x72, 0x6E];
+        let mut reader = Reader::new(&data);
+
+        assert_eq!(reader.read_u16(), Ok(1));
+        assert_eq!(reader.read_i16(), Ok(-2));
+        assert_eq!(reader.read_tag(), Ok(['k', 'e', 'r', 'n']));
+        assert_eq!(reader.offset(), 8);
+    }
+
+    #[test]
+    fn reader_read_array() {
+        let data = [0x00u8, 0x01, 0xFF, 0xFE, 0x00];
+        let mut reader = Reader::new(&data);
+
+        assert_eq!(reader.read_array::<i16, I16>(2), Ok(vec![1, -2]));
+        assert_eq!(
+            Reader::new(&data).read_array::<u16, U16>(0xFFFF),
+            Err(ReadError { offset: 4 })
+        );
+    }
+
+    #[test]
+    fn reader_failed_read_keeps_position() {
+        let data = [0x00u8, 0x01, 0x02];
+        let mut reader = Reader::at(&data, 1);
+
+        assert_eq!(reader.read_u32(), Err(ReadError { offset: 1 }));
+        assert_eq!(reader.offset(), 1);
+        assert_eq!(reader.read_u16(), Ok(0x0102));
     }
 }
@@ -0,0 +1,389 @@
+//! A scanline rasterizer which turns glyph outlines into anti-aliased coverage bitmaps.
+//!
+//! Curves are flattened into line segments in pixel space. Each pixel row is then sampled along
+//! several horizontal scanlines; along each scanline the spans with a nonzero winding number are
+//! filled with exact horizontal coverage, so edges are smooth in both directions.
+
+use opentype::tables::glyf::{Outline, OutlineBuilder};
+use std::cmp::Ordering;
+
+/// The number of scanlines sampled within each row of pixels.
+const SAMPLES_PER_ROW: usize = 16;
+/// Curves are split into lines until they stray no further than this from the true curve, in
+/// pixels.
+const FLATTEN_TOLERANCE: f32 = 0.02;
+/// Guards against malformed outlines splitting a single curve into a huge number of lines.
+const MAX_CURVE_SEGMENTS: usize = 256;
+/// Bitmaps are clipped to this many pixels in each direction, so that malformed outlines can't
+/// ask for an enormous allocation.
+pub const MAX_DIMENSION: usize = 4096;
+
+/// An 8-bit coverage mask, stored row by row from the top.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    /// The distance, in pixels, from the glyph origin right to the bitmap's left edge.
+    pub left: i32,
+    /// The distance, in pixels, from the baseline up to the bitmap's top edge.
+    pub top: i32,
+    /// How much of each pixel the glyph covers, from 0 (none) to 255 (all).
+    pub coverage: Vec<u8>,
+}
+
+impl Bitmap {
+    fn empty() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            left: 0,
+            top: 0,
+            coverage: vec![],
+        }
+    }
+
+    /// The coverage of the pixel in column `x` and row `y`, counting rows from the top.
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        if x >= self.width {
+            return None;
+        }
+        self.coverage.get(y * self.width + x).cloned()
+    }
+}
+
+/// Render an outline measured in font units at `pixel_size` pixels per em.
+///
+/// `offset` moves the glyph right and up by a fraction of a pixel, so that text can be positioned
+/// more precisely than the pixel grid allows.
+pub fn rasterize(
+    outline: &Outline,
+    units_per_em: u16,
+    pixel_size: f32,
+    offset: (f32, f32),
+) -> Bitmap {
+    let units_per_em = f32::from(units_per_em.max(1));
+    let (min_x, min_y, max_x, max_y) = match outline.bounds() {
+        Some(bounds) => bounds,
+        None => return Bitmap::empty(),
+    };
+
+    // Scaling before dividing keeps whole pixels exact, so edges on pixel boundaries don't spill
+    // into an extra row or column.
+    let left = (min_x * pixel_size / units_per_em + offset.0).floor();
+    let right = (max_x * pixel_size / units_per_em + offset.0).ceil();
+    let bottom = (min_y * pixel_size / units_per_em + offset.1).floor();
+    let top = (max_y * pixel_size / units_per_em + offset.1).ceil();
+    let width = ((right - left) as usize).min(MAX_DIMENSION);
+    let height = ((top - bottom) as usize).min(MAX_DIMENSION);
+
+    // Bitmap rows run down from the top, while font units run up from the baseline.
+    let mut rasterizer = Rasterizer {
+        pixel_size,
+        units_per_em,
+        dx: offset.0 - left,
+        dy: top - offset.1,
+        lines: vec![],
+        start: (0.0, 0.0),
+        current: (0.0, 0.0),
+    };
+    outline.replay(&mut rasterizer);
+
+    Bitmap {
+        width,
+        height,
+        left: left as i32,
+        top: top as i32,
+        coverage: rasterizer.fill(width, height),
+    }
+}
+
+/// A line segment in pixel space.
+#[derive(Debug)]
+struct Line {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+}
+
+struct Rasterizer {
+    pixel_size: f32,
+    units_per_em: f32,
+    dx: f32,
+    dy: f32,
+    lines: Vec<Line>,
+    start: (f32, f32),
+    current: (f32, f32),
+}
+
+impl Rasterizer {
+    /// Map a point from font units into pixel space.
+    fn to_pixels(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            x * self.pixel_size / self.units_per_em + self.dx,
+            self.dy - y * self.pixel_size / self.units_per_em,
+        )
+    }
+
+    fn push_line(&mut self, to: (f32, f32)) {
+        let from = self.current;
+        if from != to {
+            self.lines.push(Line {
+                x0: from.0,
+                y0: from.1,
+                x1: to.0,
+                y1: to.1,
+            });
+        }
+        self.current = to;
+    }
+
+    /// Approximate a curve with `num_segments` lines through the points returned by `eval`.
+    fn push_curve<F: Fn(f32) -> (f32, f32)>(&mut self, deviation: f32, eval: F) {
+        let num_segments =
+            ((deviation / FLATTEN_TOLERANCE).sqrt().ceil() as usize).clamp(1, MAX_CURVE_SEGMENTS);
+        for step in 1..=num_segments {
+            self.push_line(eval(step as f32 / num_segments as f32));
+        }
+    }
+
+    fn fill(&self, width: usize, height: usize) -> Vec<u8> {
+        let mut coverage = vec![0.0f32; width * height];
+        let weight = 1.0 / SAMPLES_PER_ROW as f32;
+        let mut crossings: Vec<(f32, i32)> = vec![];
+
+        for (row_idx, row) in coverage.chunks_mut(width.max(1)).enumerate() {
+            for sample in 0..SAMPLES_PER_ROW {
+                let y = row_idx as f32 + (sample as f32 + 0.5) * weight;
+
+                // Each line crossing the scanline adds to the winding number: +1 going down and
+                // -1 going up. Horizontal lines never cross.
+                crossings.clear();
+                for line in &self.lines {
+                    let (top, bottom, direction) = if line.y0 < line.y1 {
+                        (line.y0, line.y1, 1)
+                    } else {
+                        (line.y1, line.y0, -1)
+                    };
+                    if y < top || y >= bottom {
+                        continue;
+                    }
+                    let t = (y - line.y0) / (line.y1 - line.y0);
+                    crossings.push((line.x0 + t * (line.x1 - line.x0), direction));
+                }
+                crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+                let mut winding = 0;
+                let mut span_start = 0.0;
+                for &(x, direction) in &crossings {
+                    let was_inside = winding != 0;
+                    winding += direction;
+                    match (was_inside, winding != 0) {
+                        (false, true) => span_start = x,
+                        (true, false) => add_span(row, span_start, x, weight),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        coverage
+            .iter()
+            .map(|value| (value.min(1.0) * 255.0).round() as u8)
+            .collect()
+    }
+}
+
+/// Cover the part of a row between `x0` and `x1`, including fractions of the pixels at each end.
+fn add_span(row: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let width = row.len() as f32;
+    let (x0, x1) = (x0.max(0.0).min(width), x1.max(0.0).min(width));
+    if x1 <= x0 {
+        return;
+    }
+
+    let first = x0.floor() as usize;
+    let last = x1.floor() as usize;
+    if first == last {
+        row[first] += (x1 - x0) * weight;
+        return;
+    }
+
+    row[first] += (first as f32 + 1.0 - x0) * weight;
+    for value in &mut row[first + 1..last] {
+        *value += weight;
+    }
+    if let Some(value) = row.get_mut(last) {
+        *value += (x1 - last as f32) * weight;
+    }
+}
+
+impl OutlineBuilder for Rasterizer {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let point = self.to_pixels(x, y);
+        self.start = point;
+        self.current = point;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let point = self.to_pixels(x, y);
+        self.push_line(point);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let p0 = self.current;
+        let p1 = self.to_pixels(x1, y1);
+        let p2 = self.to_pixels(x, y);
+        // A quadratic curve strays from the line between its ends by at most a quarter of this.
+        let deviation = distance(p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1) / 4.0;
+        self.push_curve(deviation, |t| {
+            let u = 1.0 - t;
+            (
+                u * u * p0.0 + 2.0 * u * t * p1.0 + t * t * p2.0,
+                u * u * p0.1 + 2.0 * u * t * p1.1 + t * t * p2.1,
+            )
+        });
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.current;
+        let p1 = self.to_pixels(x1, y1);
+        let p2 = self.to_pixels(x2, y2);
+        let p3 = self.to_pixels(x, y);
+        let deviation = distance(p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1)
+            .max(distance(p1.0 - 2.0 * p2.0 + p3.0, p1.1 - 2.0 * p2.1 + p3.1))
+            * 0.75;
+        self.push_curve(deviation, |t| {
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            (
+                a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+                a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+            )
+        });
+    }
+
+    fn close(&mut self) {
+        let start = self.start;
+        self.push_line(start);
+    }
+}
+
+fn distance(dx: f32, dy: f32) -> f32 {
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::glyf::{Contour, Point};
+
+    fn rectangle(x0: f32, y0: f32, x1: f32, y1: f32, clockwise: bool) -> Contour {
+        let mut corners = vec![(x0, y0), (x0, y1), (x1, y1), (x1, y0)];
+        if !clockwise {
+            corners.reverse();
+        }
+        Contour {
+            points: corners
+                .into_iter()
+                .map(|(x, y)| Point {
+                    x,
+                    y,
+                    on_curve: true,
+                })
+                .collect(),
+        }
+    }
+
+    fn rows(bitmap: &Bitmap) -> Vec<Vec<u8>> {
+        bitmap
+            .coverage
+            .chunks(bitmap.width)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn fill_aligned_square() {
+        // At 4 pixels per em with 1000 units per em, each pixel is 250 units.
+        let outline = Outline {
+            contours: vec![rectangle(0.0, 0.0, 500.0, 500.0, true)],
+        };
+        let bitmap = rasterize(&outline, 1000, 4.0, (0.0, 0.0));
+
+        assert_eq!((bitmap.width, bitmap.height), (2, 2));
+        assert_eq!((bitmap.left, bitmap.top), (0, 2));
+        assert_eq!(bitmap.coverage, vec![255; 4]);
+    }
+
+    #[test]
+    fn fill_subpixel_offset() {
+        let outline = Outline {
+            contours: vec![rectangle(0.0, 0.0, 500.0, 250.0, true)],
+        };
+        let bitmap = rasterize(&outline, 1000, 4.0, (0.5, 0.0));
+
+        assert_eq!(bitmap.left, 0);
+        assert_eq!(rows(&bitmap), vec![vec![128, 255, 128]]);
+    }
+
+    #[test]
+    fn fill_nonzero_winding() {
+        // Overlapping contours in the same direction are filled once, while a contour in the
+        // opposite direction cuts a hole.
+        let outline = Outline {
+            contours: vec![
+                rectangle(0.0, 0.0, 750.0, 250.0, true),
+                rectangle(250.0, 0.0, 750.0, 250.0, true),
+                rectangle(0.0, 250.0, 750.0, 750.0, true),
+                rectangle(250.0, 250.0, 500.0, 500.0, false),
+            ],
+        };
+        let bitmap = rasterize(&outline, 1000, 4.0, (0.0, 0.0));
+
+        assert_eq!(
+            rows(&bitmap),
+            vec![vec![255, 255, 255], vec![255, 0, 255], vec![255, 255, 255]]
+        );
+    }
+
+    #[test]
+    fn fill_curve() {
+        // A square of control points, with no on-curve points, draws a rounded shape touching the
+        // middle of each side.
+        let circle = Contour {
+            points: vec![(0.0, 0.0), (1000.0, 0.0), (1000.0, 1000.0), (0.0, 1000.0)]
+                .into_iter()
+                .map(|(x, y)| Point {
+                    x,
+                    y,
+                    on_curve: false,
+                })
+                .collect(),
+        };
+        let bitmap = rasterize(
+            &Outline {
+                contours: vec![circle],
+            },
+            1000,
+            10.0,
+            (0.0, 0.0),
+        );
+
+        assert_eq!((bitmap.width, bitmap.height), (10, 10));
+        assert_eq!(bitmap.get(5, 5), Some(255));
+        assert!(bitmap.get(0, 0).unwrap() < 20);
+        // Quadratic curves only approximate a circle. Each one bulges out from the diamond by
+        // two thirds of the triangle under its control point, for an area of 83.3 pixels.
+        let area: f32 = bitmap.coverage.iter().map(|&c| f32::from(c) / 255.0).sum();
+        assert!((area - 83.3).abs() < 0.4, "area was {}", area);
+    }
+
+    #[test]
+    fn fill_empty_outline() {
+        let bitmap = rasterize(&Outline { contours: vec![] }, 1000, 16.0, (0.0, 0.0));
+
+        assert_eq!((bitmap.width, bitmap.height), (0, 0));
+        assert!(bitmap.coverage.is_empty());
+    }
+}
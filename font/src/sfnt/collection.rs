@@ -0,0 +1,148 @@
+//! The header of a font collection (`.ttc` or `.otc` file), which lists where each font's table
+//! directory starts. Fonts in a collection can point at the same table data.
+//!
+//! [https://docs.microsoft.com/en-us/typography/opentype/spec/otff#font-collections](https://docs.microsoft.com/en-us/typography/opentype/spec/otff#font-collections)
+
+use font::{ErrorReason, FontParseErr};
+use opentype::types::{Offset32, Reader};
+
+use super::truncated_header;
+
+const COLLECTION_TAG: [char; 4] = ['t', 't', 'c', 'f'];
+const MAJOR_VERSION_OFFSET: usize = 4;
+
+/// A parsed `ttcf` header.
+#[derive(Debug, PartialEq)]
+pub struct CollectionHeader {
+    pub major_version: u16,
+    pub minor_version: u16,
+    /// The offset of each font's table directory from the start of the file.
+    pub offsets: Vec<usize>,
+    /// The collection's digital signature, which only version 2 headers can have.
+    pub dsig: Option<DigitalSignature>,
+}
+
+/// Where a collection's `DSIG` table is, from the start of the file.
+#[derive(Debug, PartialEq)]
+pub struct DigitalSignature {
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl CollectionHeader {
+    pub fn deserialize(content: &[u8]) -> Result<Self, FontParseErr> {
+        let mut reader = Reader::new(content);
+        if reader.read_tag().map_err(truncated_header)? != COLLECTION_TAG {
+            return Err(FontParseErr::UnrecognizedFormatError);
+        }
+        let major_version = reader.read_u16().map_err(truncated_header)?;
+        let minor_version = reader.read_u16().map_err(truncated_header)?;
+        if major_version != 1 && major_version != 2 {
+            return Err(FontParseErr::HeaderError {
+                offset: MAJOR_VERSION_OFFSET,
+                reason: ErrorReason::BadVersion,
+            });
+        }
+        let num_fonts = reader.read_u32().map_err(truncated_header)? as usize;
+        let offsets = reader
+            .read_array::<usize, Offset32>(num_fonts)
+            .map_err(truncated_header)?;
+
+        let mut dsig = None;
+        if major_version == 2 {
+            let tag = reader.read_u32().map_err(truncated_header)?;
+            let length = reader.read_u32().map_err(truncated_header)? as usize;
+            let offset = reader.read_u32().map_err(truncated_header)? as usize;
+            // A tag of zero means the collection isn't signed.
+            if tag != 0 {
+                dsig = Some(DigitalSignature { offset, length });
+            }
+        }
+
+        Ok(Self {
+            major_version,
+            minor_version,
+            offsets,
+            dsig,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION_1: [u8; 20] = [
+        0x74, 0x74, 0x63, 0x66, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x14, 0x00, 0x00, 0x01, 0x20,
+    ];
+
+    fn version_2(dsig_tag: &[u8; 4]) -> Vec<u8> {
+        let mut content = VERSION_1.to_vec();
+        content[5] = 0x02;
+        content.extend_from_slice(dsig_tag);
+        content.extend_from_slice(&[0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x08, 0x00]);
+        content
+    }
+
+    #[test]
+    fn deserialize_version_1() {
+        let header = CollectionHeader::deserialize(&VERSION_1).unwrap();
+        assert_eq!(header.major_version, 1);
+        assert_eq!(header.minor_version, 0);
+        assert_eq!(header.offsets, vec![0x14, 0x120]);
+        assert_eq!(header.dsig, None);
+    }
+
+    #[test]
+    fn deserialize_version_2() {
+        let header = CollectionHeader::deserialize(&version_2(b"DSIG")).unwrap();
+        assert_eq!(header.major_version, 2);
+        assert_eq!(header.offsets, vec![0x14, 0x120]);
+        assert_eq!(
+            header.dsig,
+            Some(DigitalSignature {
+                offset: 0x800,
+                length: 0x1000,
+            })
+        );
+
+        let unsigned = CollectionHeader::deserialize(&version_2(&[0; 4])).unwrap();
+        assert_eq!(unsigned.dsig, None);
+    }
+
+    #[test]
+    fn deserialize_bad_version() {
+        let mut content = VERSION_1;
+        content[5] = 0x03;
+        assert_eq!(
+            CollectionHeader::deserialize(&content).unwrap_err(),
+            FontParseErr::HeaderError {
+                offset: 4,
+                reason: ErrorReason::BadVersion,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_truncated() {
+        assert_eq!(
+            CollectionHeader::deserialize(&VERSION_1[..18]).unwrap_err(),
+            FontParseErr::HeaderError {
+                offset: 16,
+                reason: ErrorReason::Truncated,
+            }
+        );
+        assert_eq!(
+            CollectionHeader::deserialize(&version_2(b"DSIG")[..24]).unwrap_err(),
+            FontParseErr::HeaderError {
+                offset: 24,
+                reason: ErrorReason::Truncated,
+            }
+        );
+        assert_eq!(
+            CollectionHeader::deserialize(&[0x00, 0x01, 0x00, 0x00]).unwrap_err(),
+            FontParseErr::UnrecognizedFormatError
+        );
+    }
+}
@@ -26,15 +26,20 @@
 //!     let mut data: Vec<u8> = vec![];
 //!     f.read_to_end(&mut data).unwrap();
 //!
-//!     let sfnt = SfntFile::deserialize(&data);
+//!     let sfnt = SfntFile::deserialize(&data).expect("failed to parse sfnt.");
 //!     for record in &sfnt.tables {
 //!         println!("{:?}", record);
 //!     }
 //! }
 //! ```
-use byteorder::{BigEndian, ByteOrder};
+mod collection;
+
 use std::fmt;
 
+pub use self::collection::{CollectionHeader, DigitalSignature};
+use font::{ErrorReason, FontParseErr};
+use opentype::types::{slice, DataType, Offset32, ReadError, Reader, U16, U32};
+
 const NUM_TABLES_OFFSET: usize = 4;
 const SEARCH_RANGE_OFFSET: usize = 6;
 const ENTRY_SELECTION_OFFSET: usize = 8;
@@ -42,7 +47,6 @@ const RANGE_SHIFT_OFFSET: usize = 10;
 const TABLE_RECORDS_OFFSET: usize = 12;
 const TABLE_RECORD_LENGTH: usize = 16;
 const TABLE_TAG_OFFSET: usize = 0;
-const TABLE_TAG_LENGTH: usize = 4;
 const TABLE_CHECKSUM_OFFSET: usize = 4;
 const TABLE_OFFSET_OFFSET: usize = 8;
 const TABLE_LENGTH_OFFSET: usize = 12;
@@ -58,54 +62,76 @@ pub struct SfntFile<'a> {
 }
 
 impl<'a> SfntFile<'a> {
-    pub fn deserialize(content: &'a [u8]) -> Self {
-        Self {
-            num_tables: Self::parse_num_tables(content),
-            search_range: Self::parse_search_range(content),
-            entry_selector: Self::parse_entry_selector(content),
-            range_shift: Self::parse_range_shift(content),
-            tables: Self::parse_table_records(content),
-        }
+    pub fn deserialize(content: &'a [u8]) -> Result<Self, FontParseErr> {
+        Self::deserialize_at(content, 0)
     }
 
-    fn parse_num_tables(content: &[u8]) -> u16 {
-        BigEndian::read_u16(&content[NUM_TABLES_OFFSET..NUM_TABLES_OFFSET + 2])
+    /// Parse the header and table directory starting `offset` bytes into `content`, as each font
+    /// in a collection has. Table offsets are always from the start of `content`, so fonts in a
+    /// collection can share tables.
+    pub fn deserialize_at(content: &'a [u8], offset: usize) -> Result<Self, FontParseErr> {
+        Ok(Self {
+            num_tables: Self::parse_num_tables(content, offset).map_err(truncated_header)?,
+            search_range: Self::parse_search_range(content, offset).map_err(truncated_header)?,
+            entry_selector: Self::parse_entry_selector(content, offset)
+                .map_err(truncated_header)?,
+            range_shift: Self::parse_range_shift(content, offset).map_err(truncated_header)?,
+            tables: Self::parse_table_records(content, offset)?,
+        })
     }
 
-    fn parse_search_range(content: &[u8]) -> u16 {
-        BigEndian::read_u16(&content[SEARCH_RANGE_OFFSET..SEARCH_RANGE_OFFSET + 2])
+    fn parse_num_tables(content: &[u8], offset: usize) -> Result<u16, ReadError> {
+        U16::extract(content, offset + NUM_TABLES_OFFSET)
     }
 
-    fn parse_entry_selector(content: &[u8]) -> u16 {
-        BigEndian::read_u16(&content[ENTRY_SELECTION_OFFSET..ENTRY_SELECTION_OFFSET + 2])
+    fn parse_search_range(content: &[u8], offset: usize) -> Result<u16, ReadError> {
+        U16::extract(content, offset + SEARCH_RANGE_OFFSET)
     }
 
-    fn parse_range_shift(content: &[u8]) -> u16 {
-        BigEndian::read_u16(&content[RANGE_SHIFT_OFFSET..RANGE_SHIFT_OFFSET + 2])
+    fn parse_entry_selector(content: &[u8], offset: usize) -> Result<u16, ReadError> {
+        U16::extract(content, offset + ENTRY_SELECTION_OFFSET)
     }
 
-    fn parse_table_records(content: &[u8]) -> Vec<Table> {
+    fn parse_range_shift(content: &[u8], offset: usize) -> Result<u16, ReadError> {
+        U16::extract(content, offset + RANGE_SHIFT_OFFSET)
+    }
+
+    fn parse_table_records(content: &[u8], offset: usize) -> Result<Vec<Table>, FontParseErr> {
         let mut records: Vec<Table> = vec![];
-        let num_tables: usize = Self::parse_num_tables(content) as usize;
+        let num_tables: usize =
+            Self::parse_num_tables(content, offset).map_err(truncated_header)? as usize;
         for n in 0..num_tables {
-            records.push(Self::parse_nth_table_record(content, n));
+            records.push(Self::parse_nth_table_record(content, offset, n)?);
         }
-        records
+        Ok(records)
     }
 
-    fn parse_nth_table_record(content: &[u8], n: usize) -> Table {
-        let offset = TABLE_RECORDS_OFFSET + n * TABLE_RECORD_LENGTH;
-        let record_content: &[u8] = &content[offset..offset + TABLE_RECORD_LENGTH];
+    fn parse_nth_table_record(
+        content: &[u8],
+        header_offset: usize,
+        n: usize,
+    ) -> Result<Table, FontParseErr> {
+        let offset = header_offset + TABLE_RECORDS_OFFSET + n * TABLE_RECORD_LENGTH;
+        let record_content: &[u8] =
+            slice(content, offset, TABLE_RECORD_LENGTH).map_err(truncated_header)?;
 
         Table::deserialize(record_content, content)
     }
 }
 
+fn truncated_header(err: ReadError) -> FontParseErr {
+    FontParseErr::HeaderError {
+        offset: err.offset,
+        reason: ErrorReason::Truncated,
+    }
+}
+
 /// An individual table from an `sfnt` file.
 pub struct Table<'a> {
     pub tag: [char; 4],
     checksum: u32,
-    offset: usize,
+    /// The offset of the table from the start of the file.
+    pub offset: usize,
     length: usize,
     pub table_data: &'a [u8],
 }
@@ -121,39 +147,40 @@ impl<'a> fmt::Debug for Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    fn deserialize(record_content: &'a [u8], file_content: &'a [u8]) -> Self {
-        let tag = Self::parse_tag(record_content);
-        let offset = Self::parse_offset(record_content);
-        let length = Self::parse_length(record_content);
-        Self {
-            tag: tag,
-            checksum: Self::parse_checksum(record_content),
-            offset: offset,
-            length: length,
-            table_data: &file_content[offset..offset + length],
-        }
+    fn deserialize(record_content: &'a [u8], file_content: &'a [u8]) -> Result<Self, FontParseErr> {
+        let tag = Self::parse_tag(record_content).map_err(truncated_header)?;
+        let offset = Self::parse_offset(record_content).map_err(truncated_header)?;
+        let length = Self::parse_length(record_content).map_err(truncated_header)?;
+        let table_data =
+            slice(file_content, offset, length).map_err(|_| FontParseErr::TableError {
+                tag,
+                offset,
+                reason: ErrorReason::BadOffset,
+            })?;
+
+        Ok(Self {
+            tag,
+            checksum: Self::parse_checksum(record_content).map_err(truncated_header)?,
+            offset,
+            length,
+            table_data,
+        })
     }
 
-    fn parse_tag(content: &[u8]) -> [char; 4] {
-        let tag = &content[TABLE_TAG_OFFSET..TABLE_TAG_OFFSET + TABLE_TAG_LENGTH];
-        [
-            tag[0] as char,
-            tag[1] as char,
-            tag[2] as char,
-            tag[3] as char,
-        ]
+    fn parse_tag(content: &[u8]) -> Result<[char; 4], ReadError> {
+        Reader::at(content, TABLE_TAG_OFFSET).read_tag()
     }
 
-    fn parse_checksum(content: &[u8]) -> u32 {
-        BigEndian::read_u32(&content[TABLE_CHECKSUM_OFFSET..TABLE_CHECKSUM_OFFSET + 4])
+    fn parse_checksum(content: &[u8]) -> Result<u32, ReadError> {
+        U32::extract(content, TABLE_CHECKSUM_OFFSET)
     }
 
-    fn parse_offset(content: &[u8]) -> usize {
-        BigEndian::read_u32(&content[TABLE_OFFSET_OFFSET..TABLE_OFFSET_OFFSET + 4]) as usize
+    fn parse_offset(content: &[u8]) -> Result<usize, ReadError> {
+        Offset32::extract(content, TABLE_OFFSET_OFFSET)
     }
 
-    fn parse_length(content: &[u8]) -> usize {
-        BigEndian::read_u32(&content[TABLE_LENGTH_OFFSET..TABLE_LENGTH_OFFSET + 4]) as usize
+    fn parse_length(content: &[u8]) -> Result<usize, ReadError> {
+        Offset32::extract(content, TABLE_LENGTH_OFFSET)
     }
 }
 
@@ -167,10 +194,10 @@ mod tests {
         content[..12].clone_from_slice(&[
             0x00u8, 0x01, 0x00, 0x00, 0x00, 0x11, 0x01, 0x00, 0x00, 0x04, 0x00, 0x10,
         ]);
-        assert_eq!(SfntFile::parse_num_tables(&content), 17);
-        assert_eq!(SfntFile::parse_search_range(&content), 256);
-        assert_eq!(SfntFile::parse_entry_selector(&content), 4);
-        assert_eq!(SfntFile::parse_range_shift(&content), 16);
+        assert_eq!(SfntFile::parse_num_tables(&content, 0).unwrap(), 17);
+        assert_eq!(SfntFile::parse_search_range(&content, 0).unwrap(), 256);
+        assert_eq!(SfntFile::parse_entry_selector(&content, 0).unwrap(), 4);
+        assert_eq!(SfntFile::parse_range_shift(&content, 0).unwrap(), 16);
     }
 
     #[test]
@@ -180,7 +207,7 @@ mod tests {
         content[12..12 + 4].clone_from_slice(&[0x6Eu8, 0x61, 0x6D, 0x65]);
         content[12 + 32..12 + 32 + 4].clone_from_slice(&[0x67u8, 0x6C, 0x79, 0x66]);
 
-        let table_records = SfntFile::parse_table_records(&content);
+        let table_records = SfntFile::parse_table_records(&content, 0).unwrap();
 
         assert_eq!(table_records.len(), 18);
         assert_eq!(table_records[0].tag, ['n', 'a', 'm', 'e']);
@@ -193,7 +220,7 @@ mod tests {
         content[12..12 + 4].clone_from_slice(&[0x6Eu8, 0x61, 0x6D, 0x65]);
         content[16..16 + 4].clone_from_slice(&[0xFCu8, 0xFD, 0xFE, 0xFF]);
 
-        let rec0 = SfntFile::parse_nth_table_record(&content, 0);
+        let rec0 = SfntFile::parse_nth_table_record(&content, 0, 0).unwrap();
         assert_eq!(rec0.tag, ['n', 'a', 'm', 'e']);
         assert_eq!(rec0.checksum, 0xFCFDFEFF);
     }
@@ -203,7 +230,7 @@ mod tests {
         let mut content = vec![0x00u8; 47252];
         content[12 + 32..12 + 32 + 4].clone_from_slice(&[0x6Eu8, 0x61, 0x6D, 0x65]);
 
-        let rec2 = SfntFile::parse_nth_table_record(&content, 2);
+        let rec2 = SfntFile::parse_nth_table_record(&content, 0, 2).unwrap();
         assert_eq!(rec2.tag, ['n', 'a', 'm', 'e']);
     }
 
@@ -219,11 +246,45 @@ mod tests {
         file_content[0..16].clone_from_slice(&rec_content);
         file_content[16..20].clone_from_slice(&[0x01u8, 0x02, 0x03, 0x04]);
 
-        let rec0 = Table::deserialize(&rec_content, &file_content);
+        let rec0 = Table::deserialize(&rec_content, &file_content).unwrap();
         assert_eq!(rec0.tag, ['n', 'a', 'm', 'e']);
         assert_eq!(rec0.checksum, 0xFCFDFEFF);
         assert_eq!(rec0.offset, 0x00000010);
         assert_eq!(rec0.length, 0x00000004);
         assert_eq!(rec0.table_data, &[0x01u8, 0x02, 0x03, 0x04]);
     }
+
+    #[test]
+    fn deserialize_truncated_directory() {
+        let content = [
+            0x00u8, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x20, 0x00, 0x01, 0x00, 0x00,
+        ];
+
+        assert_eq!(
+            SfntFile::deserialize(&content).unwrap_err(),
+            FontParseErr::HeaderError {
+                offset: 12,
+                reason: ErrorReason::Truncated,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_table_record_out_of_bounds() {
+        let mut rec_content = vec![0x00u8; 16];
+        rec_content[0..4].clone_from_slice(&[0x6Eu8, 0x61, 0x6D, 0x65]);
+        rec_content[8..12].clone_from_slice(&[0x00u8, 0x00, 0x00, 0x10]);
+        rec_content[12..16].clone_from_slice(&[0xFFu8, 0xFF, 0xFF, 0xFF]);
+
+        let file_content = vec![0x00u8; 20];
+
+        assert_eq!(
+            Table::deserialize(&rec_content, &file_content).unwrap_err(),
+            FontParseErr::TableError {
+                tag: ['n', 'a', 'm', 'e'],
+                offset: 16,
+                reason: ErrorReason::BadOffset,
+            }
+        );
+    }
 }
@@ -0,0 +1,100 @@
+//! How Arabic and Syriac characters join their neighbours, from the Unicode Character Database's
+//! ArabicShaping.txt.
+//!
+//! This covers the letters of the Arabic, Arabic Supplement, Arabic Extended-A and Syriac blocks,
+//! the zero width joiner, and the combining marks and format characters which letters join
+//! across. Characters which aren't listed don't join.
+
+use super::JoiningType::{self, Causing, Dual, Right, Transparent};
+
+/// Ranges of characters, with the first and last of each, sorted for binary search.
+pub const JOINING_TYPES: [(u32, u32, JoiningType); 88] = [
+    (0x0300, 0x036F, Transparent),
+    (0x0610, 0x061A, Transparent),
+    (0x061C, 0x061C, Transparent),
+    (0x0620, 0x0620, Dual),
+    (0x0622, 0x0625, Right),
+    (0x0626, 0x0626, Dual),
+    (0x0627, 0x0627, Right),
+    (0x0628, 0x0628, Dual),
+    (0x0629, 0x0629, Right),
+    (0x062A, 0x062E, Dual),
+    (0x062F, 0x0632, Right),
+    (0x0633, 0x063F, Dual),
+    (0x0640, 0x0640, Causing),
+    (0x0641, 0x0647, Dual),
+    (0x0648, 0x0648, Right),
+    (0x0649, 0x064A, Dual),
+    (0x064B, 0x065F, Transparent),
+    (0x066E, 0x066F, Dual),
+    (0x0670, 0x0670, Transparent),
+    (0x0671, 0x0673, Right),
+    (0x0675, 0x0677, Right),
+    (0x0678, 0x0687, Dual),
+    (0x0688, 0x0699, Right),
+    (0x069A, 0x06BF, Dual),
+    (0x06C0, 0x06C0, Right),
+    (0x06C1, 0x06C2, Dual),
+    (0x06C3, 0x06CB, Right),
+    (0x06CC, 0x06CC, Dual),
+    (0x06CD, 0x06CD, Right),
+    (0x06CE, 0x06CE, Dual),
+    (0x06CF, 0x06CF, Right),
+    (0x06D0, 0x06D1, Dual),
+    (0x06D2, 0x06D3, Right),
+    (0x06D5, 0x06D5, Right),
+    (0x06D6, 0x06DC, Transparent),
+    (0x06DF, 0x06E4, Transparent),
+    (0x06E7, 0x06E8, Transparent),
+    (0x06EA, 0x06ED, Transparent),
+    (0x06EE, 0x06EF, Right),
+    (0x06FA, 0x06FC, Dual),
+    (0x06FF, 0x06FF, Dual),
+    (0x070F, 0x070F, Transparent),
+    (0x0710, 0x0710, Right),
+    (0x0711, 0x0711, Transparent),
+    (0x0712, 0x0714, Dual),
+    (0x0715, 0x0719, Right),
+    (0x071A, 0x071D, Dual),
+    (0x071E, 0x071E, Right),
+    (0x071F, 0x0727, Dual),
+    (0x0728, 0x0728, Right),
+    (0x0729, 0x0729, Dual),
+    (0x072A, 0x072A, Right),
+    (0x072B, 0x072B, Dual),
+    (0x072C, 0x072C, Right),
+    (0x072D, 0x072E, Dual),
+    (0x072F, 0x072F, Right),
+    (0x0730, 0x074A, Transparent),
+    (0x074D, 0x074D, Right),
+    (0x074E, 0x0758, Dual),
+    (0x0759, 0x075B, Right),
+    (0x075C, 0x076A, Dual),
+    (0x076B, 0x076C, Right),
+    (0x076D, 0x0770, Dual),
+    (0x0771, 0x0771, Right),
+    (0x0772, 0x0772, Dual),
+    (0x0773, 0x0774, Right),
+    (0x0775, 0x0777, Dual),
+    (0x0778, 0x0779, Right),
+    (0x077A, 0x077F, Dual),
+    (0x08A0, 0x08A9, Dual),
+    (0x08AA, 0x08AC, Right),
+    (0x08AE, 0x08AE, Right),
+    (0x08AF, 0x08B0, Dual),
+    (0x08B1, 0x08B2, Right),
+    (0x08B3, 0x08B4, Dual),
+    (0x08B6, 0x08B8, Dual),
+    (0x08B9, 0x08B9, Right),
+    (0x08BA, 0x08BD, Dual),
+    (0x08CA, 0x08E1, Transparent),
+    (0x08E3, 0x08FF, Transparent),
+    (0x200B, 0x200B, Transparent),
+    (0x200D, 0x200D, Causing),
+    (0x200E, 0x200F, Transparent),
+    (0x202A, 0x202E, Transparent),
+    (0x2060, 0x2064, Transparent),
+    (0x2066, 0x206F, Transparent),
+    (0xFE00, 0xFE0F, Transparent),
+    (0xFE20, 0xFE2F, Transparent),
+];
@@ -0,0 +1,424 @@
+//! Shaping for Arabic and Syriac, whose letters take different forms depending on whether they
+//! join the letters either side.
+//!
+//! Each letter's joining type says which sides it can join on. Walking through the text with
+//! those gives every letter a form, such as initial or final, and the form chooses which of the
+//! font's `init`, `medi`, `fina` and `isol` features apply to it. Syriac's alaph has extra forms
+//! which depend on the letter before it.
+
+mod joining_type;
+
+use self::joining_type::JOINING_TYPES;
+use font::Font;
+use opentype::tables::layout::GlyphInfo;
+use shape::find_in_ranges;
+
+/// The sides a letter can join its neighbours on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum JoiningType {
+    /// Joins the letter before it, which is to its right.
+    Right,
+    /// Joins letters on both sides.
+    Dual,
+    /// Doesn't change shape itself, but makes the letters either side join it, like the tatweel.
+    Causing,
+    /// Letters join across it, like combining marks.
+    Transparent,
+}
+
+/// The shape a letter takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Form {
+    Isolated,
+    Final,
+    /// A Syriac alaph at the end of a word, after a letter which doesn't join it.
+    Final2,
+    /// A Syriac alaph at the end of a word, after a dalath or rish.
+    Final3,
+    Medial,
+    /// A Syriac alaph after a letter which doesn't join it, before one which does.
+    Medial2,
+    Initial,
+}
+
+impl Form {
+    /// Every form, in the order the features for them are applied.
+    const ALL: [Form; 7] = [
+        Form::Isolated,
+        Form::Final,
+        Form::Final2,
+        Form::Final3,
+        Form::Medial,
+        Form::Medial2,
+        Form::Initial,
+    ];
+
+    fn feature(self) -> [char; 4] {
+        match self {
+            Form::Isolated => ['i', 's', 'o', 'l'],
+            Form::Final => ['f', 'i', 'n', 'a'],
+            Form::Final2 => ['f', 'i', 'n', '2'],
+            Form::Final3 => ['f', 'i', 'n', '3'],
+            Form::Medial => ['m', 'e', 'd', 'i'],
+            Form::Medial2 => ['m', 'e', 'd', '2'],
+            Form::Initial => ['i', 'n', 'i', 't'],
+        }
+    }
+
+    /// The mask bit which selects the form's feature.
+    fn mask(self) -> u32 {
+        2 << self as u32
+    }
+}
+
+/// The mask bit for features which apply to every glyph.
+const GLOBAL_MASK: u32 = 1;
+
+/// Features which are applied before the letters take their forms, if they are asked for.
+const EARLY_FEATURES: [[char; 4]; 2] = [['c', 'c', 'm', 'p'], ['l', 'o', 'c', 'l']];
+
+/// Required ligatures, such as lam with alef, which are applied once the letters have their
+/// forms whether or not they are asked for.
+const REQUIRED_LIGATURES: [char; 4] = ['r', 'l', 'i', 'g'];
+
+/// The scripts this shaper handles.
+pub const SCRIPTS: [[char; 4]; 2] = [['a', 'r', 'a', 'b'], ['s', 'y', 'r', 'c']];
+
+type Action = Option<Form>;
+const NONE: Action = None;
+const ISOL: Action = Some(Form::Isolated);
+const FINA: Action = Some(Form::Final);
+const FIN2: Action = Some(Form::Final2);
+const FIN3: Action = Some(Form::Final3);
+const MEDI: Action = Some(Form::Medial);
+const MED2: Action = Some(Form::Medial2);
+const INIT: Action = Some(Form::Initial);
+
+/// For each state and class of letter, the new form of the previous letter, the form of the
+/// letter itself and the next state. The classes are non-joining, right joining, dual joining,
+/// alaph, and dalath or rish.
+#[rustfmt::skip]
+const STATE_TABLE: [[(Action, Action, usize); 5]; 7] = [
+    // 0: The previous letter doesn't join the next one.
+    [(NONE, NONE, 0), (NONE, ISOL, 1), (NONE, ISOL, 2), (NONE, ISOL, 1), (NONE, ISOL, 6)],
+    // 1: The previous letter only joins on its right, or is an isolated alaph.
+    [(NONE, NONE, 0), (NONE, ISOL, 1), (NONE, ISOL, 2), (NONE, FIN2, 5), (NONE, ISOL, 6)],
+    // 2: The previous letter is isolated, but would join the next one.
+    [(NONE, NONE, 0), (INIT, FINA, 1), (INIT, FINA, 3), (INIT, FINA, 4), (INIT, FINA, 6)],
+    // 3: The previous letter is final, but would join the next one.
+    [(NONE, NONE, 0), (MEDI, FINA, 1), (MEDI, FINA, 3), (MEDI, FINA, 4), (MEDI, FINA, 6)],
+    // 4: The previous letter is a final alaph.
+    [(NONE, NONE, 0), (MED2, ISOL, 1), (MED2, ISOL, 2), (MED2, FIN2, 5), (MED2, ISOL, 6)],
+    // 5: The previous letter is an alaph which follows a letter it doesn't join.
+    [(NONE, NONE, 0), (ISOL, ISOL, 1), (ISOL, ISOL, 2), (ISOL, FIN2, 5), (ISOL, ISOL, 6)],
+    // 6: The previous letter is a dalath or rish.
+    [(NONE, NONE, 0), (NONE, ISOL, 1), (NONE, ISOL, 2), (NONE, FIN3, 5), (NONE, ISOL, 6)],
+];
+
+fn joining_type(c: char) -> Option<JoiningType> {
+    find_in_ranges(&JOINING_TYPES, c)
+}
+
+/// The column of `STATE_TABLE` for a character, or `None` for characters which are skipped.
+fn joining_class(c: char) -> Option<usize> {
+    match (c, joining_type(c)) {
+        (_, Some(JoiningType::Transparent)) => None,
+        ('\u{0710}', _) => Some(3),
+        ('\u{0715}', _) | ('\u{0716}', _) | ('\u{072A}', _) | ('\u{072F}', _) => Some(4),
+        (_, None) => Some(0),
+        (_, Some(JoiningType::Right)) => Some(1),
+        (_, Some(JoiningType::Dual)) | (_, Some(JoiningType::Causing)) => Some(2),
+    }
+}
+
+/// The form of each character in the text, or `None` for those which have no forms.
+fn forms(text: &str) -> Vec<Option<Form>> {
+    let mut forms = vec![];
+    let mut state = 0;
+    let mut previous = None;
+    for c in text.chars() {
+        let class = match joining_class(c) {
+            Some(class) => class,
+            None => {
+                forms.push(None);
+                continue;
+            }
+        };
+        let (previous_form, form, next_state) = STATE_TABLE[state][class];
+        if let (Some(idx), Some(previous_form)) = (previous, previous_form) {
+            forms[idx] = Some(previous_form);
+        }
+        previous = Some(forms.len());
+        forms.push(form);
+        state = next_state;
+    }
+    forms
+}
+
+/// Replace the glyphs for `text`, one for each character, with the forms of the letters and the
+/// font's other substitutions for the given features.
+pub fn substitute(
+    font: &Font,
+    text: &str,
+    glyphs: &mut Vec<GlyphInfo>,
+    script: [char; 4],
+    language: Option<[char; 4]>,
+    features: &[[char; 4]],
+) {
+    for (glyph, form) in glyphs.iter_mut().zip(forms(text)) {
+        glyph.mask = GLOBAL_MASK | form.map_or(0, Form::mask);
+    }
+
+    let global = |tag: &[char; 4]| (*tag, GLOBAL_MASK);
+    let (early, late): (Vec<_>, Vec<_>) = features
+        .iter()
+        .filter(|tag| {
+            **tag != REQUIRED_LIGATURES && !Form::ALL.iter().any(|form| form.feature() == **tag)
+        })
+        .map(global)
+        .partition(|(tag, _)| EARLY_FEATURES.contains(tag));
+
+    // Each form is applied on its own, so that a font's lookups for one can't interfere with
+    // another's. The required feature goes with the first stage.
+    font.substitute_masked(glyphs, script, language, &early, true);
+    for form in Form::ALL.iter() {
+        let feature = (form.feature(), form.mask());
+        font.substitute_masked(glyphs, script, language, &[feature], false);
+    }
+    let rlig = global(&REQUIRED_LIGATURES);
+    font.substitute_masked(glyphs, script, language, &[rlig], false);
+    font.substitute_masked(glyphs, script, language, &late, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::build_font;
+    use shape::{shape, DEFAULT_FEATURES};
+
+    /// Glyphs for beh (1-4), lam (5-8) and alef (9, 10) in their isolated, initial, medial and
+    /// final forms, the fatha mark (11) and lam-alef ligatures (12, 13).
+    const GSUB_TABLE: [u8; 224] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x24, 0x00, 0x56,
+        // 10: script list: arab with every feature
+        0x00, 0x01, 0x61, 0x72, 0x61, 0x62, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03,
+        // 36: feature list: fina uses lookup 0, init uses lookup 1, medi uses lookup 2, rlig uses lookup 3
+        0x00, 0x04, 0x66, 0x69, 0x6E, 0x61, 0x00, 0x1A, 0x69, 0x6E, 0x69, 0x74, 0x00, 0x20, 0x6D,
+        0x65, 0x64, 0x69, 0x00, 0x26, 0x72, 0x6C, 0x69, 0x67, 0x00, 0x2C, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x03, // 86: lookup list
+        0x00, 0x04, 0x00, 0x0A, 0x00, 0x28, 0x00, 0x42, 0x00, 0x5C,
+        // 96: lookup 0: final forms of beh (1), lam (5) and alef (9)
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x0C, 0x00, 0x03, 0x00,
+        0x04, 0x00, 0x08, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x00, 0x05, 0x00, 0x09,
+        // 126: lookup 1: initial forms of beh and lam
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x02, 0x00, 0x06, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x05,
+        // 152: lookup 2: medial forms of beh and lam
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x03, 0x00, 0x07, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x05,
+        // 178: lookup 3: lam-alef ligatures from initial and medial lam, ignoring marks
+        0x00, 0x04, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x12, 0x00, 0x1C, 0x00, 0x01, 0x00, 0x02, 0x00, 0x06, 0x00, 0x07, 0x00, 0x01, 0x00, 0x04,
+        0x00, 0x0C, 0x00, 0x02, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x04, 0x00, 0x0D, 0x00, 0x02, 0x00,
+        0x0A,
+    ];
+    const GDEF_TABLE: [u8; 34] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        // 12: glyph classes
+        0x00, 0x02, 0x00, 0x03, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x01, 0x00, 0x0B, 0x00, 0x0B, 0x00,
+        0x03, 0x00, 0x0C, 0x00, 0x0D, 0x00, 0x02,
+    ];
+
+    /// A table for 'arab' whose required feature replaces beh (1) with 20, and 20 with 21.
+    const REQUIRED_GSUB_TABLE: [u8; 72] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1C, 0x00, 0x2A,
+        // 10: script list: arab, whose only feature is the required one
+        0x00, 0x01, 0x61, 0x72, 0x61, 0x62, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, // 28: feature list: ccmp uses lookup 0
+        0x00, 0x01, 0x63, 0x63, 0x6D, 0x70, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 42: lookup list
+        0x00, 0x01, 0x00, 0x04,
+        // 46: lookup 0: single substitution of 1 with 20 and 20 with 21
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x02, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x14, 0x00, 0x15, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x14,
+    ];
+
+    /// Shape Arabic text with a font for the glyphs above, in glyph ID and cluster.
+    fn shape_arabic(text: &str) -> Vec<(u16, usize)> {
+        let content = build_font(
+            &[
+                ('\u{0627}', 9),
+                ('\u{0628}', 1),
+                ('\u{0644}', 5),
+                ('\u{064E}', 11),
+            ],
+            &[500; 14],
+            &[(b"GDEF", &GDEF_TABLE), (b"GSUB", &GSUB_TABLE)],
+        );
+        let font = Font::from_bytes(&content).unwrap();
+        shape(
+            &font,
+            text,
+            16.0,
+            ['a', 'r', 'a', 'b'],
+            None,
+            &DEFAULT_FEATURES,
+        )
+        .iter()
+        .map(|glyph| (glyph.glyph_id, glyph.cluster))
+        .collect()
+    }
+
+    #[test]
+    fn joining_type_table_is_sorted() {
+        assert!(JOINING_TYPES
+            .windows(2)
+            .all(|pair| pair[0].0 <= pair[0].1 && pair[0].1 < pair[1].0));
+    }
+
+    #[test]
+    fn joining_types() {
+        assert_eq!(joining_type('\u{0628}'), Some(JoiningType::Dual));
+        assert_eq!(joining_type('\u{0627}'), Some(JoiningType::Right));
+        assert_eq!(joining_type('\u{0640}'), Some(JoiningType::Causing));
+        assert_eq!(joining_type('\u{064E}'), Some(JoiningType::Transparent));
+        assert_eq!(joining_type('\u{0621}'), None);
+        assert_eq!(joining_type('a'), None);
+    }
+
+    #[test]
+    fn arabic_words() {
+        use self::Form::*;
+
+        // beh yeh teh
+        assert_eq!(
+            forms("\u{0628}\u{064A}\u{062A}"),
+            vec![Some(Initial), Some(Medial), Some(Final)]
+        );
+        // seen lam alef meem
+        assert_eq!(
+            forms("\u{0633}\u{0644}\u{0627}\u{0645}"),
+            vec![Some(Initial), Some(Medial), Some(Final), Some(Isolated)]
+        );
+        // dal alef reh, none of which join the letter after them
+        assert_eq!(forms("\u{062F}\u{0627}\u{0631}"), vec![Some(Isolated); 3]);
+        // Two words: beh beh, then beh on its own.
+        assert_eq!(
+            forms("\u{0628}\u{0628} \u{0628}"),
+            vec![Some(Initial), Some(Final), None, Some(Isolated)]
+        );
+    }
+
+    #[test]
+    fn joining_across_marks_and_tatweel() {
+        use self::Form::*;
+
+        // beh with kasra, seen with sukun, meem with kasra
+        assert_eq!(
+            forms("\u{0628}\u{0650}\u{0633}\u{0652}\u{0645}\u{0650}"),
+            vec![Some(Initial), None, Some(Medial), None, Some(Final), None]
+        );
+        // A tatweel between two behs joins them both.
+        assert_eq!(
+            forms("\u{0628}\u{0640}\u{0628}"),
+            vec![Some(Initial), Some(Medial), Some(Final)]
+        );
+        // Hamza doesn't join, so splits the word.
+        assert_eq!(
+            forms("\u{0628}\u{0621}\u{0628}"),
+            vec![Some(Isolated), None, Some(Isolated)]
+        );
+    }
+
+    #[test]
+    fn syriac_alaph() {
+        use self::Form::*;
+
+        // alaph beth alaph: the last alaph joins the beth.
+        assert_eq!(
+            forms("\u{0710}\u{0712}\u{0710}"),
+            vec![Some(Isolated), Some(Initial), Some(Final)]
+        );
+        // waw alaph, after a letter which doesn't join it
+        assert_eq!(
+            forms("\u{0718}\u{0710}"),
+            vec![Some(Isolated), Some(Final2)]
+        );
+        // dalath alaph
+        assert_eq!(
+            forms("\u{0715}\u{0710}"),
+            vec![Some(Isolated), Some(Final3)]
+        );
+        // beth alaph beth: the alaph joins the beth before it, and the second beth is alone.
+        assert_eq!(
+            forms("\u{0712}\u{0710}\u{0712}"),
+            vec![Some(Initial), Some(Medial2), Some(Isolated)]
+        );
+    }
+
+    #[test]
+    fn shape_words() {
+        // beh beh beh, drawn from the last letter to the first
+        assert_eq!(
+            shape_arabic("\u{0628}\u{0628}\u{0628}"),
+            [(4, 4), (3, 2), (2, 0)]
+        );
+        // beh alef beh: alef doesn't join the letter after it, so the second beh is isolated.
+        assert_eq!(
+            shape_arabic("\u{0628}\u{0627}\u{0628}"),
+            [(1, 4), (10, 2), (2, 0)]
+        );
+    }
+
+    #[test]
+    fn shape_lam_alef() {
+        assert_eq!(shape_arabic("\u{0644}\u{0627}"), [(12, 0)]);
+        // beh lam alef, with the lam in its medial form
+        assert_eq!(shape_arabic("\u{0628}\u{0644}\u{0627}"), [(13, 2), (2, 0)]);
+        // lam alef beh
+        assert_eq!(shape_arabic("\u{0644}\u{0627}\u{0628}"), [(1, 4), (12, 0)]);
+    }
+
+    #[test]
+    fn shape_with_marks() {
+        // beh fatha beh: the behs join across the mark.
+        assert_eq!(
+            shape_arabic("\u{0628}\u{064E}\u{0628}"),
+            [(4, 4), (11, 2), (2, 0)]
+        );
+        // lam fatha alef: the ligature forms around the mark, which follows it.
+        assert_eq!(shape_arabic("\u{0644}\u{064E}\u{0627}"), [(11, 0), (12, 0)]);
+    }
+
+    #[test]
+    fn required_feature_applies_once() {
+        let content = build_font(
+            &[('\u{0628}', 1)],
+            &[500; 22],
+            &[(b"GSUB", &REQUIRED_GSUB_TABLE)],
+        );
+        let font = Font::from_bytes(&content).unwrap();
+        let glyphs = shape(
+            &font,
+            "\u{0628}",
+            16.0,
+            ['a', 'r', 'a', 'b'],
+            None,
+            &DEFAULT_FEATURES,
+        );
+
+        // Applied a second time, the feature would have gone on to replace 20 with 21.
+        assert_eq!(
+            glyphs
+                .iter()
+                .map(|glyph| glyph.glyph_id)
+                .collect::<Vec<_>>(),
+            [20]
+        );
+    }
+}
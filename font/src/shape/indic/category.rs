@@ -0,0 +1,211 @@
+//! The part each character of the Indic blocks plays in a syllable, from the Unicode Character
+//! Database.
+//!
+//! This covers the Devanagari, Bengali, Gurmukhi, Gujarati, Oriya, Tamil, Telugu, Kannada and
+//! Malayalam blocks, with the joiners, the no-break space and the dotted circle. Characters which
+//! aren't listed form syllables of their own.
+
+use super::Category::{
+    self, Consonant, Halant, Matra, Modifier, Nukta, Placeholder, PreBaseMatra, Ra, Vowel, Zwj,
+    Zwnj,
+};
+
+/// Ranges of characters, with the first and last of each, sorted for binary search.
+pub const CATEGORIES: [(u32, u32, Category); 196] = [
+    (0x00A0, 0x00A0, Placeholder),
+    (0x0900, 0x0903, Modifier),
+    (0x0904, 0x0914, Vowel),
+    (0x0915, 0x092F, Consonant),
+    (0x0930, 0x0930, Ra),
+    (0x0931, 0x0939, Consonant),
+    (0x093A, 0x093B, Matra),
+    (0x093C, 0x093C, Nukta),
+    (0x093E, 0x093E, Matra),
+    (0x093F, 0x093F, PreBaseMatra),
+    (0x0940, 0x094C, Matra),
+    (0x094D, 0x094D, Halant),
+    (0x094E, 0x094E, PreBaseMatra),
+    (0x094F, 0x094F, Matra),
+    (0x0951, 0x0954, Modifier),
+    (0x0955, 0x0957, Matra),
+    (0x0958, 0x095F, Consonant),
+    (0x0960, 0x0961, Vowel),
+    (0x0962, 0x0963, Matra),
+    (0x0966, 0x096F, Placeholder),
+    (0x0972, 0x0977, Vowel),
+    (0x0978, 0x097F, Consonant),
+    (0x0981, 0x0983, Modifier),
+    (0x0985, 0x098C, Vowel),
+    (0x098F, 0x0990, Vowel),
+    (0x0993, 0x0994, Vowel),
+    (0x0995, 0x09A8, Consonant),
+    (0x09AA, 0x09AF, Consonant),
+    (0x09B0, 0x09B0, Ra),
+    (0x09B2, 0x09B2, Consonant),
+    (0x09B6, 0x09B9, Consonant),
+    (0x09BC, 0x09BC, Nukta),
+    (0x09BE, 0x09BE, Matra),
+    (0x09BF, 0x09BF, PreBaseMatra),
+    (0x09C0, 0x09C4, Matra),
+    (0x09C7, 0x09C8, PreBaseMatra),
+    (0x09CB, 0x09CC, Matra),
+    (0x09CD, 0x09CD, Halant),
+    (0x09CE, 0x09CE, Consonant),
+    (0x09D7, 0x09D7, Matra),
+    (0x09DC, 0x09DD, Consonant),
+    (0x09DF, 0x09DF, Consonant),
+    (0x09E0, 0x09E1, Vowel),
+    (0x09E2, 0x09E3, Matra),
+    (0x09E6, 0x09EF, Placeholder),
+    (0x09F0, 0x09F0, Ra),
+    (0x09F1, 0x09F1, Consonant),
+    (0x09FC, 0x09FC, Consonant),
+    (0x09FE, 0x09FE, Modifier),
+    (0x0A01, 0x0A03, Modifier),
+    (0x0A05, 0x0A0A, Vowel),
+    (0x0A0F, 0x0A10, Vowel),
+    (0x0A13, 0x0A14, Vowel),
+    (0x0A15, 0x0A28, Consonant),
+    (0x0A2A, 0x0A2F, Consonant),
+    (0x0A30, 0x0A30, Ra),
+    (0x0A32, 0x0A33, Consonant),
+    (0x0A35, 0x0A36, Consonant),
+    (0x0A38, 0x0A39, Consonant),
+    (0x0A3C, 0x0A3C, Nukta),
+    (0x0A3E, 0x0A3E, Matra),
+    (0x0A3F, 0x0A3F, PreBaseMatra),
+    (0x0A40, 0x0A42, Matra),
+    (0x0A47, 0x0A48, Matra),
+    (0x0A4B, 0x0A4C, Matra),
+    (0x0A4D, 0x0A4D, Halant),
+    (0x0A51, 0x0A51, Modifier),
+    (0x0A59, 0x0A5C, Consonant),
+    (0x0A5E, 0x0A5E, Consonant),
+    (0x0A66, 0x0A6F, Placeholder),
+    (0x0A70, 0x0A71, Modifier),
+    (0x0A72, 0x0A73, Vowel),
+    (0x0A75, 0x0A75, Modifier),
+    (0x0A81, 0x0A83, Modifier),
+    (0x0A85, 0x0A8C, Vowel),
+    (0x0A8F, 0x0A90, Vowel),
+    (0x0A93, 0x0A94, Vowel),
+    (0x0A95, 0x0AA8, Consonant),
+    (0x0AAA, 0x0AAF, Consonant),
+    (0x0AB0, 0x0AB0, Ra),
+    (0x0AB2, 0x0AB3, Consonant),
+    (0x0AB5, 0x0AB9, Consonant),
+    (0x0ABC, 0x0ABC, Nukta),
+    (0x0ABE, 0x0ABE, Matra),
+    (0x0ABF, 0x0ABF, PreBaseMatra),
+    (0x0AC0, 0x0AC5, Matra),
+    (0x0AC7, 0x0AC9, Matra),
+    (0x0ACB, 0x0ACC, Matra),
+    (0x0ACD, 0x0ACD, Halant),
+    (0x0AE0, 0x0AE1, Vowel),
+    (0x0AE2, 0x0AE3, Matra),
+    (0x0AE6, 0x0AEF, Placeholder),
+    (0x0AF9, 0x0AF9, Consonant),
+    (0x0AFA, 0x0AFC, Modifier),
+    (0x0AFD, 0x0AFF, Nukta),
+    (0x0B01, 0x0B03, Modifier),
+    (0x0B05, 0x0B0C, Vowel),
+    (0x0B0F, 0x0B10, Vowel),
+    (0x0B13, 0x0B14, Vowel),
+    (0x0B15, 0x0B28, Consonant),
+    (0x0B2A, 0x0B2F, Consonant),
+    (0x0B30, 0x0B30, Ra),
+    (0x0B32, 0x0B33, Consonant),
+    (0x0B35, 0x0B39, Consonant),
+    (0x0B3C, 0x0B3C, Nukta),
+    (0x0B3E, 0x0B44, Matra),
+    (0x0B47, 0x0B47, PreBaseMatra),
+    (0x0B48, 0x0B48, Matra),
+    (0x0B4B, 0x0B4C, Matra),
+    (0x0B4D, 0x0B4D, Halant),
+    (0x0B55, 0x0B55, Modifier),
+    (0x0B56, 0x0B57, Matra),
+    (0x0B5C, 0x0B5D, Consonant),
+    (0x0B5F, 0x0B5F, Consonant),
+    (0x0B60, 0x0B61, Vowel),
+    (0x0B62, 0x0B63, Matra),
+    (0x0B66, 0x0B6F, Placeholder),
+    (0x0B71, 0x0B71, Consonant),
+    (0x0B82, 0x0B83, Modifier),
+    (0x0B85, 0x0B8A, Vowel),
+    (0x0B8E, 0x0B90, Vowel),
+    (0x0B92, 0x0B94, Vowel),
+    (0x0B95, 0x0B95, Consonant),
+    (0x0B99, 0x0B9A, Consonant),
+    (0x0B9C, 0x0B9C, Consonant),
+    (0x0B9E, 0x0B9F, Consonant),
+    (0x0BA3, 0x0BA4, Consonant),
+    (0x0BA8, 0x0BAA, Consonant),
+    (0x0BAE, 0x0BAF, Consonant),
+    (0x0BB0, 0x0BB0, Ra),
+    (0x0BB1, 0x0BB9, Consonant),
+    (0x0BBE, 0x0BC2, Matra),
+    (0x0BC6, 0x0BC8, PreBaseMatra),
+    (0x0BCA, 0x0BCC, Matra),
+    (0x0BCD, 0x0BCD, Halant),
+    (0x0BD7, 0x0BD7, Matra),
+    (0x0BE6, 0x0BEF, Placeholder),
+    (0x0C00, 0x0C04, Modifier),
+    (0x0C05, 0x0C0C, Vowel),
+    (0x0C0E, 0x0C10, Vowel),
+    (0x0C12, 0x0C14, Vowel),
+    (0x0C15, 0x0C28, Consonant),
+    (0x0C2A, 0x0C2F, Consonant),
+    (0x0C30, 0x0C30, Ra),
+    (0x0C31, 0x0C39, Consonant),
+    (0x0C3C, 0x0C3C, Nukta),
+    (0x0C3E, 0x0C44, Matra),
+    (0x0C46, 0x0C48, Matra),
+    (0x0C4A, 0x0C4C, Matra),
+    (0x0C4D, 0x0C4D, Halant),
+    (0x0C55, 0x0C56, Matra),
+    (0x0C58, 0x0C5A, Consonant),
+    (0x0C5D, 0x0C5D, Consonant),
+    (0x0C60, 0x0C61, Vowel),
+    (0x0C62, 0x0C63, Matra),
+    (0x0C66, 0x0C6F, Placeholder),
+    (0x0C81, 0x0C83, Modifier),
+    (0x0C85, 0x0C8C, Vowel),
+    (0x0C8E, 0x0C90, Vowel),
+    (0x0C92, 0x0C94, Vowel),
+    (0x0C95, 0x0CA8, Consonant),
+    (0x0CAA, 0x0CAF, Consonant),
+    (0x0CB0, 0x0CB0, Ra),
+    (0x0CB1, 0x0CB3, Consonant),
+    (0x0CB5, 0x0CB9, Consonant),
+    (0x0CBC, 0x0CBC, Nukta),
+    (0x0CBE, 0x0CC4, Matra),
+    (0x0CC6, 0x0CC8, Matra),
+    (0x0CCA, 0x0CCC, Matra),
+    (0x0CCD, 0x0CCD, Halant),
+    (0x0CD5, 0x0CD6, Matra),
+    (0x0CDD, 0x0CDE, Consonant),
+    (0x0CE0, 0x0CE1, Vowel),
+    (0x0CE2, 0x0CE3, Matra),
+    (0x0CE6, 0x0CEF, Placeholder),
+    (0x0D00, 0x0D03, Modifier),
+    (0x0D04, 0x0D0C, Vowel),
+    (0x0D0E, 0x0D10, Vowel),
+    (0x0D12, 0x0D14, Vowel),
+    (0x0D15, 0x0D2F, Consonant),
+    (0x0D30, 0x0D30, Ra),
+    (0x0D31, 0x0D3A, Consonant),
+    (0x0D3B, 0x0D3C, Halant),
+    (0x0D3E, 0x0D44, Matra),
+    (0x0D46, 0x0D48, PreBaseMatra),
+    (0x0D4A, 0x0D4C, Matra),
+    (0x0D4D, 0x0D4D, Halant),
+    (0x0D57, 0x0D57, Matra),
+    (0x0D5F, 0x0D5F, Consonant),
+    (0x0D60, 0x0D61, Vowel),
+    (0x0D62, 0x0D63, Matra),
+    (0x0D66, 0x0D6F, Placeholder),
+    (0x0D7A, 0x0D7F, Consonant),
+    (0x200C, 0x200C, Zwnj),
+    (0x200D, 0x200D, Zwj),
+    (0x25CC, 0x25CC, Placeholder),
+];
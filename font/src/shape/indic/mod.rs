@@ -0,0 +1,828 @@
+//! Shaping for the Brahmic scripts of India, such as Devanagari, Bengali and Tamil.
+//!
+//! Text is split into syllables, each built around a base consonant. Consonants before the base
+//! take half forms, those after it below-base or post-base forms, and a ra at the start of a
+//! syllable becomes a reph mark. Before the font's features apply, each syllable's characters are
+//! put in the order the features expect, with pre-base matras ahead of the consonants. Once the
+//! basic forms are in place, the reph and pre-base matras move to where they are drawn.
+//!
+//! This follows Microsoft's specification for fonts using the second version of each script's
+//! tag, such as `dev2`.
+
+mod category;
+
+use self::category::CATEGORIES;
+use font::Font;
+use opentype::tables::layout::GlyphInfo;
+use shape::find_in_ranges;
+use std::ops::Range;
+
+/// The part a character plays in a syllable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Category {
+    Consonant,
+    /// The consonant which becomes a reph at the start of a syllable.
+    Ra,
+    /// An independent vowel, which begins a syllable of its own.
+    Vowel,
+    /// A dependent vowel sign.
+    Matra,
+    /// A dependent vowel sign drawn before the consonants it follows.
+    PreBaseMatra,
+    Nukta,
+    /// The virama, which removes a consonant's inherent vowel.
+    Halant,
+    /// A sign which follows the vowel, such as the anusvara or visarga.
+    Modifier,
+    Zwj,
+    Zwnj,
+    /// A character which can stand in for a consonant, such as a digit or the dotted circle.
+    Placeholder,
+}
+
+/// Where a character goes in its syllable, in the order the basic features expect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Position {
+    Reph = 1,
+    PreBaseMatra,
+    /// A consonant before the base, which takes a half form.
+    PreBase,
+    Base,
+    /// A consonant after the base, which takes a below-base or post-base form.
+    PostBase,
+    Matra,
+    Modifier,
+}
+
+impl Position {
+    const ALL: [Position; 7] = [
+        Position::Reph,
+        Position::PreBaseMatra,
+        Position::PreBase,
+        Position::Base,
+        Position::PostBase,
+        Position::Matra,
+        Position::Modifier,
+    ];
+
+    /// The mask bits which record the position, and select the features which apply there.
+    fn mask(self) -> u32 {
+        let features = match self {
+            Position::Reph => REPH_MASK,
+            Position::PreBase => HALF_MASK,
+            Position::PostBase => POST_BASE_MASK,
+            _ => 0,
+        };
+        GLOBAL_MASK | features | (self as u32) << POSITION_SHIFT
+    }
+
+    /// The position a glyph was given, which glyphs the font substitutes keep.
+    fn of(glyph: &GlyphInfo) -> Option<Position> {
+        let value = glyph.mask >> POSITION_SHIFT & 0xFF;
+        Self::ALL
+            .iter()
+            .cloned()
+            .find(|&position| position as u32 == value)
+    }
+}
+
+/// Where a reph is drawn, relative to the rest of its syllable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RephPosition {
+    /// Just after the base consonant.
+    AfterMain,
+    /// After the consonants with below-base forms.
+    AfterSub,
+    /// At the end, before any signs such as the anusvara.
+    End,
+}
+
+/// How one of the scripts is shaped.
+#[derive(Debug)]
+pub struct Script {
+    tag: [char; 4],
+    /// The tag for fonts made to the second version of the specification.
+    new_tag: [char; 4],
+    reph_position: RephPosition,
+    /// Whether a ra after a virama takes a below-base form, rather than being the base.
+    below_base_ra: bool,
+    /// The virama, whose glyph shows that consonants didn't join.
+    halant: char,
+}
+
+/// Where each script's reph goes follows the per-script defaults in Microsoft's specifications,
+/// as HarfBuzz's Indic shaper also reads them. Oriya and Malayalam draw it just after the base,
+/// as does Gurmukhi, whose reph goes before the below-base forms. Bengali draws it after the
+/// below-base forms. The rest, whose rephs go before or after the post-base forms, draw it after
+/// the matras as well, since the matras of those scripts come before the reph's position.
+const SCRIPTS: [Script; 9] = [
+    Script {
+        tag: ['d', 'e', 'v', 'a'],
+        new_tag: ['d', 'e', 'v', '2'],
+        reph_position: RephPosition::End,
+        below_base_ra: true,
+        halant: '\u{094D}',
+    },
+    Script {
+        tag: ['b', 'e', 'n', 'g'],
+        new_tag: ['b', 'n', 'g', '2'],
+        reph_position: RephPosition::AfterSub,
+        below_base_ra: true,
+        halant: '\u{09CD}',
+    },
+    Script {
+        tag: ['g', 'u', 'r', 'u'],
+        new_tag: ['g', 'u', 'r', '2'],
+        reph_position: RephPosition::AfterMain,
+        below_base_ra: true,
+        halant: '\u{0A4D}',
+    },
+    Script {
+        tag: ['g', 'u', 'j', 'r'],
+        new_tag: ['g', 'j', 'r', '2'],
+        reph_position: RephPosition::End,
+        below_base_ra: true,
+        halant: '\u{0ACD}',
+    },
+    Script {
+        tag: ['o', 'r', 'y', 'a'],
+        new_tag: ['o', 'r', 'y', '2'],
+        reph_position: RephPosition::AfterMain,
+        below_base_ra: true,
+        halant: '\u{0B4D}',
+    },
+    Script {
+        tag: ['t', 'a', 'm', 'l'],
+        new_tag: ['t', 'm', 'l', '2'],
+        reph_position: RephPosition::End,
+        below_base_ra: false,
+        halant: '\u{0BCD}',
+    },
+    Script {
+        tag: ['t', 'e', 'l', 'u'],
+        new_tag: ['t', 'e', 'l', '2'],
+        reph_position: RephPosition::End,
+        below_base_ra: false,
+        halant: '\u{0C4D}',
+    },
+    Script {
+        tag: ['k', 'n', 'd', 'a'],
+        new_tag: ['k', 'n', 'd', '2'],
+        reph_position: RephPosition::End,
+        below_base_ra: false,
+        halant: '\u{0CCD}',
+    },
+    Script {
+        tag: ['m', 'l', 'y', 'm'],
+        new_tag: ['m', 'l', 'm', '2'],
+        reph_position: RephPosition::AfterMain,
+        below_base_ra: false,
+        halant: '\u{0D4D}',
+    },
+];
+
+/// The mask bit for features which apply to every glyph.
+const GLOBAL_MASK: u32 = 1;
+const REPH_MASK: u32 = 2;
+const HALF_MASK: u32 = 4;
+const POST_BASE_MASK: u32 = 8;
+/// Masks keep each glyph's position in the bits from here up, which no feature uses.
+const POSITION_SHIFT: u32 = 8;
+
+/// Features which are applied before anything else, if they are asked for.
+const EARLY_FEATURES: [[char; 4]; 2] = [['c', 'c', 'm', 'p'], ['l', 'o', 'c', 'l']];
+
+/// The features which form conjuncts, in the order they are applied, with the glyphs each
+/// applies to.
+const BASIC_FEATURES: [([char; 4], u32); 11] = [
+    (['n', 'u', 'k', 't'], GLOBAL_MASK),
+    (['a', 'k', 'h', 'n'], GLOBAL_MASK),
+    (['r', 'p', 'h', 'f'], REPH_MASK),
+    (['r', 'k', 'r', 'f'], GLOBAL_MASK),
+    (['p', 'r', 'e', 'f'], POST_BASE_MASK),
+    (['b', 'l', 'w', 'f'], POST_BASE_MASK),
+    (['a', 'b', 'v', 'f'], POST_BASE_MASK),
+    (['h', 'a', 'l', 'f'], HALF_MASK),
+    (['p', 's', 't', 'f'], POST_BASE_MASK),
+    (['v', 'a', 't', 'u'], GLOBAL_MASK),
+    (['c', 'j', 'c', 't'], GLOBAL_MASK),
+];
+
+/// The features which choose how the conjuncts are drawn, applied together once the reph and
+/// pre-base matras are in place.
+const PRESENTATION_FEATURES: [[char; 4]; 5] = [
+    ['p', 'r', 'e', 's'],
+    ['a', 'b', 'v', 's'],
+    ['b', 'l', 'w', 's'],
+    ['p', 's', 't', 's'],
+    ['h', 'a', 'l', 'n'],
+];
+
+/// Two-part vowel signs, drawn on both sides of the consonant, with the parts they are made of.
+const SPLIT_MATRAS: [(char, char, char); 11] = [
+    ('\u{09CB}', '\u{09C7}', '\u{09BE}'),
+    ('\u{09CC}', '\u{09C7}', '\u{09D7}'),
+    ('\u{0B48}', '\u{0B47}', '\u{0B56}'),
+    ('\u{0B4B}', '\u{0B47}', '\u{0B3E}'),
+    ('\u{0B4C}', '\u{0B47}', '\u{0B57}'),
+    ('\u{0BCA}', '\u{0BC6}', '\u{0BBE}'),
+    ('\u{0BCB}', '\u{0BC7}', '\u{0BBE}'),
+    ('\u{0BCC}', '\u{0BC6}', '\u{0BD7}'),
+    ('\u{0D4A}', '\u{0D46}', '\u{0D3E}'),
+    ('\u{0D4B}', '\u{0D47}', '\u{0D3E}'),
+    ('\u{0D4C}', '\u{0D46}', '\u{0D57}'),
+];
+
+/// Stands in for the missing base of a syllable which starts with a vowel sign or virama.
+const DOTTED_CIRCLE: char = '\u{25CC}';
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SyllableKind {
+    /// Built around a consonant, or an independent vowel.
+    Complete,
+    /// Marks with nothing to attach to.
+    Broken,
+    /// Any other character, on its own.
+    Other,
+}
+
+/// How a script is shaped, for either of its tags.
+pub fn script(tag: [char; 4]) -> Option<&'static Script> {
+    SCRIPTS
+        .iter()
+        .find(|script| script.tag == tag || script.new_tag == tag)
+}
+
+/// The tag to look up a script's features with. Fonts made to the second version of the
+/// specification use tags such as `dev2` rather than `deva`, and they are preferred.
+pub fn layout_script(font: &Font, tag: [char; 4]) -> [char; 4] {
+    match SCRIPTS.iter().find(|script| script.tag == tag) {
+        Some(script) if font.has_script(script.new_tag) => script.new_tag,
+        _ => tag,
+    }
+}
+
+fn category(c: char) -> Option<Category> {
+    find_in_ranges(&CATEGORIES, c)
+}
+
+/// Split text into syllables, given the category of each character.
+fn syllables(categories: &[Option<Category>]) -> Vec<(Range<usize>, SyllableKind)> {
+    let at = |idx: usize| categories.get(idx).cloned().unwrap_or(None);
+    let mut syllables = vec![];
+    let mut start = 0;
+    while start < categories.len() {
+        let (end, kind) = match at(start) {
+            Some(Category::Consonant) | Some(Category::Ra) | Some(Category::Placeholder) => (
+                consonant_syllable_end(categories, start),
+                SyllableKind::Complete,
+            ),
+            Some(Category::Vowel) => {
+                let mut end = start + 1;
+                if at(end) == Some(Category::Nukta) {
+                    end += 1;
+                }
+                (signs_end(categories, end), SyllableKind::Complete)
+            }
+            Some(Category::Matra)
+            | Some(Category::PreBaseMatra)
+            | Some(Category::Nukta)
+            | Some(Category::Halant)
+            | Some(Category::Modifier) => (signs_end(categories, start), SyllableKind::Broken),
+            _ => (start + 1, SyllableKind::Other),
+        };
+        syllables.push((start..end, kind));
+        start = end;
+    }
+    syllables
+}
+
+/// Find the end of a syllable starting with the consonant at `start`. A virama joins it to the
+/// next consonant, optionally with a joiner choosing the form they take.
+fn consonant_syllable_end(categories: &[Option<Category>], start: usize) -> usize {
+    let at = |idx: usize| categories.get(idx).cloned().unwrap_or(None);
+    let mut idx = start;
+    loop {
+        idx += 1;
+        if at(idx) == Some(Category::Nukta) {
+            idx += 1;
+        }
+        if at(idx) != Some(Category::Halant) {
+            return signs_end(categories, idx);
+        }
+
+        let mut next = idx + 1;
+        if let Some(Category::Zwj) | Some(Category::Zwnj) = at(next) {
+            next += 1;
+        }
+        match at(next) {
+            Some(Category::Consonant) | Some(Category::Ra) => idx = next,
+            // A consonant left without its vowel ends the syllable.
+            _ => return next,
+        }
+    }
+}
+
+/// Find the end of the vowel signs from `start`, followed by signs such as the anusvara.
+fn signs_end(categories: &[Option<Category>], start: usize) -> usize {
+    let at = |idx: usize| categories.get(idx).cloned().unwrap_or(None);
+    let mut idx = start;
+    while let Some(Category::Matra)
+    | Some(Category::PreBaseMatra)
+    | Some(Category::Nukta)
+    | Some(Category::Halant) = at(idx)
+    {
+        idx += 1;
+    }
+    while at(idx) == Some(Category::Modifier) {
+        idx += 1;
+    }
+    idx
+}
+
+/// Where each character of a syllable goes.
+fn positions(categories: &[Option<Category>], script: &Script) -> Vec<Position> {
+    let at = |idx: usize| categories.get(idx).cloned().unwrap_or(None);
+    let is_consonant = |idx: usize| {
+        matches!(
+            at(idx),
+            Some(Category::Consonant)
+                | Some(Category::Ra)
+                | Some(Category::Vowel)
+                | Some(Category::Placeholder)
+        )
+    };
+
+    // A ra and virama before another consonant become a reph, unless a joiner follows them.
+    let has_reph = at(0) == Some(Category::Ra)
+        && at(1) == Some(Category::Halant)
+        && at(2) != Some(Category::Zwj)
+        && (2..categories.len()).any(is_consonant);
+    let first = if has_reph { 2 } else { 0 };
+
+    // The base is the last consonant, unless that takes a below-base form.
+    let mut base = None;
+    for idx in (first..categories.len())
+        .rev()
+        .filter(|&idx| is_consonant(idx))
+    {
+        base = Some(idx);
+        let below_base = script.below_base_ra
+            && at(idx) == Some(Category::Ra)
+            && idx > first
+            && at(idx - 1) == Some(Category::Halant);
+        if !below_base {
+            break;
+        }
+    }
+
+    let mut positions: Vec<Option<Position>> = (0..categories.len())
+        .map(|idx| match at(idx) {
+            _ if idx < first => Some(Position::Reph),
+            _ if is_consonant(idx) => Some(match base {
+                Some(base) if idx < base => Position::PreBase,
+                Some(base) if idx == base => Position::Base,
+                _ => Position::PostBase,
+            }),
+            Some(Category::PreBaseMatra) => Some(Position::PreBaseMatra),
+            Some(Category::Matra) => Some(Position::Matra),
+            Some(Category::Modifier) => Some(Position::Modifier),
+            _ => None,
+        })
+        .collect();
+
+    // Viramas and joiners before a consonant with a below-base or post-base form go with it, so
+    // that features for those forms apply to them. Anything else stays with what it follows.
+    for idx in 0..positions.len() {
+        if positions[idx].is_some() {
+            continue;
+        }
+        let next = (idx + 1..categories.len())
+            .find(|&next| !matches!(at(next), Some(Category::Zwj) | Some(Category::Zwnj)));
+        let joins_post_base = at(idx) != Some(Category::Nukta)
+            && next.map(|next| positions[next]) == Some(Some(Position::PostBase));
+        positions[idx] = if joins_post_base {
+            Some(Position::PostBase)
+        } else {
+            Some(idx.checked_sub(1).map_or(Position::Base, |prev| {
+                positions[prev].unwrap_or(Position::Base)
+            }))
+        };
+    }
+    positions
+        .into_iter()
+        .map(|position| position.unwrap_or(Position::Base))
+        .collect()
+}
+
+/// Move a reph the font formed, and pre-base matras, to where they are drawn in one syllable.
+fn reorder_syllable(syllable: &mut [GlyphInfo], script: &Script, halant: Option<u16>) {
+    let position = |glyph: &GlyphInfo| Position::of(glyph);
+
+    // The font formed a reph if the ra and virama became a single glyph.
+    let formed_reph = syllable.first().map(position) == Some(Some(Position::Reph))
+        && syllable.get(1).map(position) != Some(Some(Position::Reph));
+    if formed_reph {
+        let target = match script.reph_position {
+            RephPosition::AfterMain => syllable
+                .iter()
+                .rposition(|glyph| position(glyph) <= Some(Position::Base)),
+            RephPosition::AfterSub => syllable
+                .iter()
+                .rposition(|glyph| position(glyph) <= Some(Position::PostBase)),
+            RephPosition::End => syllable
+                .iter()
+                .rposition(|glyph| position(glyph) != Some(Position::Modifier)),
+        };
+        syllable[..=target.unwrap_or(0)].rotate_left(1);
+    }
+
+    // Pre-base matras are drawn before the consonants, except for those the font couldn't join
+    // to the base, which are left with a visible virama.
+    let matras_start = syllable
+        .iter()
+        .position(|glyph| position(glyph) == Some(Position::PreBaseMatra));
+    if let (Some(matras_start), Some(halant)) = (matras_start, halant) {
+        let num_matras = syllable[matras_start..]
+            .iter()
+            .take_while(|glyph| position(glyph) == Some(Position::PreBaseMatra))
+            .count();
+        let base = syllable
+            .iter()
+            .position(|glyph| position(glyph) == Some(Position::Base))
+            .unwrap_or(syllable.len());
+        let last_halant = syllable[..base]
+            .iter()
+            .rposition(|glyph| glyph.glyph_id == halant);
+        if let Some(last_halant) = last_halant.filter(|&idx| idx > matras_start) {
+            syllable[matras_start..=last_halant].rotate_left(num_matras);
+        }
+    }
+}
+
+/// Replace the glyphs for `text`, one for each character, with the conjuncts and other forms the
+/// font has for the given features, and put them in the order they are drawn.
+pub fn substitute(
+    font: &Font,
+    script: &Script,
+    text: &str,
+    glyphs: &mut Vec<GlyphInfo>,
+    tag: [char; 4],
+    language: Option<[char; 4]>,
+    features: &[[char; 4]],
+) {
+    let mut chars = vec![];
+    for (glyph, c) in glyphs.iter().zip(text.chars()) {
+        let split = SPLIT_MATRAS
+            .iter()
+            .find(|(matra, _, _)| *matra == c)
+            .and_then(|&(_, first, second)| {
+                Some([
+                    (font.glyph_index(first as u32)?, Category::PreBaseMatra),
+                    (font.glyph_index(second as u32)?, Category::Matra),
+                ])
+            });
+        match split {
+            Some(parts) => chars.extend(parts.iter().map(|&(glyph_id, category)| {
+                (GlyphInfo::new(glyph_id, glyph.cluster), Some(category))
+            })),
+            None => chars.push((*glyph, category(c))),
+        }
+    }
+
+    let categories: Vec<_> = chars.iter().map(|&(_, category)| category).collect();
+    glyphs.clear();
+    for (range, kind) in syllables(&categories) {
+        let mut syllable = chars[range].to_vec();
+        let cluster = syllable
+            .iter()
+            .map(|(glyph, _)| glyph.cluster)
+            .min()
+            .unwrap_or(0);
+        if kind == SyllableKind::Other {
+            glyphs.extend(syllable.iter().map(|&(glyph, _)| GlyphInfo {
+                mask: GLOBAL_MASK,
+                ..glyph
+            }));
+            continue;
+        }
+        if kind == SyllableKind::Broken {
+            if let Some(glyph_id) = font.glyph_index(DOTTED_CIRCLE as u32) {
+                let placeholder = GlyphInfo::new(glyph_id, cluster);
+                syllable.insert(0, (placeholder, Some(Category::Placeholder)));
+            }
+        }
+
+        // The syllable is reordered, so its glyphs all belong to one cluster.
+        let categories: Vec<_> = syllable.iter().map(|&(_, category)| category).collect();
+        let start = glyphs.len();
+        glyphs.extend(syllable.iter().zip(positions(&categories, script)).map(
+            |(&(glyph, _), position)| GlyphInfo {
+                cluster,
+                mask: position.mask(),
+                ..glyph
+            },
+        ));
+        glyphs[start..].sort_by_key(Position::of);
+    }
+
+    let global = |tag: &[char; 4]| (*tag, GLOBAL_MASK);
+    let (early, late): (Vec<_>, Vec<_>) = features
+        .iter()
+        .filter(|tag| !BASIC_FEATURES.iter().any(|(basic, _)| basic == *tag))
+        .chain(PRESENTATION_FEATURES.iter())
+        .map(global)
+        .partition(|(tag, _)| EARLY_FEATURES.contains(tag));
+
+    // The required feature goes with the first stage.
+    font.substitute_masked(glyphs, tag, language, &early, true);
+    for &feature in BASIC_FEATURES.iter() {
+        font.substitute_masked(glyphs, tag, language, &[feature], false);
+    }
+    let halant = font.glyph_index(script.halant as u32);
+    let mut start = 0;
+    while start < glyphs.len() {
+        let cluster = glyphs[start].cluster;
+        let len = glyphs[start..]
+            .iter()
+            .take_while(|glyph| glyph.cluster == cluster)
+            .count();
+        reorder_syllable(&mut glyphs[start..start + len], script, halant);
+        start += len;
+    }
+    font.substitute_masked(glyphs, tag, language, &late, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::build_font;
+    use shape::{shape, DEFAULT_FEATURES};
+
+    /// Glyphs for ka (1), ra (2), ta (3), the virama (4), the matras i (5) and aa (6) and the
+    /// anusvara (7), with a reph (8), half ka (9), half ta (10) and below-base ra (11).
+    const GSUB_TABLE: [u8; 190] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x22, 0x00, 0x48,
+        // 10: script list: dev2 with every feature
+        0x00, 0x01, 0x64, 0x65, 0x76, 0x32, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02,
+        // 34: feature list: blwf uses lookup 1, half uses lookup 2, rphf uses lookup 0
+        0x00, 0x03, 0x62, 0x6C, 0x77, 0x66, 0x00, 0x14, 0x68, 0x61, 0x6C, 0x66, 0x00, 0x1A, 0x72,
+        0x70, 0x68, 0x66, 0x00, 0x20, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, // 72: lookup list
+        0x00, 0x03, 0x00, 0x08, 0x00, 0x28, 0x00, 0x48,
+        // 80: lookup 0: reph from ra (2) and virama (4)
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x04, 0x00, 0x08, 0x00, 0x02,
+        0x00, 0x04, // 112: lookup 1: below-base ra from virama and ra
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x04, 0x00, 0x01, 0x00, 0x04, 0x00, 0x0B, 0x00, 0x02,
+        0x00, 0x02, // 144: lookup 2: half forms of ka (1) and ta (3)
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x12, 0x00, 0x1C, 0x00, 0x01, 0x00, 0x02, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x00, 0x04,
+        0x00, 0x09, 0x00, 0x02, 0x00, 0x04, 0x00, 0x01, 0x00, 0x04, 0x00, 0x0A, 0x00, 0x02, 0x00,
+        0x04,
+    ];
+
+    /// Shape Devanagari text with a font for the glyphs above, in glyph ID and cluster.
+    fn shape_devanagari(text: &str) -> Vec<(u16, usize)> {
+        let content = build_font(
+            &[
+                ('क', 1),
+                ('र', 2),
+                ('त', 3),
+                ('्', 4),
+                ('ि', 5),
+                ('ा', 6),
+                ('ं', 7),
+            ],
+            &[500; 12],
+            &[(b"GSUB", &GSUB_TABLE)],
+        );
+        let font = Font::from_bytes(&content).unwrap();
+        shape(
+            &font,
+            text,
+            16.0,
+            ['d', 'e', 'v', 'a'],
+            None,
+            &DEFAULT_FEATURES,
+        )
+        .iter()
+        .map(|glyph| (glyph.glyph_id, glyph.cluster))
+        .collect()
+    }
+
+    fn categories(text: &str) -> Vec<Option<Category>> {
+        text.chars().map(category).collect()
+    }
+
+    /// The syllables of the text, as the characters in each.
+    fn split(text: &str) -> Vec<(String, SyllableKind)> {
+        let chars: Vec<_> = text.chars().collect();
+        syllables(&categories(text))
+            .into_iter()
+            .map(|(range, kind)| (chars[range].iter().collect(), kind))
+            .collect()
+    }
+
+    /// A syllable's characters, in the order the basic features see them.
+    fn initial_order(text: &str) -> String {
+        let mut chars: Vec<_> = text
+            .chars()
+            .zip(positions(&categories(text), &SCRIPTS[0]))
+            .collect();
+        chars.sort_by_key(|&(_, position)| position);
+        chars.into_iter().map(|(c, _)| c).collect()
+    }
+
+    /// A syllable after the basic features, as pairs of glyph ID and position.
+    fn reorder(glyphs: &[(u16, Position)], script: &Script) -> Vec<u16> {
+        let mut glyphs: Vec<_> = glyphs
+            .iter()
+            .map(|&(glyph_id, position)| GlyphInfo {
+                mask: position.mask(),
+                ..GlyphInfo::new(glyph_id, 0)
+            })
+            .collect();
+        reorder_syllable(&mut glyphs, script, Some(99));
+        glyphs.iter().map(|glyph| glyph.glyph_id).collect()
+    }
+
+    #[test]
+    fn category_table_is_sorted() {
+        assert!(CATEGORIES
+            .windows(2)
+            .all(|pair| pair[0].0 <= pair[0].1 && pair[0].1 < pair[1].0));
+    }
+
+    #[test]
+    fn categories_of_devanagari() {
+        assert_eq!(category('क'), Some(Category::Consonant));
+        assert_eq!(category('र'), Some(Category::Ra));
+        assert_eq!(category('अ'), Some(Category::Vowel));
+        assert_eq!(category('ि'), Some(Category::PreBaseMatra));
+        assert_eq!(category('ा'), Some(Category::Matra));
+        assert_eq!(category('्'), Some(Category::Halant));
+        assert_eq!(category('ं'), Some(Category::Modifier));
+        assert_eq!(category('a'), None);
+    }
+
+    #[test]
+    fn syllables_of_hindi() {
+        use self::SyllableKind::*;
+
+        assert_eq!(
+            split("नमस्ते"),
+            vec![
+                ("न".to_string(), Complete),
+                ("म".to_string(), Complete),
+                ("स्ते".to_string(), Complete),
+            ]
+        );
+        assert_eq!(
+            split("हिंदी भाषा"),
+            vec![
+                ("हिं".to_string(), Complete),
+                ("दी".to_string(), Complete),
+                (" ".to_string(), Other),
+                ("भा".to_string(), Complete),
+                ("षा".to_string(), Complete),
+            ]
+        );
+        // A consonant left without its vowel, and a vowel sign with nothing to attach to.
+        assert_eq!(
+            split("क्\u{200D}ि"),
+            vec![
+                ("क्\u{200D}".to_string(), Complete),
+                ("ि".to_string(), Broken)
+            ]
+        );
+        assert_eq!(split("आँ"), vec![("आँ".to_string(), Complete)]);
+    }
+
+    #[test]
+    fn initial_reordering() {
+        // Pre-base matras go before the consonants.
+        assert_eq!(initial_order("क्षि"), "िक्ष");
+        assert_eq!(initial_order("कि"), "िक");
+        // A reph stays at the start until the font has formed it.
+        assert_eq!(initial_order("र्कि"), "र्िक");
+        // A ra after a virama isn't the base, so the pre-base matra goes before the first
+        // consonant.
+        assert_eq!(initial_order("प्रि"), "िप्र");
+    }
+
+    #[test]
+    fn reph_and_below_base_positions() {
+        let positions = positions(&categories("र्प्र"), &SCRIPTS[0]);
+
+        assert_eq!(
+            positions,
+            vec![
+                Position::Reph,
+                Position::Reph,
+                Position::Base,
+                Position::PostBase,
+                Position::PostBase,
+            ]
+        );
+        // Without a consonant after it, a ra with a virama is just a dead consonant.
+        assert_eq!(
+            super::positions(&categories("र्"), &SCRIPTS[0]),
+            vec![Position::Base, Position::Base]
+        );
+    }
+
+    #[test]
+    fn reph_moves() {
+        let syllable = [
+            (1, Position::Reph),
+            (2, Position::Base),
+            (3, Position::PostBase),
+            (4, Position::Matra),
+            (5, Position::Modifier),
+        ];
+        let devanagari = script(['d', 'e', 'v', 'a']).unwrap();
+        let bengali = script(['b', 'e', 'n', 'g']).unwrap();
+        let oriya = script(['o', 'r', 'y', 'a']).unwrap();
+        let gurmukhi = script(['g', 'u', 'r', 'u']).unwrap();
+
+        assert_eq!(reorder(&syllable, devanagari), vec![2, 3, 4, 1, 5]);
+        assert_eq!(reorder(&syllable, bengali), vec![2, 3, 1, 4, 5]);
+        assert_eq!(reorder(&syllable, oriya), vec![2, 1, 3, 4, 5]);
+        assert_eq!(reorder(&syllable, gurmukhi), vec![2, 1, 3, 4, 5]);
+        // A ra and virama which the font didn't turn into a reph stay where they are.
+        assert_eq!(
+            reorder(
+                &[
+                    (1, Position::Reph),
+                    (99, Position::Reph),
+                    (2, Position::Base)
+                ],
+                devanagari
+            ),
+            vec![1, 99, 2]
+        );
+    }
+
+    #[test]
+    fn pre_base_matras_move_after_visible_viramas() {
+        let devanagari = script(['d', 'e', 'v', '2']).unwrap();
+
+        // The virama of a half form the font has is gone, so the matra stays first.
+        assert_eq!(
+            reorder(
+                &[
+                    (1, Position::PreBaseMatra),
+                    (2, Position::PreBase),
+                    (3, Position::Base)
+                ],
+                devanagari
+            ),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            reorder(
+                &[
+                    (1, Position::PreBaseMatra),
+                    (2, Position::PreBase),
+                    (99, Position::PreBase),
+                    (3, Position::Base)
+                ],
+                devanagari
+            ),
+            vec![2, 99, 1, 3]
+        );
+    }
+
+    #[test]
+    fn shape_pre_base_matras() {
+        // ki, then ka: the i matra is drawn before the consonant it follows.
+        assert_eq!(shape_devanagari("किक"), [(5, 0), (1, 0), (1, 6)]);
+        // kti: and before the whole conjunct, with ka in its half form.
+        assert_eq!(shape_devanagari("क्ति"), [(5, 0), (9, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn shape_reph() {
+        // rka: the ra and virama become a reph, drawn after the base.
+        assert_eq!(shape_devanagari("र्क"), [(1, 0), (8, 0)]);
+        // rki and rkaa: after the matras too.
+        assert_eq!(shape_devanagari("र्कि"), [(5, 0), (1, 0), (8, 0)]);
+        assert_eq!(shape_devanagari("र्का"), [(1, 0), (6, 0), (8, 0)]);
+        // rkam: but before the anusvara.
+        assert_eq!(shape_devanagari("र्कं"), [(1, 0), (8, 0), (7, 0)]);
+    }
+
+    #[test]
+    fn shape_conjuncts() {
+        // tka: ta takes its half form before the base.
+        assert_eq!(shape_devanagari("त्क"), [(10, 0), (1, 0)]);
+        // kra: a ra after the base takes its below-base form.
+        assert_eq!(shape_devanagari("क्र"), [(1, 0), (11, 0)]);
+        // ktra, with both.
+        assert_eq!(shape_devanagari("क्त्र"), [(9, 0), (3, 0), (11, 0)]);
+    }
+}
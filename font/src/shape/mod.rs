@@ -0,0 +1,278 @@
+//! Turns a string into a run of positioned glyphs, ready to be drawn.
+//!
+//! Characters are mapped to glyphs with the cmap table, replaced with the font's substitutions
+//! from GSUB and then positioned starting from their advance widths in hmtx, with GPOS or, for
+//! fonts without it, the kern table. This is enough for scripts such as Latin, Cyrillic and
+//! Greek, whose letters don't change shape or order depending on their neighbours. Arabic and
+//! Syriac letters take their joining forms first, and the syllables of Indic scripts such as
+//! Devanagari are reordered around their base consonants.
+
+mod arabic;
+mod indic;
+
+use font::Font;
+use opentype::tables::layout::{Direction, GlyphInfo};
+use std::cmp::Ordering;
+
+/// The features which text is normally shaped with: glyph composition, localized forms,
+/// ligatures, contextual alternates, kerning, cursive attachment, distances and mark positioning.
+pub const DEFAULT_FEATURES: [[char; 4]; 13] = [
+    ['c', 'c', 'm', 'p'],
+    ['l', 'o', 'c', 'l'],
+    ['r', 'l', 'i', 'g'],
+    ['l', 'i', 'g', 'a'],
+    ['c', 'l', 'i', 'g'],
+    ['c', 'a', 'l', 't'],
+    ['k', 'e', 'r', 'n'],
+    ['c', 'u', 'r', 's'],
+    ['d', 'i', 's', 't'],
+    ['a', 'b', 'v', 'm'],
+    ['b', 'l', 'w', 'm'],
+    ['m', 'a', 'r', 'k'],
+    ['m', 'k', 'm', 'k'],
+];
+
+/// Find the value for a character in a table of ranges, each with its first and last character,
+/// sorted for binary search.
+fn find_in_ranges<T: Copy>(ranges: &[(u32, u32, T)], c: char) -> Option<T> {
+    let c = c as u32;
+    ranges
+        .binary_search_by(|&(first, last, _)| {
+            if last < c {
+                Ordering::Less
+            } else if first > c {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .ok()
+        .map(|idx| ranges[idx].2)
+}
+
+/// A glyph placed in a run of text, with distances in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapedGlyph {
+    pub glyph_id: u16,
+    /// The byte offset in the text of the first character the glyph represents. Glyphs made from
+    /// several characters, such as ligatures, share the cluster of the first.
+    pub cluster: usize,
+    /// How far to move the pen after drawing the glyph.
+    pub x_advance: f32,
+    pub y_advance: f32,
+    /// How far from the pen position to draw the glyph, up and to the right.
+    pub x_offset: f32,
+    pub y_offset: f32,
+}
+
+/// Shape `text` at `pixel_size` pixels per em, using the font's features for the script and
+/// language, such as `['l', 'a', 't', 'n']`. Pass `DEFAULT_FEATURES` for the usual ones.
+///
+/// The glyphs come back in the order they are drawn from left to right, which for scripts
+/// written from right to left is the reverse of the text. Characters the font has no glyph for
+/// become glyph 0, the missing glyph.
+pub fn shape(
+    font: &Font,
+    text: &str,
+    pixel_size: f32,
+    script: [char; 4],
+    language: Option<[char; 4]>,
+    features: &[[char; 4]],
+) -> Vec<ShapedGlyph> {
+    let mut glyphs: Vec<_> = text
+        .char_indices()
+        .map(|(offset, c)| GlyphInfo::new(font.glyph_index(c as u32).unwrap_or(0), offset))
+        .collect();
+    let script = indic::layout_script(font, script);
+    if arabic::SCRIPTS.contains(&script) {
+        arabic::substitute(font, text, &mut glyphs, script, language, features);
+    } else if let Some(indic_script) = indic::script(script) {
+        indic::substitute(
+            font,
+            indic_script,
+            text,
+            &mut glyphs,
+            script,
+            language,
+            features,
+        );
+    } else {
+        font.substitute(&mut glyphs, script, language, features);
+    }
+    let positions = font.position(&glyphs, script, language, features);
+
+    let units_per_em = font
+        .units_per_em()
+        .unwrap_or(Font::DEFAULT_UNITS_PER_EM)
+        .max(1);
+    let scale = pixel_size / f32::from(units_per_em);
+    let mut shaped: Vec<_> = glyphs
+        .iter()
+        .zip(positions)
+        .map(|(glyph, position)| ShapedGlyph {
+            glyph_id: glyph.glyph_id,
+            cluster: glyph.cluster,
+            x_advance: position.x_advance as f32 * scale,
+            y_advance: position.y_advance as f32 * scale,
+            x_offset: position.x_offset as f32 * scale,
+            y_offset: position.y_offset as f32 * scale,
+        })
+        .collect();
+    // Positioning leaves right to left text in its own order, with the pen moving left before
+    // each glyph. Drawn in reverse with the pen moving right, the glyphs land in the same places.
+    if Direction::from_script(script) == Direction::RightToLeft {
+        shaped.reverse();
+    }
+    shaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentype::tables::test_data::{build_font, SAMPLE_FONT};
+
+    const GSUB_TABLE: [u8; 80] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1E, 0x00, 0x2C,
+        // 10: script list: latn with every feature
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x01, 0x00, 0x00, // 30: feature list: liga uses lookup 0
+        0x00, 0x01, 0x6C, 0x69, 0x67, 0x61, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 44: lookup list
+        0x00, 0x01, 0x00, 0x04, // 48: lookup 0: ligature of f (3) and i (4) as fi (5)
+        0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00,
+        0x0E, 0x00, 0x01, 0x00, 0x01, 0x00, 0x03, 0x00, 0x01, 0x00, 0x04, 0x00, 0x05, 0x00, 0x02,
+        0x00, 0x04,
+    ];
+    const GPOS_TABLE: [u8; 80] = [
+        // 0: header
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x1E, 0x00, 0x2C,
+        // 10: script list: latn with every feature
+        0x00, 0x01, 0x6C, 0x61, 0x74, 0x6E, 0x00, 0x08, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0xFF,
+        0xFF, 0x00, 0x01, 0x00, 0x00, // 30: feature list: kern uses lookup 0
+        0x00, 0x01, 0x6B, 0x65, 0x72, 0x6E, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        // 44: lookup list
+        0x00, 0x01, 0x00, 0x04,
+        // 48: lookup 0: pair adjustment, A (1) then V (2) moves closer by 80
+        0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x00, 0x0C, 0x00, 0x04, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x12, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x02,
+        0xFF, 0xB0,
+    ];
+    const KERN_TABLE: [u8; 24] = [
+        // 0: header
+        0x00, 0x00, 0x00, 0x01,
+        // 4: format 0 subtable: A (1) then V (2) moves closer by 80
+        0x00, 0x00, 0x00, 0x14, 0x00, 0x01, 0x00, 0x01, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x02, 0xFF, 0xB0,
+    ];
+
+    /// Characters for a font with glyphs for A, V, f, i, an fi ligature and é, 600, 600, 300,
+    /// 250, 500 and 550 units wide.
+    const LATIN_MAPPINGS: [(char, u16); 5] = [('A', 1), ('V', 2), ('f', 3), ('i', 4), ('é', 6)];
+    const LATIN_ADVANCES: [u16; 7] = [500, 600, 600, 300, 250, 500, 550];
+
+    /// Shape text at half the font's size, in glyph ID, cluster and advance.
+    fn shape_latin(content: &[u8], text: &str) -> Vec<(u16, usize, f32)> {
+        let font = Font::from_bytes(content).unwrap();
+        shape(
+            &font,
+            text,
+            500.0,
+            ['l', 'a', 't', 'n'],
+            None,
+            &DEFAULT_FEATURES,
+        )
+        .iter()
+        .map(|glyph| (glyph.glyph_id, glyph.cluster, glyph.x_advance))
+        .collect()
+    }
+
+    #[test]
+    fn clusters_are_byte_offsets() {
+        let font = Font::from_bytes(SAMPLE_FONT).unwrap();
+        let glyphs = shape(
+            &font,
+            "aé€",
+            16.0,
+            ['l', 'a', 't', 'n'],
+            None,
+            &DEFAULT_FEATURES,
+        );
+
+        // The sample font maps no characters and gives no glyph an advance width.
+        assert_eq!(
+            glyphs,
+            [0, 1, 3]
+                .iter()
+                .map(|&cluster| ShapedGlyph {
+                    glyph_id: 0,
+                    cluster,
+                    x_advance: 0.0,
+                    y_advance: 0.0,
+                    x_offset: 0.0,
+                    y_offset: 0.0,
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty_text() {
+        let font = Font::from_bytes(SAMPLE_FONT).unwrap();
+
+        assert!(shape(&font, "", 16.0, ['l', 'a', 't', 'n'], None, &[]).is_empty());
+    }
+
+    #[test]
+    fn shape_with_gsub_and_gpos() {
+        let content = build_font(
+            &LATIN_MAPPINGS,
+            &LATIN_ADVANCES,
+            &[(b"GPOS", &GPOS_TABLE), (b"GSUB", &GSUB_TABLE)],
+        );
+
+        // A is kerned against V, and the fi ligature takes the cluster of the f, after the two
+        // bytes of é.
+        assert_eq!(
+            shape_latin(&content, "AVéfi"),
+            [(1, 0, 260.0), (2, 1, 300.0), (6, 2, 275.0), (5, 4, 250.0)]
+        );
+        assert_eq!(
+            shape_latin(&content, "VAif"),
+            [(2, 0, 300.0), (1, 1, 300.0), (4, 2, 125.0), (3, 3, 150.0)]
+        );
+    }
+
+    #[test]
+    fn shape_with_kern_table() {
+        let content = build_font(
+            &LATIN_MAPPINGS,
+            &LATIN_ADVANCES,
+            &[(b"GSUB", &GSUB_TABLE), (b"kern", &KERN_TABLE)],
+        );
+
+        assert_eq!(
+            shape_latin(&content, "AVéfi"),
+            [(1, 0, 260.0), (2, 1, 300.0), (6, 2, 275.0), (5, 4, 250.0)]
+        );
+    }
+
+    #[test]
+    fn shape_without_features() {
+        let content = build_font(
+            &LATIN_MAPPINGS,
+            &LATIN_ADVANCES,
+            &[(b"GPOS", &GPOS_TABLE), (b"GSUB", &GSUB_TABLE)],
+        );
+        let font = Font::from_bytes(&content).unwrap();
+
+        let glyphs: Vec<_> = shape(&font, "AVfi", 500.0, ['l', 'a', 't', 'n'], None, &[])
+            .iter()
+            .map(|glyph| (glyph.glyph_id, glyph.cluster, glyph.x_advance))
+            .collect();
+        assert_eq!(
+            glyphs,
+            [(1, 0, 300.0), (2, 1, 300.0), (3, 2, 150.0), (4, 3, 125.0)]
+        );
+    }
+}
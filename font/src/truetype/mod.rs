@@ -1,4 +1,5 @@
 use super::sfnt::SfntFile;
+use font::FontParseErr;
 
 #[derive(Debug)]
 pub struct TrueTypeFile<'a> {
@@ -6,9 +7,9 @@ pub struct TrueTypeFile<'a> {
 }
 
 impl<'a> TrueTypeFile<'a> {
-    pub fn deserialize(content: &'a [u8]) -> Self {
-        Self {
-            sfnt: SfntFile::deserialize(content),
-        }
+    pub fn deserialize_at(content: &'a [u8], offset: usize) -> Result<Self, FontParseErr> {
+        Ok(Self {
+            sfnt: SfntFile::deserialize_at(content, offset)?,
+        })
     }
 }
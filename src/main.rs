@@ -1,10 +1,15 @@
 extern crate font;
 
 use std::env;
-use std::fs::File;
-use std::io::Read;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
 
-use font::Font;
+use font::{Font, SvgOptions};
+
+/// Glyphs are exported with 1000 units to the em, the usual size for PostScript-flavoured fonts
+/// and a convenient one for SVG.
+const SVG_EM_SIZE: f32 = 1000.0;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -12,11 +17,18 @@ fn main() {
         panic!("Must supply font file.");
     }
 
+    if args[1] == "svg" {
+        if args.len() < 4 {
+            panic!("Usage: main svg FONT OUTPUT [--sprite]");
+        }
+        let sprite = args[4..].iter().any(|arg| arg == "--sprite");
+        export_svg(&args[2], &args[3], sprite);
+        return;
+    }
+
     let filename = &args[1];
     println!("Loading file: {}", filename);
-    let mut f = File::open(filename).expect("file not found.");
-    let mut data: Vec<u8> = vec![];
-    f.read_to_end(&mut data).unwrap();
+    let data = read_file(filename);
 
     println!("Finished reading {} bytes.", data.len());
 
@@ -30,3 +42,83 @@ fn main() {
         }
     }
 }
+
+fn read_file(filename: &str) -> Vec<u8> {
+    let mut f = File::open(filename).expect("file not found.");
+    let mut data: Vec<u8> = vec![];
+    f.read_to_end(&mut data).unwrap();
+    data
+}
+
+/// Write every glyph with an outline either to its own SVG file in the `output` directory, or
+/// as a `<symbol>` in a single sprite sheet at `output`.
+fn export_svg(filename: &str, output: &str, sprite: bool) {
+    let data = read_file(filename);
+    let font = Font::from_bytes(&data).expect("Failed to parse font.");
+    let num_glyphs = font.num_glyphs().unwrap_or(0);
+    let units_per_em = font.units_per_em().unwrap_or(2048);
+    let options = SvgOptions {
+        em_size: SVG_EM_SIZE,
+        flip_y: true,
+    };
+
+    let mut symbols = vec![];
+    for glyph_id in 0..num_glyphs {
+        let outline = match font.glyph_outline(glyph_id) {
+            Ok(outline) => outline,
+            Err(error) => {
+                println!("Skipping glyph {}: {:?}", glyph_id, error);
+                continue;
+            }
+        };
+        let (min_x, min_y, max_x, max_y) = match outline.bounds() {
+            Some(bounds) => bounds,
+            // Glyphs such as the space have nothing to draw.
+            None => continue,
+        };
+
+        // The y axis is flipped, so the top of the glyph has the smallest y.
+        let scale = SVG_EM_SIZE / f32::from(units_per_em.max(1));
+        let view_box = format!(
+            "{} {} {} {}",
+            (min_x * scale).floor(),
+            (-max_y * scale).floor(),
+            ((max_x - min_x) * scale).ceil(),
+            ((max_y - min_y) * scale).ceil()
+        );
+        let path = outline.to_svg_path(units_per_em, &options);
+        symbols.push((glyph_id, view_box, path));
+    }
+
+    if sprite {
+        let mut svg = String::from("<svg xmlns=\"http://www.w3.org/2000/svg\">\n");
+        for (glyph_id, view_box, path) in &symbols {
+            svg.push_str(&format!(
+                "  <symbol id=\"glyph-{}\" viewBox=\"{}\"><path d=\"{}\"/></symbol>\n",
+                glyph_id, view_box, path
+            ));
+        }
+        svg.push_str("</svg>\n");
+        write_file(Path::new(output), &svg);
+    } else {
+        fs::create_dir_all(output).expect("Failed to create output directory.");
+        for (glyph_id, view_box, path) in &symbols {
+            let svg = format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{}\"><path d=\"{}\"/></svg>\n",
+                view_box, path
+            );
+            write_file(
+                &Path::new(output).join(format!("glyph-{}.svg", glyph_id)),
+                &svg,
+            );
+        }
+    }
+
+    println!("Exported {} of {} glyphs.", symbols.len(), num_glyphs);
+}
+
+fn write_file(path: &Path, contents: &str) {
+    let mut f = File::create(path).expect("Failed to create output file.");
+    f.write_all(contents.as_bytes())
+        .expect("Failed to write output file.");
+}
@@ -0,0 +1,141 @@
+//! Works out which Unicode characters a glyph represents from its PostScript name, following the
+//! Adobe Glyph List specification.
+//!
+//! A name such as `f_f_i.alt` is split into the components `f`, `f` and `i`, with the suffix
+//! `.alt` ignored. Each component is then looked up in the glyph list, or read as a `uniXXXX` or
+//! `uXXXXXX` codepoint.
+
+mod glyph_list;
+
+use self::glyph_list::GLYPH_LIST;
+use std::str;
+
+/// The characters a glyph name represents, in order. Returns an empty list for names which follow
+/// none of the conventions, such as `.notdef` or `glyph123`.
+pub fn glyph_name_to_codepoints(name: &str) -> Vec<u32> {
+    // Everything from the first period is a suffix naming a variant, such as the `.sc` of `a.sc`.
+    let base = name.split('.').next().unwrap_or("");
+    base.split('_').flat_map(component_codepoints).collect()
+}
+
+fn component_codepoints(component: &str) -> Vec<u32> {
+    if let Ok(idx) = GLYPH_LIST.binary_search_by_key(&component, |&(name, _)| name) {
+        return vec![GLYPH_LIST[idx].1];
+    }
+
+    if let Some(digits) = component.strip_prefix("uni") {
+        return uni_codepoints(digits).unwrap_or_default();
+    }
+    if let Some(digits) = component.strip_prefix('u') {
+        return u_codepoint(digits).map_or(vec![], |codepoint| vec![codepoint]);
+    }
+    vec![]
+}
+
+/// Read the digits after `uni`, which are groups of four giving characters in the Basic
+/// Multilingual Plane.
+fn uni_codepoints(digits: &str) -> Option<Vec<u32>> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut codepoints = vec![];
+    for group in digits.as_bytes().chunks(4) {
+        if group.len() != 4 {
+            return None;
+        }
+        let codepoint = parse_hex(str::from_utf8(group).ok()?)?;
+        if is_surrogate(codepoint) {
+            return None;
+        }
+        codepoints.push(codepoint);
+    }
+    Some(codepoints)
+}
+
+/// Read the four to six digits after `u`, which give a single character from any plane.
+fn u_codepoint(digits: &str) -> Option<u32> {
+    if digits.len() < 4 || digits.len() > 6 {
+        return None;
+    }
+
+    let codepoint = parse_hex(digits)?;
+    if is_surrogate(codepoint) || codepoint > 0x10FFFF {
+        return None;
+    }
+    Some(codepoint)
+}
+
+/// Parse uppercase hexadecimal digits. Lowercase digits aren't allowed in glyph names.
+fn parse_hex(digits: &str) -> Option<u32> {
+    let is_upper_hex = |c: char| c.is_ascii_digit() || ('A'..='F').contains(&c);
+    if !digits.chars().all(is_upper_hex) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+fn is_surrogate(codepoint: u32) -> bool {
+    (0xD800..=0xDFFF).contains(&codepoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_list_is_sorted() {
+        assert!(GLYPH_LIST.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn list_names() {
+        assert_eq!(glyph_name_to_codepoints("A"), vec![0x41]);
+        assert_eq!(glyph_name_to_codepoints("Aacute"), vec![0xC1]);
+        assert_eq!(glyph_name_to_codepoints("quotedblleft"), vec![0x201C]);
+        assert_eq!(glyph_name_to_codepoints("Euro"), vec![0x20AC]);
+        // Legacy names for other scripts.
+        assert_eq!(glyph_name_to_codepoints("afii10017"), vec![0x410]);
+        assert_eq!(glyph_name_to_codepoints("alefarabic"), vec![0x627]);
+        assert_eq!(glyph_name_to_codepoints("Aacutesmall"), vec![0xF7E1]);
+        // Names the list gives two characters use the one from the list for new fonts.
+        assert_eq!(glyph_name_to_codepoints("Omega"), vec![0x3A9]);
+    }
+
+    #[test]
+    fn uni_names() {
+        assert_eq!(glyph_name_to_codepoints("uni20AC"), vec![0x20AC]);
+        assert_eq!(
+            glyph_name_to_codepoints("uni004100420043"),
+            vec![0x41, 0x42, 0x43]
+        );
+        // Lowercase digits, surrogates and incomplete groups aren't allowed.
+        assert!(glyph_name_to_codepoints("uni20ac").is_empty());
+        assert!(glyph_name_to_codepoints("uniD800").is_empty());
+        assert!(glyph_name_to_codepoints("uni20A").is_empty());
+    }
+
+    #[test]
+    fn u_names() {
+        assert_eq!(glyph_name_to_codepoints("u1F600"), vec![0x1F600]);
+        assert_eq!(glyph_name_to_codepoints("u0041"), vec![0x41]);
+        assert!(glyph_name_to_codepoints("u110000").is_empty());
+        assert!(glyph_name_to_codepoints("u41").is_empty());
+    }
+
+    #[test]
+    fn ligatures_and_suffixes() {
+        assert_eq!(glyph_name_to_codepoints("f_f_i"), vec![0x66, 0x66, 0x69]);
+        assert_eq!(glyph_name_to_codepoints("f_uni0069.alt"), vec![0x66, 0x69]);
+        assert_eq!(glyph_name_to_codepoints("a.sc"), vec![0x61]);
+        // Components which mean nothing are dropped, leaving the rest.
+        assert_eq!(glyph_name_to_codepoints("foo_a"), vec![0x61]);
+    }
+
+    #[test]
+    fn unmapped_names() {
+        assert!(glyph_name_to_codepoints(".notdef").is_empty());
+        assert!(glyph_name_to_codepoints("glyph123").is_empty());
+        assert!(glyph_name_to_codepoints("").is_empty());
+    }
+}
@@ -12,6 +12,26 @@ impl AppleRoman {
     pub fn to_bytes(&self) -> &[u8] {
         &self.bytes[..]
     }
+
+    /// The Unicode codepoint which a Mac OS Roman byte represents.
+    pub fn decode_byte(byte: u8) -> u32 {
+        let mapped = map_to_ucs2(byte);
+        u32::from(mapped[0]) << 8 | u32::from(mapped[1])
+    }
+
+    /// Find the Mac OS Roman byte which represents a Unicode codepoint, if there is one.
+    pub fn encode_codepoint(codepoint: u32) -> Option<u8> {
+        map_from_unicode(codepoint)
+    }
+}
+
+/// The inverse of `map_to_ucs2`.
+pub fn map_from_unicode(codepoint: u32) -> Option<u8> {
+    if codepoint < 0x80 {
+        return Some(codepoint as u8);
+    }
+
+    (0x80..=0xFF).find(|&byte| AppleRoman::decode_byte(byte) == codepoint)
 }
 
 pub fn map_to_ucs2(input: u8) -> [u8; 2] {
@@ -153,3 +173,29 @@ pub fn map_to_ucs2(input: u8) -> [u8; 2] {
         _ => panic!("Unknown byte {:?}", input),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_ascii() {
+        assert_eq!(AppleRoman::encode_codepoint('A' as u32), Some(0x41));
+        assert_eq!(AppleRoman::encode_codepoint(0x7F), Some(0x7F));
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        for byte in 0x80..=0xFF {
+            let codepoint = AppleRoman::decode_byte(byte);
+            assert_eq!(AppleRoman::encode_codepoint(codepoint), Some(byte));
+        }
+    }
+
+    #[test]
+    fn encode_unmapped() {
+        assert_eq!(AppleRoman::encode_codepoint(0x00A4), None);
+        assert_eq!(AppleRoman::encode_codepoint(0x0100), None);
+        assert_eq!(AppleRoman::encode_codepoint(0x1F600), None);
+    }
+}
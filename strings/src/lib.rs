@@ -1,6 +1,8 @@
+mod agl;
 mod apple;
 mod unicode;
 
+pub use agl::glyph_name_to_codepoints;
 pub use apple::roman::AppleRoman;
 pub use unicode::ucs2::Ucs2;
 pub use unicode::utf8::Utf8;